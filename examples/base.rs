@@ -8,14 +8,25 @@
 //!
 //! Set the `MKS_ENV_SERVO42C_UART` environment variable to your serial port path.
 
-use mks_servo42_rs::{Driver, RotationDirection};
+use mks_servo42_rs::{
+    defaults, microsteps_for_index, Degrees, Driver, Pulses, RotationDirection, Speed,
+};
 use serial::{SerialPort, SerialPortSettings};
 use std::env;
 use std::thread;
 use std::time::Duration;
 
-/// Microstepping configuration (index 4 = 4 microsteps per step)
-const MICROSTEPS: u8 = 4;
+/// Microstepping configuration (the byte is the microstep count)
+const SUBDIVISION_INDEX: u8 = 4;
+
+/// Microsteps per full step selected by `SUBDIVISION_INDEX`
+const MICROSTEPS: u16 = microsteps_for_index(SUBDIVISION_INDEX);
+
+/// Slowest non-zero speed gear
+const SPEED: Speed = match Speed::new(1) {
+    Ok(speed) => speed,
+    Err(_) => panic!("invalid speed"),
+};
 
 fn main() {
     dotenvy::dotenv().ok();
@@ -29,7 +40,9 @@ fn main() {
     // Open and configure serial port
     let mut port = serial::open(&port_path).expect("Failed to open serial port");
     port.reconfigure(&|settings: &mut dyn SerialPortSettings| {
-        settings.set_baud_rate(serial::Baud38400)?;
+        settings.set_baud_rate(serial::BaudRate::from_speed(
+            defaults::BAUD_RATE.bps() as usize
+        ))?;
         settings.set_char_size(serial::Bits8);
         settings.set_parity(serial::ParityNone);
         settings.set_stop_bits(serial::Stop1);
@@ -39,57 +52,57 @@ fn main() {
     .expect("Failed to configure serial port");
     port.set_timeout(Duration::from_millis(100)).unwrap();
 
-    let mut driver = Driver::default();
+    let driver = Driver::default();
 
     // === Setup ===
     println!("\n=== Setup ===");
 
-    send(&mut port, driver.set_subdivision(MICROSTEPS).unwrap());
-    send(&mut port, driver.enable_motor(true));
+    send(&mut port, &driver.set_subdivision(SUBDIVISION_INDEX));
+    send(&mut port, &driver.enable_motor(true));
 
     // Go to zero position
-    send(&mut port, driver.set_zero_speed(1).unwrap());
-    send(&mut port, driver.go_to_zero());
+    send(&mut port, &driver.set_zero_speed(1).unwrap());
+    send(&mut port, &driver.go_to_zero());
     thread::sleep(Duration::from_secs(2));
 
     // === Move 360° clockwise ===
     println!("\n=== Move 360° Clockwise ===");
 
-    let start_angle = read_encoder(&mut port, &mut driver);
-    println!("Start: {:.1}°", start_angle);
+    let start_angle = read_encoder(&mut port, &driver);
+    println!("Start: {:.1}°", start_angle.0);
 
-    let pulses = mks_servo42_rs::angle_to_steps(360.0, MICROSTEPS as f32);
+    let pulses = Pulses::from_degrees(Degrees(360.0), MICROSTEPS);
     send(
         &mut port,
-        driver
-            .run_motor(RotationDirection::Clockwise, 1, pulses)
+        &driver
+            .run_motor(RotationDirection::Clockwise, SPEED, pulses)
             .unwrap(),
     );
     thread::sleep(Duration::from_secs(3));
 
-    let end_angle = read_encoder(&mut port, &mut driver);
-    println!("End: {:.1}°", end_angle);
-    println!("Moved: {:.1}°", (end_angle - start_angle).abs());
+    let end_angle = read_encoder(&mut port, &driver);
+    println!("End: {:.1}°", end_angle.0);
+    println!("Moved: {:.1}°", (end_angle - start_angle).abs().0);
 
     // === Move 360° counter-clockwise ===
     println!("\n=== Move 360° Counter-Clockwise ===");
 
-    let start_angle = read_encoder(&mut port, &mut driver);
+    let start_angle = read_encoder(&mut port, &driver);
     send(
         &mut port,
-        driver
-            .run_motor(RotationDirection::CounterClockwise, 1, pulses)
+        &driver
+            .run_motor(RotationDirection::CounterClockwise, SPEED, pulses)
             .unwrap(),
     );
     thread::sleep(Duration::from_secs(3));
 
-    let end_angle = read_encoder(&mut port, &mut driver);
-    println!("Moved: {:.1}°", (end_angle - start_angle).abs());
+    let end_angle = read_encoder(&mut port, &driver);
+    println!("Moved: {:.1}°", (end_angle - start_angle).abs().0);
 
     // === Cleanup ===
     println!("\n=== Done ===");
-    send(&mut port, driver.stop());
-    send(&mut port, driver.enable_motor(false));
+    send(&mut port, &driver.stop());
+    send(&mut port, &driver.enable_motor(false));
 }
 
 /// Send command and read response
@@ -109,8 +122,8 @@ fn send<S: SerialPort + std::io::Read + std::io::Write>(port: &mut S, cmd: &[u8]
 }
 
 /// Read encoder and return angle in degrees
-fn read_encoder(port: &mut impl SerialPort, driver: &mut Driver) -> f32 {
-    let response = send(port, driver.read_encoder_value());
+fn read_encoder(port: &mut impl SerialPort, driver: &Driver) -> Degrees {
+    let response = send(port, &driver.read_encoder_value());
     mks_servo42_rs::parse_encoder_response(&response)
         .expect("Failed to parse encoder")
         .to_degrees()
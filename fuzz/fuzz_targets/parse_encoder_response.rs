@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mks_servo42_rs::parse_encoder_response;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_encoder_response(data);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mks_servo42_rs::{parse_motor_shaft_angle_error, parse_motor_shaft_angle_response};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_motor_shaft_angle_response(data);
+    let _ = parse_motor_shaft_angle_error(data);
+});
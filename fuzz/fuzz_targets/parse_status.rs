@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mks_servo42_rs::helpers::{
+    parse_en_pin_status_response, parse_pulse_count_response, parse_shaft_status_response,
+    parse_success_response, strip_leading_garbage,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_en_pin_status_response(data);
+    let _ = parse_shaft_status_response(data);
+    let _ = parse_success_response(data);
+    let _ = parse_pulse_count_response(data);
+    let _ = strip_leading_garbage(data);
+});
@@ -0,0 +1,30 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mks_servo42_rs::servo42d::{self, can};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = servo42d::parse_motor_status(data);
+    let _ = servo42d::parse_absolute_pulses_status(data);
+    let _ = servo42d::parse_speed(data);
+    let _ = servo42d::parse_io_ports(data);
+    let _ = servo42d::parse_home_status(data);
+    let _ = servo42d::parse_work_mode(data);
+    let _ = servo42d::parse_working_current_ma(data);
+    let _ = servo42d::parse_subdivision(data);
+    let _ = servo42d::parse_hold_current(data);
+    if let Some((&code, rest)) = data.split_first() {
+        let _ = servo42d::parse_response(rest, code);
+        let _ = servo42d::parse_reply(rest, code, usize::from(code % 8));
+    }
+
+    // CAN replies: first two bytes are the identifier, the rest the payload.
+    if let [high, low, payload @ ..] = data {
+        let mut out = [0u8; 16];
+        if let Ok(len) = can::reply_to_serial(u16::from_be_bytes([*high, *low]), payload, &mut out)
+        {
+            let _ = servo42d::parse_reply(&out[..len], out[2], len - 4);
+        }
+        let _ = can::CanFrame::from_serial(u16::from_be_bytes([*high, *low]), payload);
+    }
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mks_servo42_rs::telemetry::Channel;
+
+const CHANNELS: [Channel; 7] = [
+    Channel::Encoder,
+    Channel::PulseCount,
+    Channel::MotorShaftAngle,
+    Channel::AngleError,
+    Channel::EnPinStatus,
+    Channel::ReleaseStatus,
+    Channel::ShaftStatus,
+];
+
+fuzz_target!(|data: &[u8]| {
+    for channel in CHANNELS {
+        let _ = channel.parse(data);
+    }
+});
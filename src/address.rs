@@ -0,0 +1,72 @@
+use crate::{Error, DEFAULT_ADDRESS, MAX_ADDRESS, MIN_ADDRESS};
+
+/// Slave address of an MKS SERVO42 board on the serial bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address(u8);
+
+impl Address {
+    /// Factory default address (0xE0).
+    pub const DEFAULT: Self = Self(DEFAULT_ADDRESS);
+
+    /// Creates an address within `MIN_ADDRESS..=MAX_ADDRESS`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the address is outside the supported range.
+    pub const fn new(address: u8) -> Result<Self, Error> {
+        if address < MIN_ADDRESS || address > MAX_ADDRESS {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self(address))
+    }
+
+    /// Wraps a raw address byte without range checks.
+    pub(crate) const fn from_raw(address: u8) -> Self {
+        Self(address)
+    }
+
+    /// Returns the raw address byte sent on the wire.
+    #[must_use]
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for Address {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl TryFrom<u8> for Address {
+    type Error = Error;
+
+    fn try_from(address: u8) -> Result<Self, Self::Error> {
+        Self::new(address)
+    }
+}
+
+impl From<Address> for u8 {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_new() {
+        assert_eq!(Address::new(MIN_ADDRESS).map(Address::get), Ok(MIN_ADDRESS));
+        assert_eq!(Address::new(MAX_ADDRESS).map(Address::get), Ok(MAX_ADDRESS));
+        assert_eq!(Address::new(MIN_ADDRESS - 1), Err(Error::InvalidValue));
+        assert_eq!(Address::new(MAX_ADDRESS + 1), Err(Error::InvalidValue));
+        assert_eq!(Address::try_from(0xE3).map(u8::from), Ok(0xE3));
+    }
+
+    #[test]
+    fn test_address_default() {
+        assert_eq!(Address::default(), Address::DEFAULT);
+        assert_eq!(Address::DEFAULT.get(), DEFAULT_ADDRESS);
+    }
+}
@@ -0,0 +1,301 @@
+//! Decoding of raw SERVO42C bus captures.
+//!
+//! [`analyze`] splits a byte capture into command frames, the replies that
+//! follow them and unrecognized bytes, with a checksum verdict for each frame.
+//! Command lengths follow from their function code and reply lengths from the
+//! command they answer, so a capture of both directions in order decodes
+//! without timing information:
+//!
+//! ```
+//! use mks_servo42_rs::analyzer::{analyze, command_name, SegmentKind};
+//!
+//! let capture = [0xE0, 0xF7, 0xD7, 0xE0, 0x01, 0xE1, 0x55];
+//! let mut timeline = analyze(&capture);
+//! let stop = timeline.next().unwrap();
+//! assert_eq!(stop.kind, SegmentKind::Command { code: 0xF7, checksum_ok: true });
+//! assert_eq!(command_name(0xF7), Some("stop"));
+//! let ack = timeline.next().unwrap();
+//! assert_eq!(ack.kind, SegmentKind::Reply { code: 0xF7, checksum_ok: true });
+//! assert_eq!(timeline.next().unwrap().kind, SegmentKind::Unknown);
+//! assert!(timeline.next().is_none());
+//! ```
+
+use crate::{calculate_checksum, cmd, MAX_ADDRESS, MIN_ADDRESS};
+
+/// What a [`Segment`] of a capture was decoded as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SegmentKind {
+    /// A command frame with function `code`.
+    Command {
+        /// Function code.
+        code: u8,
+        /// Whether the frame's checksum matches.
+        checksum_ok: bool,
+    },
+    /// The reply to the preceding command with function `code`.
+    Reply {
+        /// Function code of the answered command.
+        code: u8,
+        /// Whether the frame's checksum matches.
+        checksum_ok: bool,
+    },
+    /// Bytes that belong to no recognizable frame.
+    Unknown,
+}
+
+/// A run of bytes in a capture and what it was decoded as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment<'a> {
+    /// Position of the first byte in the capture.
+    pub offset: usize,
+    /// The segment's bytes.
+    pub bytes: &'a [u8],
+    /// The decoded meaning.
+    pub kind: SegmentKind,
+}
+
+/// Iterator over the [`Segment`]s of a capture; see [`analyze`].
+#[derive(Debug, Clone)]
+pub struct Analyzer<'a> {
+    capture: &'a [u8],
+    pos: usize,
+    pending: Option<u8>,
+}
+
+/// Decodes `capture` into a timeline of commands, replies and unknown bytes.
+#[must_use]
+pub const fn analyze(capture: &[u8]) -> Analyzer<'_> {
+    Analyzer {
+        capture,
+        pos: 0,
+        pending: None,
+    }
+}
+
+/// Returns the [`Driver`](crate::Driver) method building commands with function `code`.
+#[must_use]
+pub const fn command_name(code: u8) -> Option<&'static str> {
+    Some(match code {
+        cmd::READ_ENCODER_VALUE => "read_encoder_value",
+        cmd::READ_PULSE_COUNT => "read_pulse_count",
+        cmd::READ_MOTOR_SHAFT_ANGLE => "read_motor_shaft_angle",
+        cmd::READ_MOTOR_SHAFT_ANGLE_ERROR => "read_motor_shaft_angle_error",
+        cmd::READ_EN_PIN_STATUS => "read_en_pin_status",
+        cmd::READ_RELEASE_STATUS => "read_release_status",
+        cmd::READ_SHAFT_STATUS => "read_shaft_status",
+        cmd::SAVE_CLEAR_STATUS => "save_clear_status",
+        cmd::CALIBRATE_ENCODER => "calibrate_encoder",
+        cmd::SET_CURRENT_LIMIT => "set_current_limit",
+        cmd::SET_SUBDIVISION => "set_subdivision",
+        cmd::SET_EN_LOGIC => "set_enable_logic",
+        cmd::SET_DIRECTION => "set_direction",
+        cmd::SET_AUTO_SCREEN_OFF => "set_auto_screen_off",
+        cmd::SET_PROTECTION => "set_stall_protection",
+        cmd::SET_INTERPOLATION => "set_interpolation",
+        cmd::SET_ZERO_MODE => "set_zero_mode",
+        cmd::SET_CURRENT_AS_ZERO => "set_current_as_zero",
+        cmd::SET_ZERO_SPEED => "set_zero_speed",
+        cmd::SET_ZERO_DIRECTION => "set_zero_direction",
+        cmd::GO_TO_ZERO => "go_to_zero",
+        cmd::SET_POSITION_KP => "set_position_kp",
+        cmd::SET_POSITION_KI => "set_position_ki",
+        cmd::SET_POSITION_KD => "set_position_kd",
+        cmd::SET_ACCELERATION => "set_acceleration",
+        cmd::SET_MAX_TORQUE => "set_max_torque",
+        cmd::ENABLE_MOTOR => "enable_motor",
+        cmd::RUN_WITH_CONSTANT_SPEED => "run_with_constant_speed",
+        cmd::STOP => "stop",
+        cmd::RUN_MOTOR => "run_motor",
+        _ => return None,
+    })
+}
+
+/// Length of the command frame with function `code`, checksum included.
+const fn command_len(code: u8) -> Option<usize> {
+    match code {
+        cmd::READ_ENCODER_VALUE
+        | cmd::READ_PULSE_COUNT
+        | cmd::READ_MOTOR_SHAFT_ANGLE
+        | cmd::READ_MOTOR_SHAFT_ANGLE_ERROR
+        | cmd::READ_EN_PIN_STATUS
+        | cmd::READ_RELEASE_STATUS
+        | cmd::READ_SHAFT_STATUS
+        | cmd::STOP => Some(3),
+        cmd::SET_POSITION_KP
+        | cmd::SET_POSITION_KI
+        | cmd::SET_POSITION_KD
+        | cmd::SET_ACCELERATION
+        | cmd::SET_MAX_TORQUE => Some(5),
+        cmd::RUN_MOTOR => Some(8),
+        _ if command_name(code).is_some() => Some(4),
+        _ => None,
+    }
+}
+
+/// Length of the reply to `code` and the index of its checksum byte.
+const fn reply_layout(code: u8) -> (usize, usize) {
+    match code {
+        cmd::READ_ENCODER_VALUE => (8, 7),
+        cmd::READ_PULSE_COUNT | cmd::READ_MOTOR_SHAFT_ANGLE => (6, 5),
+        // The firmware appends an undocumented 0x00 after the checksum.
+        cmd::READ_MOTOR_SHAFT_ANGLE_ERROR => (5, 3),
+        _ => (3, 2),
+    }
+}
+
+const fn is_address(byte: u8) -> bool {
+    byte >= MIN_ADDRESS && byte <= MAX_ADDRESS
+}
+
+/// Returns whether `frame[checksum]` is the sum of the bytes before it.
+fn checksum_ok(frame: &[u8], checksum: usize) -> bool {
+    calculate_checksum(&frame[..checksum]) == frame[checksum]
+}
+
+impl<'a> Analyzer<'a> {
+    fn reply_at(&self, rest: &'a [u8]) -> Option<(usize, SegmentKind)> {
+        let code = self.pending?;
+        let (len, checksum) = reply_layout(code);
+        let frame = rest.get(..len)?;
+        // Three-byte replies carry a status of 0, 1 or 2; anything else is the
+        // next command, e.g. a `stop` when the awaited reply never came.
+        let plausible = len != 3 || frame[1] <= 2;
+        (is_address(frame[0]) && plausible).then(|| {
+            let checksum_ok = checksum_ok(frame, checksum);
+            (len, SegmentKind::Reply { code, checksum_ok })
+        })
+    }
+
+    fn command_at(rest: &'a [u8]) -> Option<(usize, SegmentKind)> {
+        let (&address, tail) = rest.split_first()?;
+        let code = *tail.first()?;
+        let len = command_len(code)?;
+        let frame = rest.get(..len)?;
+        is_address(address).then(|| {
+            let checksum_ok = checksum_ok(frame, len - 1);
+            (len, SegmentKind::Command { code, checksum_ok })
+        })
+    }
+}
+
+impl<'a> Iterator for Analyzer<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self
+            .capture
+            .get(self.pos..)
+            .filter(|rest| !rest.is_empty())?;
+        let reply = self.reply_at(rest);
+        let command = Self::command_at(rest);
+        // A valid reply wins over a command; a command wins over a corrupt
+        // reply, since the awaited reply may never have come.
+        let valid_reply = matches!(
+            reply,
+            Some((
+                _,
+                SegmentKind::Reply {
+                    checksum_ok: true,
+                    ..
+                }
+            ))
+        );
+        let decoded = if valid_reply {
+            reply
+        } else {
+            command.or(reply)
+        };
+        let (len, kind) = decoded.unwrap_or_else(|| {
+            let len = rest[1..]
+                .iter()
+                .position(|&b| is_address(b))
+                .map_or(rest.len(), |idx| idx + 1);
+            (len, SegmentKind::Unknown)
+        });
+        self.pending = match kind {
+            SegmentKind::Command { code, .. } => Some(code),
+            _ => None,
+        };
+        let segment = Segment {
+            offset: self.pos,
+            bytes: &rest[..len],
+            kind,
+        };
+        self.pos += len;
+        Some(segment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_vectors::*;
+
+    #[test]
+    fn test_analyze_timeline() {
+        let mut capture = [0u8; 64];
+        let mut len = 0;
+        for part in [
+            READ_ENCODER_VALUE,
+            ENCODER_90_DEG,
+            &[0x00, 0x13][..],
+            SET_POSITION_KP_0X120,
+            SUCCESS,
+            READ_MOTOR_SHAFT_ANGLE_ERROR,
+            ANGLE_ERROR_1_DEG,
+            READ_PULSE_COUNT,
+            &[0xE0, 0x00, 0x00, 0x01, 0x00, 0xE2][..],
+            STOP,
+        ] {
+            capture[len..len + part.len()].copy_from_slice(part);
+            len += part.len();
+        }
+
+        let command = |code| SegmentKind::Command {
+            code,
+            checksum_ok: true,
+        };
+        let reply = |code, checksum_ok| SegmentKind::Reply { code, checksum_ok };
+        let mut timeline = analyze(&capture[..len]);
+        let mut kinds = [SegmentKind::Unknown; 10];
+        for kind in &mut kinds {
+            *kind = timeline.next().unwrap().kind;
+        }
+        assert!(timeline.next().is_none());
+        assert_eq!(
+            kinds,
+            [
+                command(0x30),
+                reply(0x30, true),
+                SegmentKind::Unknown,
+                command(0xA1),
+                reply(0xA1, true),
+                command(0x39),
+                reply(0x39, true),
+                command(0x33),
+                reply(0x33, false),
+                command(0xF7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_reply() {
+        let capture = [0xE0, 0x3A, 0x1A, 0xE0, 0xF7, 0xD7];
+        let mut timeline = analyze(&capture);
+        assert_eq!(timeline.next().unwrap().bytes, READ_EN_PIN_STATUS);
+        let stop = timeline.next().unwrap();
+        assert_eq!((stop.offset, stop.bytes), (3, STOP));
+        assert!(matches!(stop.kind, SegmentKind::Command { code: 0xF7, .. }));
+    }
+
+    #[test]
+    fn test_command_lengths_cover_names() {
+        for code in 0..=u8::MAX {
+            assert_eq!(command_len(code).is_some(), command_name(code).is_some());
+        }
+        assert_eq!(command_len(0xA4), Some(SET_ACCELERATION_0X80.len()));
+        assert_eq!(command_len(0xFD), Some(RUN_MOTOR_CW_360_DEG.len()));
+        assert_eq!(command_len(0x83), Some(SET_CURRENT_LIMIT_1200MA.len()));
+    }
+}
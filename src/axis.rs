@@ -0,0 +1,163 @@
+//! Mapping between user coordinates and motor angles.
+//!
+//! An [`AxisTransform`] holds the scale, offset and direction of one joint or
+//! axis, so callers work in their own units (joint degrees, millimetres of
+//! travel, turret bearing) and the transform does the sign and offset
+//! bookkeeping:
+//!
+//! ```
+//! use mks_servo42_rs::axis::AxisTransform;
+//! use mks_servo42_rs::{Command, Degrees, RotationDirection, Speed};
+//!
+//! // A joint behind a 3:1 reduction, mounted mirrored, whose zero is
+//! // 45° of motor rotation away from the encoder zero.
+//! let joint = AxisTransform::new(3.0)?.offset(Degrees(45.0)).inverted(true);
+//! assert_eq!(joint.to_motor(10.0), Degrees(15.0));
+//! assert_eq!(joint.to_user(Degrees(15.0)), 10.0);
+//!
+//! let command = joint.move_between(0.0, 30.0, 16, Speed::new(5)?);
+//! assert!(matches!(
+//!     command,
+//!     Some(Command::RunMotor { direction: RotationDirection::CounterClockwise, pulses, .. })
+//!         if pulses.get() == 800
+//! ));
+//! # Ok::<(), mks_servo42_rs::Error>(())
+//! ```
+
+use crate::{Command, Degrees, Error, Pulses, Result, RotationDirection, Speed};
+
+/// Scale, offset and direction of one axis.
+///
+/// `motor = offset ± scale × user`, with the minus sign when inverted.
+/// Positive motor angles turn clockwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisTransform {
+    scale: f32,
+    offset: Degrees,
+    inverted: bool,
+}
+
+impl AxisTransform {
+    /// User units are motor degrees.
+    pub const IDENTITY: Self = Self {
+        scale: 1.0,
+        offset: Degrees(0.0),
+        inverted: false,
+    };
+
+    /// Creates a transform with `scale` motor degrees per user unit.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `scale` is not finite and positive.
+    pub fn new(scale: f32) -> Result<Self> {
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            scale,
+            ..Self::IDENTITY
+        })
+    }
+
+    /// Sets the motor angle at user coordinate zero.
+    #[must_use]
+    pub const fn offset(mut self, offset: Degrees) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets whether the user axis runs against the motor.
+    #[must_use]
+    pub const fn inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    /// Returns the motor angle for user coordinate `user`.
+    #[must_use]
+    pub fn to_motor(&self, user: f32) -> Degrees {
+        Degrees(self.offset.0 + self.signed_scale() * user)
+    }
+
+    /// Returns the user coordinate for motor angle `motor`.
+    #[must_use]
+    pub fn to_user(&self, motor: Degrees) -> f32 {
+        (motor.0 - self.offset.0) / self.signed_scale()
+    }
+
+    /// Returns the relative move from user coordinate `from` to `to`, or
+    /// `None` if it is shorter than one pulse at `microsteps`.
+    #[must_use]
+    pub fn move_between(
+        &self,
+        from: f32,
+        to: f32,
+        microsteps: u16,
+        speed: Speed,
+    ) -> Option<Command> {
+        let delta = self.to_motor(to) - self.to_motor(from);
+        let pulses = Pulses::from_degrees(delta, microsteps);
+        if pulses.get() == 0 {
+            return None;
+        }
+        let direction = if delta.0 < 0.0 {
+            RotationDirection::CounterClockwise
+        } else {
+            RotationDirection::Clockwise
+        };
+        Some(Command::RunMotor {
+            direction,
+            speed,
+            pulses,
+        })
+    }
+
+    fn signed_scale(&self) -> f32 {
+        if self.inverted {
+            -self.scale
+        } else {
+            self.scale
+        }
+    }
+}
+
+impl Default for AxisTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_axis_transform() {
+        // A leadscrew with 8 mm of travel per turn: 45 motor degrees per mm.
+        let axis = AxisTransform::new(45.0).unwrap().offset(Degrees(-90.0));
+        assert_eq!(axis.to_motor(0.0), Degrees(-90.0));
+        assert_eq!(axis.to_motor(4.0), Degrees(90.0));
+        assert_eq!(axis.to_user(Degrees(270.0)), 8.0);
+        assert_eq!(
+            axis.move_between(4.0, 2.0, 1, Speed::new(2).unwrap()),
+            Some(Command::RunMotor {
+                direction: RotationDirection::CounterClockwise,
+                speed: Speed::new(2).unwrap(),
+                pulses: Pulses::new(50),
+            })
+        );
+        assert_eq!(
+            axis.move_between(1.0, 1.001, 1, Speed::new(2).unwrap()),
+            None
+        );
+
+        let mirrored = axis.inverted(true);
+        assert_eq!(mirrored.to_motor(4.0), Degrees(-270.0));
+        assert_eq!(mirrored.to_user(Degrees(-270.0)), 4.0);
+
+        assert_eq!(AxisTransform::default().to_motor(12.5), Degrees(12.5));
+        assert_eq!(AxisTransform::new(0.0), Err(Error::InvalidValue));
+        assert_eq!(AxisTransform::new(-1.0), Err(Error::InvalidValue));
+        assert_eq!(AxisTransform::new(f32::NAN), Err(Error::InvalidValue));
+    }
+}
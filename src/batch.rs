@@ -0,0 +1,184 @@
+use crate::{Command, Driver, Error};
+
+/// Encodes several frames back to back into one caller-provided buffer.
+///
+/// The batch remembers where each frame ends, so replies can be matched to
+/// their commands after the whole buffer went out in a single write or DMA
+/// transfer. `M` is the maximum number of frames.
+///
+/// ```
+/// use mks_servo42_rs::{Command, CommandBatch, Driver};
+///
+/// let driver = Driver::default();
+/// let mut buf = [0u8; 32];
+/// let mut batch: CommandBatch = CommandBatch::new(&mut buf);
+/// batch.push_command(&driver, &Command::EnableMotor(true))?;
+/// batch.push(driver.set_zero_speed(1)?)?;
+///
+/// assert_eq!(batch.frame_count(), 2);
+/// assert_eq!(batch.as_slice(), &[0xE0, 0xF3, 0x01, 0xD4, 0xE0, 0x92, 0x01, 0x73]);
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct CommandBatch<'a, const M: usize = 16> {
+    buf: &'a mut [u8],
+    len: usize,
+    ends: [usize; M],
+    count: usize,
+}
+
+impl<'a, const M: usize> CommandBatch<'a, M> {
+    /// Creates an empty batch writing into `buf`.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            buf,
+            len: 0,
+            ends: [0; M],
+            count: 0,
+        }
+    }
+
+    /// Appends an already encoded frame.
+    ///
+    /// # Errors
+    /// Returns `Error::BufferTooSmall` if the frame or its boundary does not fit.
+    pub fn push(&mut self, frame: impl AsRef<[u8]>) -> Result<(), Error> {
+        let frame = frame.as_ref();
+        if self.count == M {
+            return Err(Error::BufferTooSmall);
+        }
+        let end = self.len + frame.len();
+        let dst = self
+            .buf
+            .get_mut(self.len..end)
+            .ok_or(Error::BufferTooSmall)?;
+        dst.copy_from_slice(frame);
+        self.commit(end);
+        Ok(())
+    }
+
+    /// Encodes a typed [`Command`] for `driver` directly into the batch buffer.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the command carries an out-of-range index,
+    /// or `Error::BufferTooSmall` if the frame or its boundary does not fit.
+    pub fn push_command<const N: usize>(
+        &mut self,
+        driver: &Driver<N>,
+        command: &Command,
+    ) -> Result<(), Error> {
+        if self.count == M {
+            return Err(Error::BufferTooSmall);
+        }
+        let written = driver.encode_into(command, &mut self.buf[self.len..])?;
+        self.commit(self.len + written);
+        Ok(())
+    }
+
+    /// Returns the encoded bytes of all frames.
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Returns the number of encoded bytes.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no frame was pushed yet.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the number of frames in the batch.
+    #[must_use]
+    pub const fn frame_count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the bytes of the frame at `index`.
+    #[must_use]
+    pub fn frame(&self, index: usize) -> Option<&[u8]> {
+        if index >= self.count {
+            return None;
+        }
+        let start = if index == 0 { 0 } else { self.ends[index - 1] };
+        Some(&self.buf[start..self.ends[index]])
+    }
+
+    /// Iterates over the frames in push order.
+    pub fn frames(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.count).filter_map(|index| self.frame(index))
+    }
+
+    /// Drops all frames, keeping the buffer.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.count = 0;
+    }
+
+    fn commit(&mut self, end: usize) {
+        self.ends[self.count] = end;
+        self.count += 1;
+        self.len = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frames;
+    use crate::Address;
+
+    #[test]
+    fn test_batch_boundaries() {
+        let driver = Driver::default();
+        let mut buf = [0u8; 16];
+        let mut batch: CommandBatch<4> = CommandBatch::new(&mut buf);
+        assert!(batch.is_empty());
+
+        batch.push(frames::stop(Address::DEFAULT)).unwrap();
+        batch
+            .push_command(&driver, &Command::ReadEncoderValue)
+            .unwrap();
+        batch.push(driver.calibrate_encoder()).unwrap();
+
+        assert_eq!(batch.frame_count(), 3);
+        assert_eq!(batch.len(), 10);
+        assert_eq!(batch.frame(0), Some(&[0xE0, 0xF7, 0xD7][..]));
+        assert_eq!(batch.frame(1), Some(&[0xE0, 0x30, 0x10][..]));
+        assert_eq!(batch.frame(2), Some(&[0xE0, 0x80, 0x00, 0x60][..]));
+        assert_eq!(batch.frame(3), None);
+        assert_eq!(batch.frames().map(<[u8]>::len).sum::<usize>(), batch.len());
+
+        batch.clear();
+        assert!(batch.is_empty());
+        assert_eq!(batch.as_slice(), &[]);
+    }
+
+    #[test]
+    fn test_batch_overflow_keeps_contents() {
+        let driver = Driver::default();
+        let mut buf = [0u8; 5];
+        let mut batch: CommandBatch<2> = CommandBatch::new(&mut buf);
+        batch.push_command(&driver, &Command::Stop).unwrap();
+        assert_eq!(
+            batch.push_command(&driver, &Command::CalibrateEncoder),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(
+            batch.push_command(&driver, &Command::SetZeroSpeed(0xFF)),
+            Err(Error::InvalidValue)
+        );
+        assert_eq!(batch.frame_count(), 1);
+        assert_eq!(batch.as_slice(), &[0xE0, 0xF7, 0xD7]);
+
+        let mut buf = [0u8; 16];
+        let mut batch: CommandBatch<1> = CommandBatch::new(&mut buf);
+        batch.push(driver.stop()).unwrap();
+        assert_eq!(batch.push(driver.stop()), Err(Error::BufferTooSmall));
+    }
+}
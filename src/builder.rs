@@ -0,0 +1,136 @@
+use crate::{Address, DeviceProfile, Driver, Error, ProtocolVariant};
+
+/// Fluent builder for [`Driver`].
+///
+/// ```
+/// use mks_servo42_rs::{Driver, ProtocolVariant};
+///
+/// let driver = Driver::builder()
+///     .address(0xE3)
+///     .protocol(ProtocolVariant::Servo42C)
+///     .build()?;
+/// assert_eq!(driver.address().get(), 0xE3);
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DriverBuilder {
+    address: Option<u8>,
+    protocol: ProtocolVariant,
+    profile: Option<DeviceProfile>,
+}
+
+impl DriverBuilder {
+    /// Creates a builder with default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the slave address (defaults to `DEFAULT_ADDRESS`).
+    #[must_use]
+    pub const fn address(mut self, address: u8) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Sets the protocol variant (defaults to `ProtocolVariant::Servo42C`).
+    #[must_use]
+    pub const fn protocol(mut self, protocol: ProtocolVariant) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Sets the board family (defaults to `DeviceProfile::SERVO57C` for
+    /// `ProtocolVariant::Servo57`, `DeviceProfile::SERVO42D` for
+    /// `ProtocolVariant::Servo42D` and `DeviceProfile::SERVO42C` otherwise).
+    #[must_use]
+    pub const fn profile(mut self, profile: DeviceProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Validates the options and builds the driver.
+    ///
+    /// SERVO42D boards accept any address byte and default to
+    /// `servo42d::DEFAULT_ADDRESS`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the address of a C-protocol board is
+    /// outside `MIN_ADDRESS..=MAX_ADDRESS`.
+    pub fn build(self) -> Result<Driver, Error> {
+        let address = match (self.protocol, self.address) {
+            #[cfg(feature = "servo42d")]
+            (ProtocolVariant::Servo42D, address) => {
+                Address::from_raw(address.unwrap_or(crate::servo42d::DEFAULT_ADDRESS))
+            }
+            (_, Some(address)) => Address::new(address)?,
+            (_, None) => Address::DEFAULT,
+        };
+        let profile = self.profile.unwrap_or(match self.protocol {
+            ProtocolVariant::Servo57 => DeviceProfile::SERVO57C,
+            #[cfg(feature = "servo42d")]
+            ProtocolVariant::Servo42D => DeviceProfile::SERVO42D,
+            _ => DeviceProfile::SERVO42C,
+        });
+        Ok(Driver {
+            address,
+            protocol: self.protocol,
+            profile,
+            microsteps: None,
+            motor_type: crate::MotorType::Deg18,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults() {
+        let driver = DriverBuilder::new().build().unwrap();
+        assert_eq!(driver.address(), Address::DEFAULT);
+        assert_eq!(driver.protocol(), ProtocolVariant::Servo42C);
+        assert_eq!(driver.profile(), &DeviceProfile::SERVO42C);
+    }
+
+    #[test]
+    fn test_builder_servo57() {
+        let driver = Driver::builder()
+            .protocol(ProtocolVariant::Servo57)
+            .build()
+            .unwrap();
+        assert_eq!(driver.profile(), &DeviceProfile::SERVO57C);
+        assert_eq!(driver.address(), Address::DEFAULT);
+    }
+
+    #[cfg(feature = "servo42d")]
+    #[test]
+    fn test_builder_servo42d_address() {
+        let builder = Driver::builder().protocol(ProtocolVariant::Servo42D);
+        assert_eq!(builder.build().unwrap().address().get(), 0x01);
+        assert_eq!(builder.build().unwrap().profile(), &DeviceProfile::SERVO42D);
+        assert_eq!(builder.address(0x20).build().unwrap().address().get(), 0x20);
+    }
+
+    #[test]
+    fn test_builder_profile() {
+        let driver = Driver::builder()
+            .profile(DeviceProfile::SERVO57C)
+            .build()
+            .unwrap();
+        assert_eq!(driver.profile().max_current_ma, 5200);
+    }
+
+    #[test]
+    fn test_builder_address() {
+        let driver = Driver::builder().address(0xE9).build().unwrap();
+        assert_eq!(driver.address().get(), 0xE9);
+    }
+
+    #[test]
+    fn test_builder_invalid_address() {
+        let result = Driver::builder().address(0xEA).build();
+        assert!(matches!(result, Err(Error::InvalidValue)));
+    }
+}
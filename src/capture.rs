@@ -0,0 +1,185 @@
+//! Compact timestamped frame log (feature `capture`).
+//!
+//! A capture is [`MAGIC`] followed by records of
+//! `[direction, timestamp_ms (u32 LE), len, bytes..]`: six bytes of overhead
+//! per frame. Transports append a record per frame sent or received, and the
+//! log can be archived, replayed or fed to [`analyzer`](crate::analyzer):
+//!
+//! ```
+//! use mks_servo42_rs::capture::{read, Direction, Record, MAGIC};
+//!
+//! let mut log = [0u8; 32];
+//! log[..4].copy_from_slice(&MAGIC);
+//! let mut len = 4;
+//! for record in [
+//!     Record { timestamp_ms: 0, direction: Direction::Tx, bytes: &[0xE0, 0xF7, 0xD7] },
+//!     Record { timestamp_ms: 3, direction: Direction::Rx, bytes: &[0xE0, 0x01, 0xE1] },
+//! ] {
+//!     len += record.write_to(&mut log[len..])?;
+//! }
+//!
+//! let mut records = read(&log[..len])?;
+//! assert_eq!(records.next(), Some(Ok(Record {
+//!     timestamp_ms: 0,
+//!     direction: Direction::Tx,
+//!     bytes: &[0xE0, 0xF7, 0xD7],
+//! })));
+//! assert_eq!(records.next().unwrap()?.timestamp_ms, 3);
+//! assert!(records.next().is_none());
+//! # Ok::<(), mks_servo42_rs::Error>(())
+//! ```
+
+use crate::{Error, Result};
+
+/// Bytes every capture starts with; the last one is the format version.
+pub const MAGIC: [u8; 4] = *b"MKS\x01";
+
+/// Bytes a record adds in front of the frame.
+pub const RECORD_HEADER_LEN: usize = 6;
+
+/// Which way a recorded frame travelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Direction {
+    /// Sent by the host.
+    Tx = 0x00,
+    /// Received from the bus.
+    Rx = 0x01,
+}
+
+/// One recorded frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Record<'a> {
+    /// Milliseconds since the start of the session.
+    pub timestamp_ms: u32,
+    /// Which way the frame travelled.
+    pub direction: Direction,
+    /// The frame bytes, at most 255.
+    pub bytes: &'a [u8],
+}
+
+impl Record<'_> {
+    /// Returns the encoded size of the record.
+    #[must_use]
+    pub const fn encoded_len(&self) -> usize {
+        RECORD_HEADER_LEN + self.bytes.len()
+    }
+
+    /// Encodes the record into the start of `out` and returns its length.
+    ///
+    /// `out` is left untouched on error.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the frame is longer than 255 bytes and
+    /// `Error::BufferTooSmall` if `out` cannot hold the record.
+    pub fn write_to(&self, out: &mut [u8]) -> Result<usize> {
+        let len = u8::try_from(self.bytes.len()).map_err(|_| Error::InvalidValue)?;
+        let out = out
+            .get_mut(..self.encoded_len())
+            .ok_or(Error::BufferTooSmall)?;
+        let (header, bytes) = out.split_at_mut(RECORD_HEADER_LEN);
+        header[0] = self.direction as u8;
+        header[1..5].copy_from_slice(&self.timestamp_ms.to_le_bytes());
+        header[5] = len;
+        bytes.copy_from_slice(self.bytes);
+        Ok(self.encoded_len())
+    }
+}
+
+/// Iterator over the records of a capture; see [`read`].
+///
+/// Yields `Err(Error::InvalidPacket)` once for a corrupt or truncated record,
+/// then stops.
+#[derive(Debug, Clone)]
+pub struct Records<'a> {
+    data: &'a [u8],
+}
+
+/// Checks the header of `capture` and returns its records.
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if `capture` does not start with [`MAGIC`].
+pub fn read(capture: &[u8]) -> Result<Records<'_>> {
+    let data = capture.strip_prefix(&MAGIC).ok_or(Error::InvalidPacket)?;
+    Ok(Records { data })
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<Record<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let record = parse_record(self.data);
+        self.data = match record {
+            Ok(record) => &self.data[record.encoded_len()..],
+            Err(_) => &[],
+        };
+        Some(record)
+    }
+}
+
+fn parse_record(data: &[u8]) -> Result<Record<'_>> {
+    let header = data.get(..RECORD_HEADER_LEN).ok_or(Error::InvalidPacket)?;
+    let direction = match header[0] {
+        0x00 => Direction::Tx,
+        0x01 => Direction::Rx,
+        _ => return Err(Error::InvalidPacket),
+    };
+    let timestamp_ms = u32::from_le_bytes([header[1], header[2], header[3], header[4]]);
+    let end = RECORD_HEADER_LEN + usize::from(header[5]);
+    let bytes = data
+        .get(RECORD_HEADER_LEN..end)
+        .ok_or(Error::InvalidPacket)?;
+    Ok(Record {
+        timestamp_ms,
+        direction,
+        bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_record() {
+        let record = Record {
+            timestamp_ms: 0x0102_0304,
+            direction: Direction::Rx,
+            bytes: &[0xE0, 0x01, 0xE1],
+        };
+        let mut out = [0xAA; 10];
+        assert_eq!(record.write_to(&mut out), Ok(9));
+        assert_eq!(
+            out,
+            [0x01, 0x04, 0x03, 0x02, 0x01, 0x03, 0xE0, 0x01, 0xE1, 0xAA]
+        );
+
+        let mut short = [0; 8];
+        assert_eq!(record.write_to(&mut short), Err(Error::BufferTooSmall));
+        assert_eq!(short, [0; 8]);
+
+        let long = [0; 256];
+        let record = Record {
+            bytes: &long,
+            ..record
+        };
+        assert_eq!(record.write_to(&mut [0; 300]), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_read_corrupt() {
+        assert!(read(b"MKS\x02").is_err());
+        assert_eq!(read(&MAGIC).unwrap().count(), 0);
+
+        let mut records = read(b"MKS\x01\x00\x00\x00\x00\x00\x03\xE0\xF7").unwrap();
+        assert_eq!(records.next(), Some(Err(Error::InvalidPacket)));
+        assert_eq!(records.next(), None);
+
+        let mut records = read(b"MKS\x01\x07\x00\x00\x00\x00\x00").unwrap();
+        assert_eq!(records.next(), Some(Err(Error::InvalidPacket)));
+        assert_eq!(records.next(), None);
+    }
+}
@@ -0,0 +1,183 @@
+//! Time abstractions for code that waits on the motor.
+//!
+//! The frame builders and [`Poller`](crate::telemetry::Poller) take timestamps
+//! from the caller. [`Clock`] and [`Delay`] let retry, timeout and pacing logic
+//! built on top of them stay independent of the platform timer, and let tests
+//! substitute a manual clock (`testing::FakeClock`, feature `testing`).
+//!
+//! [`Turnaround`] holds the quiet times an RS485 link needs around each
+//! exchange, so they live in one place instead of sleeps scattered through
+//! the transport.
+//!
+//! With the `embassy` feature, `EmbassyClock` implements all three traits on
+//! `embassy-time`.
+
+use crate::BaudRate;
+
+/// A monotonic millisecond time source.
+pub trait Clock {
+    /// Returns the current time in milliseconds since an arbitrary epoch.
+    fn now_ms(&self) -> u64;
+
+    /// Returns the milliseconds elapsed since `start_ms`, saturating at zero.
+    fn elapsed_ms(&self, start_ms: u64) -> u64 {
+        self.now_ms().saturating_sub(start_ms)
+    }
+}
+
+/// A blocking millisecond delay.
+pub trait Delay {
+    /// Blocks for at least `ms` milliseconds.
+    fn delay_ms(&mut self, ms: u32);
+}
+
+/// A non-blocking millisecond delay for async code.
+#[allow(async_fn_in_trait)]
+pub trait AsyncDelay {
+    /// Waits for at least `ms` milliseconds.
+    async fn delay_ms(&mut self, ms: u32);
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now_ms(&self) -> u64 {
+        (**self).now_ms()
+    }
+}
+
+impl<D: Delay + ?Sized> Delay for &mut D {
+    fn delay_ms(&mut self, ms: u32) {
+        (**self).delay_ms(ms);
+    }
+}
+
+impl<D: AsyncDelay + ?Sized> AsyncDelay for &mut D {
+    async fn delay_ms(&mut self, ms: u32) {
+        (**self).delay_ms(ms).await;
+    }
+}
+
+/// Minimum quiet times around an exchange on a half-duplex link.
+///
+/// RS485 converters with automatic direction control keep driving the bus for
+/// a moment after the last byte, and the board needs a pause before it can
+/// take the next frame. A transport waits [`after_tx`](Self::after_tx) between
+/// sending a frame and listening for the reply, and
+/// [`before_tx`](Self::before_tx) between a reply and the next frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Turnaround {
+    /// Pause between the end of a transmitted frame and reading the reply, in milliseconds.
+    pub after_tx_ms: u32,
+    /// Pause between the end of a reply and the next transmission, in milliseconds.
+    pub before_tx_ms: u32,
+}
+
+impl Turnaround {
+    /// No pauses, for full-duplex links.
+    pub const NONE: Self = Self {
+        after_tx_ms: 0,
+        before_tx_ms: 0,
+    };
+
+    /// Pauses of 3.5 character times at `rate` (the usual RS485 inter-frame
+    /// gap), rounded up to whole milliseconds.
+    #[must_use]
+    pub const fn for_baud_rate(rate: BaudRate) -> Self {
+        // 3.5 characters of 10 bits each (start, 8 data, stop).
+        let bps = rate.bps();
+        let ms = 35_000_u32.div_ceil(bps);
+        Self {
+            after_tx_ms: ms,
+            before_tx_ms: ms,
+        }
+    }
+
+    /// Waits out the pause after a transmitted frame.
+    pub fn after_tx<D: Delay + ?Sized>(&self, delay: &mut D) {
+        if self.after_tx_ms > 0 {
+            delay.delay_ms(self.after_tx_ms);
+        }
+    }
+
+    /// Waits out the pause before the next transmission.
+    pub fn before_tx<D: Delay + ?Sized>(&self, delay: &mut D) {
+        if self.before_tx_ms > 0 {
+            delay.delay_ms(self.before_tx_ms);
+        }
+    }
+}
+
+#[cfg(feature = "embassy")]
+pub use self::embassy::EmbassyClock;
+
+#[cfg(feature = "embassy")]
+mod embassy {
+    use super::{AsyncDelay, Clock, Delay};
+    use embassy_time::{block_for, Duration, Instant, Timer};
+
+    /// [`Clock`] and delays backed by the `embassy-time` driver (feature `embassy`).
+    ///
+    /// [`AsyncDelay`] awaits a [`Timer`], yielding to the executor; the
+    /// blocking [`Delay`] busy-waits and is meant for short pauses only.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct EmbassyClock;
+
+    impl Clock for EmbassyClock {
+        fn now_ms(&self) -> u64 {
+            Instant::now().as_millis()
+        }
+    }
+
+    impl Delay for EmbassyClock {
+        fn delay_ms(&mut self, ms: u32) {
+            block_for(Duration::from_millis(u64::from(ms)));
+        }
+    }
+
+    impl AsyncDelay for EmbassyClock {
+        async fn delay_ms(&mut self, ms: u32) {
+            Timer::after_millis(u64::from(ms)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Recorder(u32);
+
+    impl Delay for Recorder {
+        fn delay_ms(&mut self, ms: u32) {
+            self.0 += ms;
+        }
+    }
+
+    #[test]
+    fn test_turnaround() {
+        let timing = Turnaround::for_baud_rate(BaudRate::Baud9600);
+        assert_eq!(timing.after_tx_ms, 4);
+        assert_eq!(
+            Turnaround::for_baud_rate(BaudRate::Baud38400).before_tx_ms,
+            1
+        );
+        assert_eq!(
+            Turnaround::for_baud_rate(BaudRate::Baud115200).after_tx_ms,
+            1
+        );
+
+        let mut delay = Recorder(0);
+        timing.after_tx(&mut delay);
+        timing.before_tx(&mut delay);
+        Turnaround::NONE.after_tx(&mut delay);
+        assert_eq!(delay.0, 8);
+    }
+
+    #[cfg(feature = "embassy")]
+    #[test]
+    fn test_embassy_clock() {
+        let mut clock = EmbassyClock;
+        let start = clock.now_ms();
+        Delay::delay_ms(&mut clock, 5);
+        assert!(clock.elapsed_ms(start) >= 5);
+    }
+}
@@ -0,0 +1,317 @@
+use core::ops::Deref;
+
+use crate::enums::{EnLogic, RotationDirection, SaveClearStatus, ZeroMode};
+use crate::units::{Milliamps, Pulses, Speed, TorqueLimit};
+use crate::{Error, CALIBRATION_TIMEOUT_MS, CMD_BUFFER_SIZE, HOMING_TIMEOUT_MS, MAX_FRAME_LEN};
+
+/// A typed MKS SERVO42 command, independent of the target address.
+///
+/// Each variant mirrors one [`Driver`](crate::Driver) builder method and is
+/// turned into a frame by [`Driver::encode`](crate::Driver::encode) or
+/// [`Driver::encode_into`](crate::Driver::encode_into).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// See [`Driver::enable_motor`](crate::Driver::enable_motor).
+    EnableMotor(bool),
+    /// See [`Driver::run_with_constant_speed`](crate::Driver::run_with_constant_speed).
+    RunWithConstantSpeed {
+        direction: RotationDirection,
+        speed: Speed,
+    },
+    /// See [`Driver::stop`](crate::Driver::stop).
+    Stop,
+    /// See [`Driver::save_clear_status`](crate::Driver::save_clear_status).
+    SaveClearStatus(SaveClearStatus),
+    /// See [`Driver::run_motor`](crate::Driver::run_motor).
+    RunMotor {
+        direction: RotationDirection,
+        speed: Speed,
+        pulses: Pulses,
+    },
+    /// See [`Driver::calibrate_encoder`](crate::Driver::calibrate_encoder).
+    CalibrateEncoder,
+    /// See [`Driver::set_current_limit`](crate::Driver::set_current_limit).
+    SetCurrentLimit(Milliamps),
+    /// See [`Driver::set_subdivision`](crate::Driver::set_subdivision).
+    SetSubdivision(u8),
+    /// See [`Driver::set_enable_logic`](crate::Driver::set_enable_logic).
+    SetEnableLogic(EnLogic),
+    /// See [`Driver::set_direction`](crate::Driver::set_direction).
+    SetDirection(RotationDirection),
+    /// See [`Driver::set_auto_screen_off`](crate::Driver::set_auto_screen_off).
+    SetAutoScreenOff(bool),
+    /// See [`Driver::set_stall_protection`](crate::Driver::set_stall_protection).
+    SetStallProtection(bool),
+    /// See [`Driver::set_interpolation`](crate::Driver::set_interpolation).
+    SetInterpolation(bool),
+    /// See [`Driver::set_zero_mode`](crate::Driver::set_zero_mode).
+    SetZeroMode(ZeroMode),
+    /// See [`Driver::set_current_as_zero`](crate::Driver::set_current_as_zero).
+    SetCurrentAsZero,
+    /// See [`Driver::set_zero_speed`](crate::Driver::set_zero_speed).
+    SetZeroSpeed(u8),
+    /// See [`Driver::go_to_zero`](crate::Driver::go_to_zero).
+    GoToZero,
+    /// See [`Driver::set_zero_direction`](crate::Driver::set_zero_direction).
+    SetZeroDirection(RotationDirection),
+    /// See [`Driver::set_position_kp`](crate::Driver::set_position_kp).
+    SetPositionKp(u16),
+    /// See [`Driver::set_position_ki`](crate::Driver::set_position_ki).
+    SetPositionKi(u16),
+    /// See [`Driver::set_position_kd`](crate::Driver::set_position_kd).
+    SetPositionKd(u16),
+    /// See [`Driver::set_acceleration`](crate::Driver::set_acceleration).
+    SetAcceleration(u16),
+    /// See [`Driver::set_max_torque`](crate::Driver::set_max_torque).
+    SetMaxTorque(TorqueLimit),
+    /// See [`Driver::read_shaft_status`](crate::Driver::read_shaft_status).
+    ReadShaftStatus,
+    /// See [`Driver::read_encoder_value`](crate::Driver::read_encoder_value).
+    ReadEncoderValue,
+    /// See [`Driver::read_pulse_count`](crate::Driver::read_pulse_count).
+    ReadPulseCount,
+    /// See [`Driver::read_motor_shaft_angle`](crate::Driver::read_motor_shaft_angle).
+    ReadMotorShaftAngle,
+    /// See [`Driver::read_en_pin_status`](crate::Driver::read_en_pin_status).
+    ReadEnPinStatus,
+    /// See [`Driver::read_motor_shaft_angle_error`](crate::Driver::read_motor_shaft_angle_error).
+    ReadMotorShaftAngleError,
+    /// See [`Driver::read_release_status`](crate::Driver::read_release_status).
+    ReadReleaseStatus,
+}
+
+/// Broad effect of a [`Command`], for wrappers that gate or highlight operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandClass {
+    /// Reads state without changing it.
+    Read,
+    /// Changes a setting; takes effect without moving the shaft.
+    Configure,
+    /// Energizes, moves or stops the motor.
+    Motion,
+    /// Can leave the board miscalibrated or unreachable.
+    Destructive,
+}
+
+impl Command {
+    /// Classifies the command.
+    ///
+    /// Encoder calibration turns the shaft for up to a minute and replaces the
+    /// stored calibration, so it is [`CommandClass::Destructive`].
+    #[must_use]
+    pub const fn class(&self) -> CommandClass {
+        match self {
+            Self::ReadShaftStatus
+            | Self::ReadEncoderValue
+            | Self::ReadPulseCount
+            | Self::ReadMotorShaftAngle
+            | Self::ReadEnPinStatus
+            | Self::ReadMotorShaftAngleError
+            | Self::ReadReleaseStatus => CommandClass::Read,
+            Self::SetCurrentLimit(_)
+            | Self::SetSubdivision(_)
+            | Self::SetEnableLogic(_)
+            | Self::SetDirection(_)
+            | Self::SetAutoScreenOff(_)
+            | Self::SetStallProtection(_)
+            | Self::SetInterpolation(_)
+            | Self::SetZeroMode(_)
+            | Self::SetCurrentAsZero
+            | Self::SetZeroSpeed(_)
+            | Self::SetZeroDirection(_)
+            | Self::SetPositionKp(_)
+            | Self::SetPositionKi(_)
+            | Self::SetPositionKd(_)
+            | Self::SetAcceleration(_)
+            | Self::SetMaxTorque(_)
+            | Self::SaveClearStatus(_) => CommandClass::Configure,
+            Self::EnableMotor(_)
+            | Self::RunWithConstantSpeed { .. }
+            | Self::Stop
+            | Self::RunMotor { .. }
+            | Self::GoToZero => CommandClass::Motion,
+            Self::CalibrateEncoder => CommandClass::Destructive,
+        }
+    }
+
+    /// Returns how long to wait for the reply, given the timeout `default_ms`
+    /// used for ordinary commands.
+    ///
+    /// Commands answered only after a long-running action override the default:
+    /// [`CALIBRATION_TIMEOUT_MS`] for calibration and [`HOMING_TIMEOUT_MS`] for
+    /// return to zero. A longer `default_ms` is kept.
+    #[must_use]
+    pub const fn reply_timeout_ms(&self, default_ms: u32) -> u32 {
+        let minimum = match self {
+            Self::CalibrateEncoder => CALIBRATION_TIMEOUT_MS,
+            Self::GoToZero => HOMING_TIMEOUT_MS,
+            _ => 0,
+        };
+        if default_ms > minimum {
+            default_ms
+        } else {
+            minimum
+        }
+    }
+}
+
+/// An encoded command frame, owned and independent of the [`Driver`](crate::Driver).
+///
+/// Dereferences to the frame bytes (checksum included). `N` is the buffer size,
+/// [`CMD_BUFFER_SIZE`] by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommandBytes<const N: usize = CMD_BUFFER_SIZE> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> CommandBytes<N> {
+    const FITS_LONGEST_FRAME: () =
+        assert!(N >= MAX_FRAME_LEN, "buffer too small for the longest frame");
+
+    /// Copies `payload` and appends its checksum.
+    pub(crate) const fn new(payload: &[u8]) -> Self {
+        let () = Self::FITS_LONGEST_FRAME;
+        let mut bytes = [0; N];
+        let mut i = 0;
+        while i < payload.len() {
+            bytes[i] = payload[i];
+            i += 1;
+        }
+        bytes[payload.len()] = crate::calculate_checksum(payload);
+        Self {
+            bytes,
+            len: payload.len() + 1,
+        }
+    }
+
+    /// Returns the frame bytes.
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// Returns the whole buffer and the frame length, consuming the frame.
+    ///
+    /// The array can be moved into a `'static` slot (e.g. a `StaticCell` or a
+    /// DMA queue) and handed to a HAL transmit function as `&buffer[..len]`.
+    /// Bytes past `len` are zero.
+    #[must_use]
+    pub const fn into_parts(self) -> ([u8; N], usize) {
+        (self.bytes, self.len)
+    }
+
+    /// Copies the frame into the start of `out` and returns its length.
+    pub(crate) fn write_to(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let dst = out.get_mut(..self.len).ok_or(Error::BufferTooSmall)?;
+        dst.copy_from_slice(self.as_slice());
+        Ok(self.len)
+    }
+}
+
+impl<const N: usize> Deref for CommandBytes<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> CommandBytes<N> {
+    /// Converts the frame into a `heapless::Vec`, e.g. to store it in a queue.
+    #[must_use]
+    pub fn into_vec(self) -> heapless::Vec<u8, N> {
+        self.into()
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> From<CommandBytes<N>> for heapless::Vec<u8, N> {
+    fn from(frame: CommandBytes<N>) -> Self {
+        let mut vec = Self::new();
+        // A frame never exceeds its own capacity.
+        let _ = vec.extend_from_slice(frame.as_slice());
+        vec
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for CommandBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_timeout() {
+        use crate::DEFAULT_REPLY_TIMEOUT_MS;
+
+        assert_eq!(
+            Command::Stop.reply_timeout_ms(DEFAULT_REPLY_TIMEOUT_MS),
+            DEFAULT_REPLY_TIMEOUT_MS
+        );
+        assert_eq!(
+            Command::CalibrateEncoder.reply_timeout_ms(DEFAULT_REPLY_TIMEOUT_MS),
+            CALIBRATION_TIMEOUT_MS
+        );
+        assert_eq!(
+            Command::GoToZero.reply_timeout_ms(DEFAULT_REPLY_TIMEOUT_MS),
+            HOMING_TIMEOUT_MS
+        );
+        assert_eq!(Command::GoToZero.reply_timeout_ms(u32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn test_command_class() {
+        assert_eq!(Command::ReadPulseCount.class(), CommandClass::Read);
+        assert_eq!(Command::SetZeroSpeed(1).class(), CommandClass::Configure);
+        assert_eq!(Command::Stop.class(), CommandClass::Motion);
+        assert_eq!(Command::GoToZero.class(), CommandClass::Motion);
+        assert_eq!(Command::CalibrateEncoder.class(), CommandClass::Destructive);
+        assert_eq!(
+            Command::SaveClearStatus(SaveClearStatus::Clear).class(),
+            CommandClass::Configure
+        );
+    }
+
+    #[test]
+    fn test_command_bytes_appends_checksum() {
+        let cmd: CommandBytes = CommandBytes::new(&[0xE0, 0xF6, 0x01]);
+        assert_eq!(cmd.as_slice(), &[0xE0, 0xF6, 0x01, 0xD7]);
+        assert_eq!(cmd.len(), 4);
+        assert_eq!(cmd.as_ref(), &cmd[..]);
+    }
+
+    #[test]
+    fn test_command_bytes_ignores_unused_tail() {
+        let a: CommandBytes = CommandBytes::new(&[0xE0, 0xF7]);
+        let b: CommandBytes = CommandBytes::new(&[0xE0, 0xF7]);
+        assert_eq!(a, b);
+        assert_ne!(a, CommandBytes::<CMD_BUFFER_SIZE>::new(&[0xE1, 0xF7]));
+    }
+
+    #[test]
+    fn test_command_bytes_into_parts() {
+        let cmd: CommandBytes<12> = CommandBytes::new(&[0xE0, 0xF7]);
+        let (buffer, len) = cmd.into_parts();
+        assert_eq!(len, 3);
+        assert_eq!(&buffer[..len], &[0xE0, 0xF7, 0xD7]);
+        assert!(buffer[len..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_command_bytes_write_to() {
+        let cmd: CommandBytes = CommandBytes::new(&[0xE0, 0xF6, 0x01]);
+        let mut out = [0xAA; 6];
+        assert_eq!(cmd.write_to(&mut out), Ok(4));
+        assert_eq!(out, [0xE0, 0xF6, 0x01, 0xD7, 0xAA, 0xAA]);
+
+        let mut short = [0; 3];
+        assert_eq!(cmd.write_to(&mut short), Err(Error::BufferTooSmall));
+        assert_eq!(short, [0; 3]);
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{defaults, Pid, TorqueLimit};
+
+/// Tuning parameters of one motor: PID, acceleration and torque limit.
+///
+/// The target board is the [`Driver`](crate::Driver) that applies the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MotorConfig {
+    /// Position loop coefficients.
+    pub pid: Pid,
+    /// Acceleration (ACC), up to `MAX_ACCELERATION`.
+    pub acceleration: u16,
+    /// Maximum torque.
+    pub max_torque: TorqueLimit,
+}
+
+impl MotorConfig {
+    /// Returns the firmware's factory defaults.
+    #[must_use]
+    pub const fn factory() -> Self {
+        Self {
+            pid: Pid::DEFAULT,
+            acceleration: defaults::ACCELERATION,
+            max_torque: TorqueLimit::MAX,
+        }
+    }
+}
+
+impl Default for MotorConfig {
+    fn default() -> Self {
+        Self::factory()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factory_matches_manual() {
+        let config = MotorConfig::factory();
+        assert_eq!(config.pid.kp(), 0x650);
+        assert_eq!(config.pid.ki(), 1);
+        assert_eq!(config.pid.kd(), 0x650);
+        assert_eq!(config.acceleration, 0x11E);
+        assert_eq!(config.max_torque.get(), 0x4B0);
+        assert_eq!(MotorConfig::default(), config);
+    }
+}
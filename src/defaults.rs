@@ -0,0 +1,19 @@
+//! Factory default settings documented in the MKS SERVO42C manual.
+//!
+//! [`MotorConfig::factory`](crate::MotorConfig::factory) bundles these into a
+//! configuration that can be sent back to a board to restore known-good tuning.
+
+/// Default slave address.
+pub const ADDRESS: u8 = crate::DEFAULT_ADDRESS;
+/// Default UART baud rate.
+pub const BAUD_RATE: crate::BaudRate = crate::BaudRate::Baud38400;
+/// Default position loop Kp.
+pub const POSITION_KP: u16 = 0x650;
+/// Default position loop Ki.
+pub const POSITION_KI: u16 = 1;
+/// Default position loop Kd.
+pub const POSITION_KD: u16 = 0x650;
+/// Default acceleration (ACC).
+pub const ACCELERATION: u16 = 0x11E;
+/// Default maximum torque (MaxT).
+pub const MAX_TORQUE: u16 = crate::MAX_TORQUE_LIMIT;
@@ -0,0 +1,315 @@
+//! One-call bring-up checks and status snapshots.
+//!
+//! [`HealthCheck`] reads the EN pin, shaft status, encoder and angle error in
+//! turn and summarizes them in a [`HealthReport`]. [`ReadAll`] issues every read
+//! command and collects a [`Snapshot`]. As everywhere else in the crate, the
+//! caller moves the bytes:
+//!
+//! ```
+//! use mks_servo42_rs::diagnostics::HealthCheck;
+//! use mks_servo42_rs::Address;
+//!
+//! let mut check = HealthCheck::new(Address::DEFAULT);
+//! while let Some(frame) = check.next_frame() {
+//!     // Send `frame` and wait for the reply; here the board never answers.
+//!     check.on_timeout();
+//! }
+//! let report = check.report();
+//! assert!(!report.communication_ok());
+//! ```
+
+use crate::enums::{ProtectionState, ShaftStatus};
+use crate::helpers::{EnPinStatus, EncoderValue, MotorShaftAngle, PulseCount, ShaftErrValue};
+use crate::telemetry::{Channel, ReadSequence, Reading};
+use crate::{Address, Error, Response};
+
+/// Extracts the typed outcome of `$channel` from a read sequence.
+macro_rules! outcome {
+    ($sequence:expr, $channel:ident) => {
+        match $sequence.result(Channel::$channel) {
+            Some(Ok(Reading::$channel(value))) => Ok(value),
+            Some(Ok(_)) => Err(Error::InvalidPacket),
+            Some(Err(error)) => Err(error),
+            None => Err(Error::Timeout),
+        }
+    };
+}
+
+/// Default largest healthy angle error, in encoder units (about 10°).
+pub const DEFAULT_MAX_ANGLE_ERROR: u16 = 182 * 10;
+
+const CHECKS: [Channel; 4] = [
+    Channel::EnPinStatus,
+    Channel::ShaftStatus,
+    Channel::Encoder,
+    Channel::AngleError,
+];
+
+/// Sequences the reads of a health check.
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    sequence: ReadSequence<4>,
+    max_angle_error: u16,
+}
+
+impl HealthCheck {
+    /// Creates a health check for the board at `address`.
+    #[must_use]
+    pub const fn new(address: Address) -> Self {
+        Self {
+            sequence: ReadSequence::new(address, CHECKS),
+            max_angle_error: DEFAULT_MAX_ANGLE_ERROR,
+        }
+    }
+
+    /// Sets the largest angle error magnitude considered healthy, in encoder units.
+    #[must_use]
+    pub const fn max_angle_error(mut self, max_angle_error: u16) -> Self {
+        self.max_angle_error = max_angle_error;
+        self
+    }
+
+    /// Returns the frame to send next, or `None` once the check is complete.
+    #[must_use]
+    pub fn next_frame(&self) -> Option<[u8; 3]> {
+        self.sequence.next_frame()
+    }
+
+    /// Feeds the reply to the frame last returned by [`next_frame`](Self::next_frame).
+    pub fn on_reply(&mut self, data: &[u8]) {
+        self.sequence.on_reply(data);
+    }
+
+    /// Records that the frame last returned by [`next_frame`](Self::next_frame) got no reply.
+    pub fn on_timeout(&mut self) {
+        self.sequence.on_timeout();
+    }
+
+    /// Summarizes the outcomes so far; reads not run yet are reported as timeouts.
+    #[must_use]
+    pub fn report(&self) -> HealthReport {
+        HealthReport {
+            en_pin: outcome!(self.sequence, EnPinStatus),
+            shaft_status: outcome!(self.sequence, ShaftStatus),
+            encoder: outcome!(self.sequence, Encoder),
+            angle_error: outcome!(self.sequence, AngleError),
+            max_angle_error: self.max_angle_error,
+        }
+    }
+}
+
+/// Outcome of a [`HealthCheck`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthReport {
+    /// EN pin status read.
+    pub en_pin: Result<EnPinStatus, Error>,
+    /// Shaft status read.
+    pub shaft_status: Result<ShaftStatus, Error>,
+    /// Encoder read.
+    pub encoder: Result<EncoderValue, Error>,
+    /// Angle error read.
+    pub angle_error: Result<ShaftErrValue, Error>,
+    /// Largest angle error magnitude considered healthy, in encoder units.
+    pub max_angle_error: u16,
+}
+
+impl HealthReport {
+    /// Returns `true` if at least one read got a valid reply.
+    #[must_use]
+    pub const fn communication_ok(&self) -> bool {
+        self.en_pin.is_ok()
+            || self.shaft_status.is_ok()
+            || self.encoder.is_ok()
+            || self.angle_error.is_ok()
+    }
+
+    /// Returns `true` if every read succeeded, no status reports an error,
+    /// the shaft is not blocked and the angle error is within bounds.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        matches!(
+            self.en_pin,
+            Ok(EnPinStatus::Enabled | EnPinStatus::Disabled)
+        ) && self.shaft_status == Ok(ShaftStatus::Unblocked)
+            && self.encoder.is_ok()
+            && self
+                .angle_error
+                .is_ok_and(|error| error.value.unsigned_abs() <= self.max_angle_error)
+    }
+}
+
+const ALL_READS: [Channel; 7] = [
+    Channel::Encoder,
+    Channel::PulseCount,
+    Channel::MotorShaftAngle,
+    Channel::AngleError,
+    Channel::EnPinStatus,
+    Channel::ShaftStatus,
+    Channel::ReleaseStatus,
+];
+
+/// Sequences every read command to build a [`Snapshot`].
+#[derive(Debug, Clone)]
+pub struct ReadAll {
+    sequence: ReadSequence<7>,
+}
+
+impl ReadAll {
+    /// Creates a snapshot reader for the board at `address`.
+    #[must_use]
+    pub const fn new(address: Address) -> Self {
+        Self {
+            sequence: ReadSequence::new(address, ALL_READS),
+        }
+    }
+
+    /// Returns the frame to send next, or `None` once every read completed.
+    #[must_use]
+    pub fn next_frame(&self) -> Option<[u8; 3]> {
+        self.sequence.next_frame()
+    }
+
+    /// Feeds the reply to the frame last returned by [`next_frame`](Self::next_frame).
+    pub fn on_reply(&mut self, data: &[u8]) {
+        self.sequence.on_reply(data);
+    }
+
+    /// Records that the frame last returned by [`next_frame`](Self::next_frame) got no reply.
+    pub fn on_timeout(&mut self) {
+        self.sequence.on_timeout();
+    }
+
+    /// Collects the outcomes so far; reads not run yet are reported as timeouts.
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            encoder: outcome!(self.sequence, Encoder),
+            pulse_count: outcome!(self.sequence, PulseCount),
+            motor_shaft_angle: outcome!(self.sequence, MotorShaftAngle),
+            angle_error: outcome!(self.sequence, AngleError),
+            en_pin: outcome!(self.sequence, EnPinStatus),
+            shaft_status: outcome!(self.sequence, ShaftStatus),
+            release_status: outcome!(self.sequence, ReleaseStatus),
+        }
+    }
+}
+
+/// Every readable value of a board, each with its own read outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Encoder read.
+    pub encoder: Result<EncoderValue, Error>,
+    /// Pulse count read.
+    pub pulse_count: Result<PulseCount, Error>,
+    /// Shaft angle read.
+    pub motor_shaft_angle: Result<MotorShaftAngle, Error>,
+    /// Angle error read.
+    pub angle_error: Result<ShaftErrValue, Error>,
+    /// EN pin status read.
+    pub en_pin: Result<EnPinStatus, Error>,
+    /// Shaft status read.
+    pub shaft_status: Result<ShaftStatus, Error>,
+    /// Release status read.
+    pub release_status: Result<Response, Error>,
+}
+
+impl Snapshot {
+    /// Interprets the shaft status and release reads together.
+    #[must_use]
+    pub fn protection_state(&self) -> ProtectionState {
+        ProtectionState::from_replies(self.shaft_status.ok(), self.release_status.ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EN_PIN_REPLY: [u8; 3] = [0xE0, 0x01, 0xE1];
+    const SHAFT_REPLY: [u8; 3] = [0xE0, 0x02, 0xE2];
+    const ENCODER_REPLY: [u8; 8] = [0xE0, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x20];
+    const ERROR_REPLY: [u8; 5] = [0xE0, 0x00, 0xB7, 0x97, 0x00];
+
+    fn run(check: &mut HealthCheck, replies: [&[u8]; 4]) {
+        for reply in replies {
+            assert!(check.next_frame().is_some());
+            check.on_reply(reply);
+        }
+        assert_eq!(check.next_frame(), None);
+    }
+
+    #[test]
+    fn test_healthy_board() {
+        let mut check = HealthCheck::new(Address::DEFAULT);
+        assert_eq!(check.next_frame(), Some([0xE0, 0x3A, 0x1A]));
+        run(
+            &mut check,
+            [&EN_PIN_REPLY, &SHAFT_REPLY, &ENCODER_REPLY, &ERROR_REPLY],
+        );
+        let report = check.report();
+        assert!(report.communication_ok());
+        assert!(report.is_healthy());
+        assert_eq!(report.en_pin, Ok(EnPinStatus::Enabled));
+        assert_eq!(report.angle_error, Ok(ShaftErrValue { value: 183 }));
+    }
+
+    #[test]
+    fn test_read_all() {
+        let mut read_all = ReadAll::new(Address::DEFAULT);
+        let replies: [&[u8]; 7] = [
+            &ENCODER_REPLY,
+            &[0xE0, 0x00, 0x00, 0x01, 0x00, 0xE1],
+            &[0xE0, 0x00, 0x00, 0x40, 0x00, 0x20],
+            &ERROR_REPLY,
+            &EN_PIN_REPLY,
+            &SHAFT_REPLY,
+            &[0xE0, 0x01, 0xE1],
+        ];
+        let mut codes = [0u8; 7];
+        for (code, reply) in codes.iter_mut().zip(replies) {
+            *code = read_all.next_frame().unwrap()[1];
+            read_all.on_reply(reply);
+        }
+        assert_eq!(codes, [0x30, 0x33, 0x36, 0x39, 0x3A, 0x3E, 0x3D]);
+        assert_eq!(read_all.next_frame(), None);
+
+        let snapshot = read_all.snapshot();
+        assert_eq!(snapshot.pulse_count, Ok(PulseCount(256)));
+        assert_eq!(
+            snapshot.motor_shaft_angle,
+            Ok(MotorShaftAngle { value: 0x4000 })
+        );
+        assert_eq!(snapshot.shaft_status, Ok(ShaftStatus::Unblocked));
+        assert_eq!(snapshot.release_status, Ok(Response::Success));
+        assert_eq!(snapshot.protection_state(), ProtectionState::Released);
+    }
+
+    #[test]
+    fn test_read_all_keeps_per_read_errors() {
+        let mut read_all = ReadAll::new(Address::DEFAULT);
+        read_all.on_reply(&[0xE0, 0x00]);
+        read_all.on_timeout();
+        let snapshot = read_all.snapshot();
+        assert_eq!(snapshot.encoder, Err(Error::InvalidPacket));
+        assert_eq!(snapshot.pulse_count, Err(Error::Timeout));
+        assert_eq!(snapshot.release_status, Err(Error::Timeout));
+        assert_eq!(snapshot.protection_state(), ProtectionState::Unknown);
+    }
+
+    #[test]
+    fn test_unhealthy_board() {
+        let mut check = HealthCheck::new(Address::DEFAULT).max_angle_error(100);
+        run(
+            &mut check,
+            [&EN_PIN_REPLY, &SHAFT_REPLY, &ENCODER_REPLY, &ERROR_REPLY],
+        );
+        assert!(!check.report().is_healthy());
+
+        let mut check = HealthCheck::new(Address::DEFAULT);
+        check.on_reply(&EN_PIN_REPLY);
+        let report = check.report();
+        assert!(report.communication_ok());
+        assert!(!report.is_healthy());
+        assert_eq!(report.shaft_status, Err(Error::Timeout));
+    }
+}
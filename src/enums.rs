@@ -1,3 +1,5 @@
+use crate::{Error, Response};
+
 /// Motor step angle configuration.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -8,6 +10,17 @@ pub enum MotorType {
     Deg18 = 0x01,
 }
 
+impl MotorType {
+    /// Returns the number of full steps per revolution.
+    #[must_use]
+    pub const fn steps_per_rev(self) -> u16 {
+        match self {
+            Self::Deg09 => 400,
+            Self::Deg18 => 200,
+        }
+    }
+}
+
 /// Motor operating mode.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -35,6 +48,7 @@ pub enum EnLogic {
 /// UART baud rate settings.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum BaudRate {
     /// 9600 bps.
     Baud9600 = 0x01,
@@ -48,6 +62,57 @@ pub enum BaudRate {
     Baud57600 = 0x05,
     /// 115200 bps.
     Baud115200 = 0x06,
+    /// 256000 bps (SERVO42D firmware only).
+    #[cfg(feature = "servo42d")]
+    Baud256000 = 0x07,
+}
+
+impl BaudRate {
+    /// Rates to try, in order, when the board's rate is unknown: the factory
+    /// default first, then the most common choices.
+    pub const SCAN_ORDER: &'static [Self] = &[
+        Self::Baud38400,
+        Self::Baud115200,
+        Self::Baud9600,
+        Self::Baud57600,
+        Self::Baud19200,
+        Self::Baud25000,
+        #[cfg(feature = "servo42d")]
+        Self::Baud256000,
+    ];
+
+    /// Returns the setting for a rate in bits per second.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the firmware does not support `bps`.
+    pub const fn try_from_bps(bps: u32) -> Result<Self, Error> {
+        match bps {
+            9600 => Ok(Self::Baud9600),
+            19200 => Ok(Self::Baud19200),
+            25000 => Ok(Self::Baud25000),
+            38400 => Ok(Self::Baud38400),
+            57600 => Ok(Self::Baud57600),
+            115_200 => Ok(Self::Baud115200),
+            #[cfg(feature = "servo42d")]
+            256_000 => Ok(Self::Baud256000),
+            _ => Err(Error::InvalidValue),
+        }
+    }
+
+    /// Returns the rate in bits per second, e.g. to configure the host serial port.
+    #[must_use]
+    pub const fn bps(self) -> u32 {
+        match self {
+            Self::Baud9600 => 9600,
+            Self::Baud19200 => 19200,
+            Self::Baud25000 => 25000,
+            Self::Baud38400 => 38400,
+            Self::Baud57600 => 57600,
+            Self::Baud115200 => 115_200,
+            #[cfg(feature = "servo42d")]
+            Self::Baud256000 => 256_000,
+        }
+    }
 }
 
 /// Return-to-zero mode settings.
@@ -84,6 +149,70 @@ pub enum ShaftStatus {
     Error = 0x00,
 }
 
+/// Stall protection state of the motor shaft.
+///
+/// Built from the replies to `read_shaft_status` (0x3E) and
+/// `read_release_status` (0x3D):
+///
+/// | Reply            | State       |
+/// |------------------|-------------|
+/// | 0x3E `Blocked`   | `Protected` |
+/// | 0x3E `Unblocked` | `Released`  |
+/// | 0x3E `Error`     | `Unknown`   |
+/// | 0x3D `Success`   | `Released`  |
+/// | 0x3D `Failure`   | `Unknown`   |
+///
+/// A failed release says nothing about the shaft: the motor may not have been
+/// protected in the first place.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProtectionState {
+    /// Stall protection tripped; the motor is de-energized until released.
+    Protected,
+    /// The shaft is free to run.
+    Released,
+    /// The replies do not determine the state.
+    Unknown,
+}
+
+impl ProtectionState {
+    /// Interprets a release reply (0x3D).
+    #[must_use]
+    pub const fn from_release(response: Response) -> Self {
+        match response {
+            Response::Success => Self::Released,
+            Response::Failure => Self::Unknown,
+        }
+    }
+
+    /// Combines the shaft status and release replies, either of which may be missing.
+    ///
+    /// A conclusive shaft status wins, since it reflects the current state; a
+    /// successful release is used otherwise.
+    #[must_use]
+    pub fn from_replies(shaft: Option<ShaftStatus>, release: Option<Response>) -> Self {
+        match shaft.map(Self::from) {
+            Some(state @ (Self::Protected | Self::Released)) => state,
+            _ => release.map_or(Self::Unknown, Self::from_release),
+        }
+    }
+
+    /// Returns `true` if the motor must be released before it can move.
+    #[must_use]
+    pub const fn is_protected(self) -> bool {
+        matches!(self, Self::Protected)
+    }
+}
+
+impl From<ShaftStatus> for ProtectionState {
+    fn from(status: ShaftStatus) -> Self {
+        match status {
+            ShaftStatus::Blocked => Self::Protected,
+            ShaftStatus::Unblocked => Self::Released,
+            ShaftStatus::Error => Self::Unknown,
+        }
+    }
+}
+
 /// Rotation direction configuration.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -93,3 +222,87 @@ pub enum RotationDirection {
     /// Counter-clockwise rotation (CCW).
     CounterClockwise = 0x01,
 }
+
+impl RotationDirection {
+    /// Returns the opposite direction.
+    #[must_use]
+    pub const fn reversed(self) -> Self {
+        match self {
+            Self::Clockwise => Self::CounterClockwise,
+            Self::CounterClockwise => Self::Clockwise,
+        }
+    }
+}
+
+/// Serial protocol dialect spoken by the target board.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ProtocolVariant {
+    /// MKS SERVO42C firmware (V1.0+).
+    #[default]
+    Servo42C,
+    /// MKS SERVO57C firmware: SERVO42C frames with the SERVO57C limits.
+    Servo57,
+    /// MKS SERVO42D / SERVO57D firmware (`FA`/`FB` frames, addresses 0x00-0xFF).
+    #[cfg(feature = "servo42d")]
+    Servo42D,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_baud_rate_scan_order() {
+        for &rate in BaudRate::SCAN_ORDER {
+            assert_eq!(BaudRate::try_from_bps(rate.bps()), Ok(rate));
+        }
+        assert_eq!(BaudRate::SCAN_ORDER[0], crate::defaults::BAUD_RATE);
+        assert_eq!(BaudRate::try_from_bps(4800), Err(Error::InvalidValue));
+        #[cfg(not(feature = "servo42d"))]
+        assert_eq!(BaudRate::try_from_bps(256_000), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_protection_state_mapping() {
+        assert_eq!(
+            ProtectionState::from(ShaftStatus::Blocked),
+            ProtectionState::Protected
+        );
+        assert_eq!(
+            ProtectionState::from(ShaftStatus::Unblocked),
+            ProtectionState::Released
+        );
+        assert_eq!(
+            ProtectionState::from(ShaftStatus::Error),
+            ProtectionState::Unknown
+        );
+        assert_eq!(
+            ProtectionState::from_release(Response::Success),
+            ProtectionState::Released
+        );
+        assert_eq!(
+            ProtectionState::from_release(Response::Failure),
+            ProtectionState::Unknown
+        );
+    }
+
+    #[test]
+    fn test_protection_state_combinations() {
+        let from = ProtectionState::from_replies;
+        assert_eq!(
+            from(Some(ShaftStatus::Blocked), Some(Response::Success)),
+            ProtectionState::Protected
+        );
+        assert_eq!(
+            from(Some(ShaftStatus::Error), Some(Response::Success)),
+            ProtectionState::Released
+        );
+        assert_eq!(
+            from(None, Some(Response::Failure)),
+            ProtectionState::Unknown
+        );
+        assert_eq!(from(None, None), ProtectionState::Unknown);
+        assert!(from(Some(ShaftStatus::Blocked), None).is_protected());
+    }
+}
@@ -7,6 +7,16 @@ pub enum Error {
     Checksum,
     /// Received packet has invalid format or length.
     InvalidPacket,
+    /// Output buffer is too small for the encoded frame.
+    BufferTooSmall,
+    /// No reply was received in time.
+    Timeout,
+    /// Command is not supported by the target protocol.
+    Unsupported,
+    /// Command is blocked by the active [`CommandPolicy`](crate::CommandPolicy).
+    NotPermitted,
+    /// Received data holds more than one plausible reply.
+    Ambiguous,
 }
 
 impl Error {
@@ -16,6 +26,11 @@ impl Error {
             Self::InvalidValue => "Invalid value",
             Self::Checksum => "Checksum mismatch",
             Self::InvalidPacket => "Invalid packet format",
+            Self::BufferTooSmall => "Buffer too small",
+            Self::Timeout => "Timed out waiting for reply",
+            Self::Unsupported => "Unsupported command",
+            Self::NotPermitted => "Command not permitted",
+            Self::Ambiguous => "Ambiguous reply",
         }
     }
 }
@@ -31,6 +46,11 @@ mod tests {
         assert_eq!(Error::InvalidValue.as_str(), "Invalid value");
         assert_eq!(Error::Checksum.as_str(), "Checksum mismatch");
         assert_eq!(Error::InvalidPacket.as_str(), "Invalid packet format");
+        assert_eq!(Error::BufferTooSmall.as_str(), "Buffer too small");
+        assert_eq!(Error::Timeout.as_str(), "Timed out waiting for reply");
+        assert_eq!(Error::Unsupported.as_str(), "Unsupported command");
+        assert_eq!(Error::NotPermitted.as_str(), "Command not permitted");
+        assert_eq!(Error::Ambiguous.as_str(), "Ambiguous reply");
     }
 
     #[test]
@@ -38,6 +58,10 @@ mod tests {
         assert_eq!(std::format!("{:?}", Error::InvalidValue), "InvalidValue");
         assert_eq!(std::format!("{:?}", Error::Checksum), "Checksum");
         assert_eq!(std::format!("{:?}", Error::InvalidPacket), "InvalidPacket");
+        assert_eq!(
+            std::format!("{:?}", Error::BufferTooSmall),
+            "BufferTooSmall"
+        );
     }
 
     #[test]
@@ -0,0 +1,211 @@
+//! `const fn` builders for commands without runtime parameters.
+//!
+//! These return complete frames (checksum included) as fixed-size arrays, so they
+//! can be evaluated at compile time, stored in flash and sent without a [`Driver`](crate::Driver).
+//!
+//! ```
+//! use mks_servo42_rs::{frames, Address};
+//!
+//! const STOP: [u8; 3] = frames::stop(Address::DEFAULT);
+//! assert_eq!(STOP, [0xE0, 0xF7, 0xD7]);
+//! ```
+//!
+//! For telemetry loops, [`cached`] returns the read frames of every valid address
+//! from a static table, so polling needs no encoding at all:
+//!
+//! ```
+//! use mks_servo42_rs::{frames, Address};
+//!
+//! let reads = frames::cached(Address::DEFAULT).unwrap();
+//! assert_eq!(reads.encoder_value, [0xE0, 0x30, 0x10]);
+//! ```
+
+use crate::{calculate_checksum, cmd, Address, MAX_ADDRESS, MIN_ADDRESS};
+
+const ADDRESS_COUNT: usize = (MAX_ADDRESS - MIN_ADDRESS) as usize + 1;
+
+/// Pre-encoded read frames for one slave address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadFrames {
+    /// See [`read_encoder_value`].
+    pub encoder_value: [u8; 3],
+    /// See [`read_pulse_count`].
+    pub pulse_count: [u8; 3],
+    /// See [`read_motor_shaft_angle`].
+    pub motor_shaft_angle: [u8; 3],
+    /// See [`read_motor_shaft_angle_error`].
+    pub motor_shaft_angle_error: [u8; 3],
+    /// See [`read_en_pin_status`].
+    pub en_pin_status: [u8; 3],
+    /// See [`read_release_status`].
+    pub release_status: [u8; 3],
+    /// See [`read_shaft_status`].
+    pub shaft_status: [u8; 3],
+}
+
+impl ReadFrames {
+    /// Encodes all read frames for `address`.
+    #[must_use]
+    pub const fn new(address: Address) -> Self {
+        Self {
+            encoder_value: read_encoder_value(address),
+            pulse_count: read_pulse_count(address),
+            motor_shaft_angle: read_motor_shaft_angle(address),
+            motor_shaft_angle_error: read_motor_shaft_angle_error(address),
+            en_pin_status: read_en_pin_status(address),
+            release_status: read_release_status(address),
+            shaft_status: read_shaft_status(address),
+        }
+    }
+}
+
+static READ_FRAMES: [ReadFrames; ADDRESS_COUNT] = {
+    let mut table = [ReadFrames::new(Address::DEFAULT); ADDRESS_COUNT];
+    let mut i = 0;
+    while i < ADDRESS_COUNT {
+        table[i] = ReadFrames::new(Address::from_raw(MIN_ADDRESS + i as u8));
+        i += 1;
+    }
+    table
+};
+
+/// Returns the pre-encoded read frames for `address` from a static table.
+///
+/// Returns `None` for addresses outside `MIN_ADDRESS..=MAX_ADDRESS`, which can
+/// only be built through [`Driver::with_raw_address`](crate::Driver::with_raw_address).
+#[must_use]
+pub fn cached(address: Address) -> Option<&'static ReadFrames> {
+    READ_FRAMES.get(usize::from(address.get().wrapping_sub(MIN_ADDRESS)))
+}
+
+/// Builds a two-byte command frame: `[address, code, checksum]`.
+const fn frame2(address: Address, code: u8) -> [u8; 3] {
+    let bytes = [address.get(), code];
+    [bytes[0], bytes[1], calculate_checksum(&bytes)]
+}
+
+/// Builds a command frame with a zero argument: `[address, code, 0x00, checksum]`.
+const fn frame3(address: Address, code: u8) -> [u8; 4] {
+    let bytes = [address.get(), code, 0x00];
+    [bytes[0], bytes[1], bytes[2], calculate_checksum(&bytes)]
+}
+
+/// Stop the motor immediately.
+#[must_use]
+pub const fn stop(address: Address) -> [u8; 3] {
+    frame2(address, cmd::STOP)
+}
+
+/// Read the current encoder value.
+#[must_use]
+pub const fn read_encoder_value(address: Address) -> [u8; 3] {
+    frame2(address, cmd::READ_ENCODER_VALUE)
+}
+
+/// Read the total pulse count.
+#[must_use]
+pub const fn read_pulse_count(address: Address) -> [u8; 3] {
+    frame2(address, cmd::READ_PULSE_COUNT)
+}
+
+/// Read the motor shaft angle.
+#[must_use]
+pub const fn read_motor_shaft_angle(address: Address) -> [u8; 3] {
+    frame2(address, cmd::READ_MOTOR_SHAFT_ANGLE)
+}
+
+/// Read the motor shaft angle error.
+#[must_use]
+pub const fn read_motor_shaft_angle_error(address: Address) -> [u8; 3] {
+    frame2(address, cmd::READ_MOTOR_SHAFT_ANGLE_ERROR)
+}
+
+/// Read the EN pin status.
+#[must_use]
+pub const fn read_en_pin_status(address: Address) -> [u8; 3] {
+    frame2(address, cmd::READ_EN_PIN_STATUS)
+}
+
+/// Read the release status.
+#[must_use]
+pub const fn read_release_status(address: Address) -> [u8; 3] {
+    frame2(address, cmd::READ_RELEASE_STATUS)
+}
+
+/// Read the motor shaft status (Blocked/Unblocked/Error).
+#[must_use]
+pub const fn read_shaft_status(address: Address) -> [u8; 3] {
+    frame2(address, cmd::READ_SHAFT_STATUS)
+}
+
+/// Trigger encoder calibration.
+#[must_use]
+pub const fn calibrate_encoder(address: Address) -> [u8; 4] {
+    frame3(address, cmd::CALIBRATE_ENCODER)
+}
+
+/// Set the current position as zero.
+#[must_use]
+pub const fn set_current_as_zero(address: Address) -> [u8; 4] {
+    frame3(address, cmd::SET_CURRENT_AS_ZERO)
+}
+
+/// Initiate the return-to-zero sequence.
+#[must_use]
+pub const fn go_to_zero(address: Address) -> [u8; 4] {
+    frame3(address, cmd::GO_TO_ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Driver;
+
+    #[test]
+    fn test_frames_match_manual() {
+        let address = Address::DEFAULT;
+        // Examples from the manual
+        assert_eq!(read_encoder_value(address), [0xE0, 0x30, 0x10]);
+        assert_eq!(read_pulse_count(address), [0xE0, 0x33, 0x13]);
+        assert_eq!(read_motor_shaft_angle(address), [0xE0, 0x36, 0x16]);
+        assert_eq!(read_motor_shaft_angle_error(address), [0xE0, 0x39, 0x19]);
+        assert_eq!(read_en_pin_status(address), [0xE0, 0x3A, 0x1A]);
+        assert_eq!(read_shaft_status(address), [0xE0, 0x3E, 0x1E]);
+        assert_eq!(stop(address), [0xE0, 0xF7, 0xD7]);
+        assert_eq!(calibrate_encoder(address), [0xE0, 0x80, 0x00, 0x60]);
+        assert_eq!(set_current_as_zero(address), [0xE0, 0x91, 0x00, 0x71]);
+        assert_eq!(go_to_zero(address), [0xE0, 0x94, 0x00, 0x74]);
+    }
+
+    #[test]
+    fn test_frames_match_driver() {
+        let address = Address::new(0xE5).unwrap();
+        let driver = Driver::try_with_address(address.get()).unwrap();
+        assert_eq!(driver.stop().as_slice(), &stop(address));
+        assert_eq!(
+            driver.read_encoder_value().as_slice(),
+            &read_encoder_value(address)
+        );
+        assert_eq!(
+            driver.read_release_status().as_slice(),
+            &read_release_status(address)
+        );
+        assert_eq!(driver.go_to_zero().as_slice(), &go_to_zero(address));
+    }
+
+    #[test]
+    fn test_cached_read_frames() {
+        for raw in MIN_ADDRESS..=MAX_ADDRESS {
+            let address = Address::new(raw).unwrap();
+            let cached = cached(address).unwrap();
+            assert_eq!(*cached, ReadFrames::new(address));
+            assert_eq!(cached.encoder_value[0], raw);
+        }
+        assert_eq!(
+            cached(Address::DEFAULT).unwrap().shaft_status,
+            [0xE0, 0x3E, 0x1E]
+        );
+        assert!(cached(Address::from_raw(0x01)).is_none());
+        assert!(cached(Address::from_raw(0xFF)).is_none());
+    }
+}
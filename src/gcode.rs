@@ -0,0 +1,303 @@
+//! A G-code subset for one rotary axis (feature `gcode`).
+//!
+//! [`parse_line`] reads `G0`/`G1` (move), `G28` (home), `G92` (set position),
+//! `M17` (enable) and `M18` (disable). `X` words are absolute positions in
+//! degrees and `F` words are raw speed gears. [`Interpreter`] tracks the axis
+//! position and turns each instruction into the [`Command`] to send:
+//!
+//! ```
+//! use mks_servo42_rs::gcode::{parse_line, Interpreter};
+//! use mks_servo42_rs::{Command, Speed};
+//!
+//! let mut axis = Interpreter::new(16, Speed::new(10)?);
+//! let mut commands = [None; 3];
+//! for (line, slot) in ["M17", "G1 X90 F20 ; quarter turn", "G1 X45"]
+//!     .into_iter()
+//!     .zip(&mut commands)
+//! {
+//!     if let Some(instruction) = parse_line(line)? {
+//!         *slot = axis.execute(&instruction);
+//!     }
+//! }
+//! assert_eq!(commands[0], Some(Command::EnableMotor(true)));
+//! assert!(matches!(commands[2], Some(Command::RunMotor { pulses, .. }) if pulses.get() == 400));
+//! # Ok::<(), mks_servo42_rs::Error>(())
+//! ```
+
+use crate::{Command, Degrees, Error, Pulses, Result, RotationDirection, Speed};
+
+/// One parsed G-code line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+    /// `G0`/`G1`: move to `x` (absolute, degrees), at speed gear `feed` if given.
+    Move {
+        /// Target position; the axis stays put if absent.
+        x: Option<Degrees>,
+        /// New speed gear, kept for later moves.
+        feed: Option<Speed>,
+    },
+    /// `G28`: return to zero.
+    Home,
+    /// `G92`: declare the current position to be `X` (zero if absent).
+    SetPosition(Degrees),
+    /// `M17`: energize the motor.
+    Enable,
+    /// `M18`: release the motor.
+    Disable,
+}
+
+/// Parses one line of G-code.
+///
+/// Text after `;` is a comment. Returns `Ok(None)` for blank and comment-only lines.
+///
+/// # Errors
+/// Returns `Error::Unsupported` for codes and words outside the subset, and
+/// `Error::InvalidValue` for malformed or out-of-range numbers.
+pub fn parse_line(line: &str) -> Result<Option<Instruction>> {
+    let code = line.split(';').next().unwrap_or_default();
+    let mut words = code.split_whitespace();
+    let Some(command) = words.next() else {
+        return Ok(None);
+    };
+    let (letter, number) = split_word(command)?;
+    let number: u16 = number.parse().map_err(|_| Error::InvalidValue)?;
+
+    let mut x = None;
+    let mut feed = None;
+    for word in words {
+        match split_word(word)? {
+            ('X', value) => x = Some(Degrees(parse_f32(value)?)),
+            ('F', value) => {
+                let gear = value.parse().map_err(|_| Error::InvalidValue)?;
+                feed = Some(Speed::new(gear)?);
+            }
+            _ => return Err(Error::Unsupported),
+        }
+    }
+
+    let instruction = match (letter, number) {
+        ('G', 0 | 1) => Instruction::Move { x, feed },
+        ('G', 28) => Instruction::Home,
+        ('G', 92) => Instruction::SetPosition(x.unwrap_or_default()),
+        ('M', 17) => Instruction::Enable,
+        ('M', 18) => Instruction::Disable,
+        _ => return Err(Error::Unsupported),
+    };
+    Ok(Some(instruction))
+}
+
+/// Splits a word such as `X-12.5` into its upper-cased letter and value.
+fn split_word(word: &str) -> Result<(char, &str)> {
+    let mut chars = word.chars();
+    let letter = chars.next().ok_or(Error::InvalidValue)?;
+    Ok((letter.to_ascii_uppercase(), chars.as_str()))
+}
+
+fn parse_f32(value: &str) -> Result<f32> {
+    match value.parse::<f32>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        _ => Err(Error::InvalidValue),
+    }
+}
+
+/// Single-axis interpreter state: the commanded position and speed.
+///
+/// Positive moves turn clockwise. The position is what the interpreter has
+/// commanded, not a measurement; resynchronize it with `G92` after a stall.
+/// Moves are sent as the difference between whole-pulse targets, so rounding
+/// never accumulates over a run of short moves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interpreter {
+    position: Degrees,
+    pulses: i64,
+    microsteps: u16,
+    speed: Speed,
+}
+
+impl Interpreter {
+    /// Creates an interpreter at position zero for a board set to `microsteps`,
+    /// moving at `speed` until an `F` word changes it.
+    #[must_use]
+    pub const fn new(microsteps: u16, speed: Speed) -> Self {
+        Self {
+            position: Degrees(0.0),
+            pulses: 0,
+            microsteps,
+            speed,
+        }
+    }
+
+    /// Returns the commanded position.
+    #[must_use]
+    pub const fn position(&self) -> Degrees {
+        self.position
+    }
+
+    /// Returns the speed gear used for moves.
+    #[must_use]
+    pub const fn speed(&self) -> Speed {
+        self.speed
+    }
+
+    /// Applies `instruction` and returns the command to send, if any.
+    ///
+    /// `G92`, feed-only moves and moves that do not reach the next whole pulse
+    /// only update the state.
+    pub fn execute(&mut self, instruction: &Instruction) -> Option<Command> {
+        match *instruction {
+            Instruction::Move { x, feed } => {
+                if let Some(feed) = feed {
+                    self.speed = feed;
+                }
+                let target = x?;
+                let target_pulses = self.pulses_at(target);
+                let delta = target_pulses - self.pulses;
+                self.position = target;
+                self.pulses = target_pulses;
+                if delta == 0 {
+                    return None;
+                }
+                let direction = if delta < 0 {
+                    RotationDirection::CounterClockwise
+                } else {
+                    RotationDirection::Clockwise
+                };
+                Some(Command::RunMotor {
+                    direction,
+                    speed: self.speed,
+                    pulses: Pulses::new(u32::try_from(delta.unsigned_abs()).unwrap_or(u32::MAX)),
+                })
+            }
+            Instruction::Home => {
+                self.position = Degrees(0.0);
+                self.pulses = 0;
+                Some(Command::GoToZero)
+            }
+            Instruction::SetPosition(position) => {
+                self.position = position;
+                self.pulses = self.pulses_at(position);
+                None
+            }
+            Instruction::Enable => Some(Command::EnableMotor(true)),
+            Instruction::Disable => Some(Command::EnableMotor(false)),
+        }
+    }
+
+    /// Returns the signed whole-pulse position of `position`.
+    fn pulses_at(&self, position: Degrees) -> i64 {
+        let magnitude = i64::from(Pulses::from_degrees(position, self.microsteps).get());
+        if position.0 < 0.0 {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(parse_line("  ; comment"), Ok(None));
+        assert_eq!(parse_line(""), Ok(None));
+        assert_eq!(
+            parse_line("g01 x-12.5 f3"),
+            Ok(Some(Instruction::Move {
+                x: Some(Degrees(-12.5)),
+                feed: Some(Speed::new(3).unwrap()),
+            }))
+        );
+        assert_eq!(parse_line("G28"), Ok(Some(Instruction::Home)));
+        assert_eq!(
+            parse_line("G92"),
+            Ok(Some(Instruction::SetPosition(Degrees(0.0))))
+        );
+        assert_eq!(parse_line("M18"), Ok(Some(Instruction::Disable)));
+        assert_eq!(parse_line("G2 X10"), Err(Error::Unsupported));
+        assert_eq!(parse_line("G1 Y10"), Err(Error::Unsupported));
+        assert_eq!(parse_line("G1 Xabc"), Err(Error::InvalidValue));
+        assert_eq!(parse_line("G1 Xinf"), Err(Error::InvalidValue));
+        assert_eq!(parse_line("G1 F200"), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_interpreter() {
+        let mut axis = Interpreter::new(16, Speed::new(5).unwrap());
+        let run = |direction, gear, pulses| Command::RunMotor {
+            direction,
+            speed: Speed::new(gear).unwrap(),
+            pulses: Pulses::new(pulses),
+        };
+
+        let forward = Instruction::Move {
+            x: Some(Degrees(360.0)),
+            feed: None,
+        };
+        assert_eq!(
+            axis.execute(&forward),
+            Some(run(RotationDirection::Clockwise, 5, 3200))
+        );
+        assert_eq!(axis.execute(&forward), None);
+
+        let back = Instruction::Move {
+            x: Some(Degrees(-90.0)),
+            feed: Some(Speed::new(7).unwrap()),
+        };
+        assert_eq!(
+            axis.execute(&back),
+            Some(run(RotationDirection::CounterClockwise, 7, 4000))
+        );
+        assert_eq!(axis.position(), Degrees(-90.0));
+
+        assert_eq!(axis.execute(&Instruction::SetPosition(Degrees(0.0))), None);
+        assert_eq!(axis.execute(&Instruction::Home), Some(Command::GoToZero));
+        assert_eq!(axis.position(), Degrees(0.0));
+        assert_eq!(axis.speed().get(), 7);
+    }
+
+    #[test]
+    fn test_interpreter_sub_pulse_moves() {
+        // At 16 microsteps one pulse is 0.1125°.
+        let mut axis = Interpreter::new(16, Speed::new(5).unwrap());
+        let mut sent = 0;
+        for step in 1..=10u8 {
+            let x = Some(Degrees(f32::from(step) * 0.1));
+            if let Some(Command::RunMotor { pulses, .. }) =
+                axis.execute(&Instruction::Move { x, feed: None })
+            {
+                sent += pulses.get();
+            }
+        }
+        // 1° is 8.9 pulses: the run sends 9, not one per move.
+        assert_eq!(sent, 9);
+
+        // Steps under half a pulse still add up.
+        let mut axis = Interpreter::new(16, Speed::new(5).unwrap());
+        let mut sent = 0;
+        for step in 1..=4u8 {
+            let x = Some(Degrees(f32::from(step) * 0.05));
+            if let Some(Command::RunMotor { pulses, .. }) =
+                axis.execute(&Instruction::Move { x, feed: None })
+            {
+                sent += pulses.get();
+            }
+        }
+        assert_eq!(sent, 2);
+
+        // G92 re-bases the pulse position too.
+        axis.execute(&Instruction::SetPosition(Degrees(0.0)));
+        assert_eq!(
+            axis.execute(&Instruction::Move {
+                x: Some(Degrees(-0.1)),
+                feed: None,
+            }),
+            Some(Command::RunMotor {
+                direction: RotationDirection::CounterClockwise,
+                speed: Speed::new(5).unwrap(),
+                pulses: Pulses::new(1),
+            })
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{Command, Driver};
+
+/// A board connection that [`MotionGuard`] can stop.
+///
+/// Implement it for the type owning the transport; [`guarded`](Self::guarded)
+/// then wraps the connection for the duration of a motion sequence.
+pub trait StopTarget {
+    /// Returns the driver addressing the board.
+    fn driver(&self) -> &Driver;
+
+    /// Sends a frame, ignoring failures: the guard runs during unwinding and
+    /// has nobody to report to.
+    fn send(&mut self, frame: &[u8]);
+
+    /// Guards the connection until the returned value is dropped.
+    fn guarded(&mut self) -> MotionGuard<'_, Self>
+    where
+        Self: Sized,
+    {
+        MotionGuard::new(self)
+    }
+}
+
+/// Stops and disables the motor when dropped, even on panic or early return.
+///
+/// Dereferences to the guarded connection:
+///
+/// ```
+/// use mks_servo42_rs::{Driver, RotationDirection, Speed, StopTarget};
+///
+/// struct Port {
+///     driver: Driver,
+///     sent: usize,
+/// }
+///
+/// impl StopTarget for Port {
+///     fn driver(&self) -> &Driver {
+///         &self.driver
+///     }
+///
+///     fn send(&mut self, _frame: &[u8]) {
+///         self.sent += 1;
+///     }
+/// }
+///
+/// let mut port = Port { driver: Driver::default(), sent: 0 };
+/// {
+///     let mut motion = port.guarded();
+///     let run = motion.driver().run_with_constant_speed(RotationDirection::Clockwise, Speed::new(1)?)?;
+///     motion.send(&run);
+/// }
+/// assert_eq!(port.sent, 3); // run, then stop + disable
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct MotionGuard<'a, T: StopTarget> {
+    /// The guarded connection.
+    pub ctx: &'a mut T,
+}
+
+impl<'a, T: StopTarget> MotionGuard<'a, T> {
+    /// Guards `ctx` until the returned value is dropped.
+    pub const fn new(ctx: &'a mut T) -> Self {
+        Self { ctx }
+    }
+}
+
+impl<T: StopTarget> Drop for MotionGuard<'_, T> {
+    fn drop(&mut self) {
+        for command in [Command::Stop, Command::EnableMotor(false)] {
+            // Both commands exist in every protocol.
+            if let Ok(frame) = self.ctx.driver().encode(&command) {
+                self.ctx.send(&frame);
+            }
+        }
+    }
+}
+
+impl<T: StopTarget> Deref for MotionGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.ctx
+    }
+}
+
+impl<T: StopTarget> DerefMut for MotionGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ctx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Log {
+        driver: Driver,
+        heads: [[u8; 3]; 4],
+        count: usize,
+    }
+
+    impl StopTarget for Log {
+        fn driver(&self) -> &Driver {
+            &self.driver
+        }
+
+        fn send(&mut self, frame: &[u8]) {
+            self.heads[self.count].copy_from_slice(&frame[..3]);
+            self.count += 1;
+        }
+    }
+
+    #[test]
+    fn test_guarded_stops_after_use() {
+        let mut log = Log {
+            driver: Driver::try_with_address(0xE4).unwrap(),
+            heads: [[0; 3]; 4],
+            count: 0,
+        };
+        {
+            let mut motion = log.guarded();
+            let enable = motion.driver().enable_motor(true);
+            motion.send(&enable);
+            assert_eq!(motion.count, 1);
+        }
+        assert_eq!(
+            log.heads[..log.count],
+            [[0xE4, 0xF3, 0x01], [0xE4, 0xF7, 0xDB], [0xE4, 0xF3, 0x00]]
+        );
+    }
+
+    #[cfg(feature = "servo42d")]
+    #[test]
+    fn test_guarded_stops_servo42d() {
+        let mut log = Log {
+            driver: Driver::builder()
+                .protocol(crate::ProtocolVariant::Servo42D)
+                .build()
+                .unwrap(),
+            heads: [[0; 3]; 4],
+            count: 0,
+        };
+        drop(log.guarded());
+        assert_eq!(
+            log.heads[..log.count],
+            [[0xFA, 0x01, 0xF7], [0xFA, 0x01, 0xF3]]
+        );
+    }
+}
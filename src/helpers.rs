@@ -1,4 +1,5 @@
-use crate::Error;
+use crate::units::{Degrees, Revolutions};
+use crate::{Error, CURRENT_STEP_MA, MAX_CURRENT_INDEX};
 
 /// Standard steps per revolution for a 1.8° motor.
 pub const STEPS_PER_REV: f32 = 200.0;
@@ -17,26 +18,100 @@ pub struct EncoderValue {
 impl EncoderValue {
     /// Converts the full multi-turn encoder value to total degrees.
     #[must_use]
-    pub fn to_degrees(self) -> f32 {
+    pub fn to_degrees(self) -> Degrees {
         let degrees = (f32::from(self.value) / ENCODER_RESOLUTION) * 360.0;
-        (self.carry as f32 * 360.0) + degrees
+        Degrees((self.carry as f32 * 360.0) + degrees)
+    }
+
+    /// Converts the full multi-turn encoder value to total revolutions.
+    #[must_use]
+    pub fn to_revolutions(self) -> Revolutions {
+        Revolutions(self.carry as f32 + f32::from(self.value) / ENCODER_RESOLUTION)
     }
 }
 
 /// Utility to calculate required pulses for a given angle and microstepping level.
 #[must_use]
-pub fn angle_to_steps(angle: f32, microsteps: f32) -> u32 {
-    let steps = (angle / 360.0) * STEPS_PER_REV * microsteps;
+pub fn angle_to_steps(angle: Degrees, microsteps: f32) -> u32 {
+    let steps = (angle.0 / 360.0) * STEPS_PER_REV * microsteps;
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     {
         (steps + 0.5) as u32
     }
 }
 
+/// Returns the microsteps per full step selected by a subdivision byte.
+///
+/// The firmware takes the microstep count itself, so bytes 1 to 255 select
+/// that many microsteps and `0x00` selects 256.
+#[must_use]
+pub const fn microsteps_for_index(index: u8) -> u16 {
+    if index == 0 {
+        256
+    } else {
+        index as u16
+    }
+}
+
+/// Returns the subdivision byte selecting `microsteps` per full step.
+///
+/// Returns `None` unless `microsteps` is from 1 to 256.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub const fn index_for_microsteps(microsteps: u16) -> Option<u8> {
+    match microsteps {
+        256 => Some(0),
+        1..=255 => Some(microsteps as u8),
+        _ => None,
+    }
+}
+
+/// Returns the current in milliamps selected by a current limit index.
+///
+/// Each index step is `CURRENT_STEP_MA`. Returns `None` above `MAX_CURRENT_INDEX`.
+#[must_use]
+pub const fn current_ma_for_index(index: u8) -> Option<u16> {
+    if index > MAX_CURRENT_INDEX {
+        return None;
+    }
+    Some(index as u16 * CURRENT_STEP_MA)
+}
+
+/// Returns the current limit index for exactly `milliamps`.
+///
+/// Returns `None` unless `milliamps` is a multiple of `CURRENT_STEP_MA` within range.
+#[must_use]
+pub const fn index_for_current_ma(milliamps: u16) -> Option<u8> {
+    if !milliamps.is_multiple_of(CURRENT_STEP_MA) {
+        return None;
+    }
+    let index = milliamps / CURRENT_STEP_MA;
+    if index > MAX_CURRENT_INDEX as u16 {
+        return None;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    Some(index as u8)
+}
+
+/// Returns the current limit index closest to `milliamps`, clamped to `MAX_CURRENT_INDEX`.
+///
+/// Halfway values round up.
+#[must_use]
+pub const fn nearest_index_for_current_ma(milliamps: u16) -> u8 {
+    let index = (milliamps as u32 + CURRENT_STEP_MA as u32 / 2) / CURRENT_STEP_MA as u32;
+    if index > MAX_CURRENT_INDEX as u32 {
+        return MAX_CURRENT_INDEX;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    {
+        index as u8
+    }
+}
+
 /// Converts a 16-bit encoder value to degrees (0-360).
 #[must_use]
-pub fn encoder_val_to_degrees(val: u16) -> f32 {
-    (f32::from(val) / ENCODER_RESOLUTION) * 360.0
+pub fn encoder_val_to_degrees(val: u16) -> Degrees {
+    Degrees((f32::from(val) / ENCODER_RESOLUTION) * 360.0)
 }
 
 /// Parses raw serial feedback into an `EncoderValue`.
@@ -48,7 +123,7 @@ pub fn parse_encoder_response(data: &[u8]) -> Result<EncoderValue, Error> {
     while idx < data.len() {
         if data[idx] >= crate::MIN_ADDRESS
             && data[idx] <= crate::MAX_ADDRESS
-            && idx + 5 < data.len()
+            && idx + 7 < data.len()
         {
             let sum: u32 = data[idx..idx + 7].iter().map(|&b| u32::from(b)).sum();
             if (sum as u8) == data[idx + 7] {
@@ -82,8 +157,8 @@ pub struct ShaftErrValue {
 impl ShaftErrValue {
     /// Converts the full multi-turn encoder value to total degrees.
     #[must_use]
-    pub fn to_degrees(self) -> f32 {
-        f32::from(self.value) / 360.0
+    pub fn to_degrees(self) -> Degrees {
+        Degrees(f32::from(self.value) / 360.0)
     }
 }
 
@@ -136,8 +211,14 @@ pub struct MotorShaftAngle {
 impl MotorShaftAngle {
     /// Converts the angle to degrees.
     #[must_use]
-    pub fn to_degrees(self) -> f32 {
-        (self.value as f32 / 65536.0) * 360.0
+    pub fn to_degrees(self) -> Degrees {
+        Degrees((self.value as f32 / 65536.0) * 360.0)
+    }
+
+    /// Converts the angle to revolutions.
+    #[must_use]
+    pub fn to_revolutions(self) -> Revolutions {
+        Revolutions(self.value as f32 / 65536.0)
     }
 }
 
@@ -172,6 +253,50 @@ pub fn parse_motor_shaft_angle_response(data: &[u8]) -> Result<MotorShaftAngle,
     Err(Error::InvalidPacket)
 }
 
+/// Represents a received pulse count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PulseCount(pub i32);
+
+impl PulseCount {
+    /// Converts the count to degrees for a board set to `microsteps` driving
+    /// a motor with `steps_per_rev` full steps (see [`STEPS_PER_REV`]).
+    #[must_use]
+    pub fn to_degrees(self, microsteps: u16, steps_per_rev: f32) -> Degrees {
+        Degrees(self.to_revolutions(microsteps, steps_per_rev).0 * 360.0)
+    }
+
+    /// Converts the count to revolutions; see [`to_degrees`](Self::to_degrees).
+    #[must_use]
+    pub fn to_revolutions(self, microsteps: u16, steps_per_rev: f32) -> Revolutions {
+        let pulses_per_rev = f64::from(steps_per_rev) * f64::from(microsteps);
+        #[allow(clippy::cast_possible_truncation)]
+        Revolutions((f64::from(self.0) / pulses_per_rev) as f32)
+    }
+}
+
+/// Parses the pulse count response.
+///
+/// This function parses responses from the `READ_PULSE_COUNT` command (0x33).
+/// The response format is: `[slave_address, count_byte1, count_byte2, count_byte3, count_byte4, crc]`
+/// where the count is a signed 32-bit integer of received pulses.
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid pulse count response is found.
+pub fn parse_pulse_count_response(data: &[u8]) -> Result<PulseCount, Error> {
+    for window in data.windows(6) {
+        if !(crate::MIN_ADDRESS..=crate::MAX_ADDRESS).contains(&window[0]) {
+            continue;
+        }
+        if crate::calculate_checksum(&window[..5]) != window[5] {
+            continue;
+        }
+        return Ok(PulseCount(i32::from_be_bytes([
+            window[1], window[2], window[3], window[4],
+        ])));
+    }
+    Err(Error::InvalidPacket)
+}
+
 /// Represents EN pin status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EnPinStatus {
@@ -268,66 +393,222 @@ pub fn strip_leading_garbage(data: &[u8]) -> &[u8] {
         .map_or(&[], |idx| &data[idx..])
 }
 
-/// Parses standard success/failure response: `[address, status, checksum]`.
+/// Strips the echo of `request` from the start of `received`.
 ///
-/// Most MKS SERVO42 commands return a simple 3-byte response indicating success (0x01)
-/// or failure (0x00). This function scans for a valid packet and returns the response.
+/// On single-wire and some RS485 links every transmitted frame is read back
+/// before the reply. The echo must go first: a read request such as
+/// `[0xE0, 0x3A, 0x1A]` is itself a checksum-valid `[address, status, checksum]`
+/// frame, which the status parsers reject instead of skipping. If `received` holds only part of
+/// the echo so far, the result is empty; if it does not start with the echo,
+/// it is returned unchanged.
 ///
-/// # Errors
-/// Returns `Error::InvalidPacket` if no valid success/failure response is found.
-pub fn parse_success_response(data: &[u8]) -> Result<crate::Response, Error> {
-    if data.len() < 3 {
-        return Err(Error::InvalidPacket);
+/// # Example
+/// ```
+/// use mks_servo42_rs::strip_echo;
+/// let request = [0xE0, 0x3A, 0x1A];
+/// let received = [0xE0, 0x3A, 0x1A, 0xE0, 0x01, 0xE1];
+/// assert_eq!(strip_echo(&request, &received), &[0xE0, 0x01, 0xE1]);
+/// assert_eq!(strip_echo(&request, &received[..2]), &[]);
+/// ```
+#[must_use]
+pub fn strip_echo<'a>(request: &[u8], received: &'a [u8]) -> &'a [u8] {
+    if let Some(rest) = received.strip_prefix(request) {
+        rest
+    } else if request.starts_with(received) {
+        &[]
+    } else {
+        received
+    }
+}
+
+/// Acknowledgement of a write command: `[address, status, checksum]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ack {
+    /// Address of the answering board.
+    pub address: crate::Address,
+    /// Reported result.
+    pub response: crate::Response,
+}
+
+impl Ack {
+    /// Returns `true` if the board reported success.
+    #[must_use]
+    pub const fn is_success(self) -> bool {
+        self.response.is_success()
+    }
+
+    /// Encodes the acknowledgement as the board would send it.
+    ///
+    /// Lets a dry-run or mock transport answer write commands without a board.
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; 3] {
+        let address = self.address.get();
+        let status = self.response as u8;
+        [address, status, address.wrapping_add(status)]
     }
+}
+
+/// Parses a write command acknowledgement, keeping the answering address.
+///
+/// Scans for the first checksum-valid `[address, status, checksum]` frame, so
+/// leading garbage is skipped.
+///
+/// ```
+/// use mks_servo42_rs::{parse_ack, Response};
+///
+/// let ack = parse_ack(&[0x00, 0xE2, 0x01, 0xE3]).unwrap();
+/// assert_eq!((ack.address.get(), ack.response), (0xE2, Response::Success));
+/// ```
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid acknowledgement is found.
+pub fn parse_ack(data: &[u8]) -> Result<Ack, Error> {
     for window in data.windows(3) {
-        let addr = window[0];
-        if !(crate::MIN_ADDRESS..=crate::MAX_ADDRESS).contains(&addr) {
+        let Ok(address) = crate::Address::new(window[0]) else {
             continue;
-        }
+        };
         let status = window[1];
         let checksum = window[2];
-        if checksum != addr.wrapping_add(status) {
+        if checksum != window[0].wrapping_add(status) {
             continue;
         }
-        return crate::Response::try_from(status).map_err(|_| Error::InvalidPacket);
+        let response = crate::Response::try_from(status).map_err(|_| Error::InvalidPacket)?;
+        return Ok(Ack { address, response });
     }
     Err(Error::InvalidPacket)
 }
 
+/// Parses standard success/failure response: `[address, status, checksum]`.
+///
+/// Most MKS SERVO42 commands return a simple 3-byte response indicating success (0x01)
+/// or failure (0x00). This function scans for a valid packet and returns the response.
+/// Use [`parse_ack`] to also learn which board answered.
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid success/failure response is found.
+pub fn parse_success_response(data: &[u8]) -> Result<crate::Response, Error> {
+    parse_ack(data).map(|ack| ack.response)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_strip_echo() {
+        let request = [0xE0, 0x3A, 0x1A];
+        let received = [0xE0, 0x3A, 0x1A, 0xE0, 0x01, 0xE1];
+        assert_eq!(
+            parse_en_pin_status_response(&received),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            parse_en_pin_status_response(strip_echo(&request, &received)),
+            Ok(EnPinStatus::Enabled)
+        );
+        assert_eq!(strip_echo(&request, &received[3..]), &received[3..]);
+        assert_eq!(strip_echo(&request, &[]), &[]);
+    }
+
     #[test]
     fn test_angle_to_steps() {
-        assert_eq!(angle_to_steps(360.0, 1.0), 200);
-        assert_eq!(angle_to_steps(360.0, 4.0), 800);
-        assert_eq!(angle_to_steps(180.0, 4.0), 400);
+        assert_eq!(angle_to_steps(Degrees(360.0), 1.0), 200);
+        assert_eq!(angle_to_steps(Degrees(360.0), 4.0), 800);
+        assert_eq!(angle_to_steps(Degrees(180.0), 4.0), 400);
+    }
+
+    #[test]
+    fn test_current_index_mapping() {
+        assert_eq!(current_ma_for_index(0), Some(0));
+        assert_eq!(current_ma_for_index(6), Some(1200));
+        assert_eq!(current_ma_for_index(MAX_CURRENT_INDEX), Some(3000));
+        assert_eq!(current_ma_for_index(MAX_CURRENT_INDEX + 1), None);
+
+        assert_eq!(index_for_current_ma(1200), Some(6));
+        assert_eq!(index_for_current_ma(1250), None);
+        assert_eq!(index_for_current_ma(3200), None);
+
+        assert_eq!(nearest_index_for_current_ma(1250), 6);
+        assert_eq!(nearest_index_for_current_ma(1300), 7);
+        assert_eq!(nearest_index_for_current_ma(90), 0);
+        assert_eq!(nearest_index_for_current_ma(u16::MAX), MAX_CURRENT_INDEX);
+    }
+
+    #[test]
+    fn test_pulse_count_conversions() {
+        let count = PulseCount(-800);
+        assert_eq!(count.to_revolutions(16, STEPS_PER_REV), Revolutions(-0.25));
+        assert_eq!(count.to_degrees(16, STEPS_PER_REV), Degrees(-90.0));
+        // 0.9° motor at full steps.
+        assert_eq!(PulseCount(600).to_degrees(1, 400.0), Degrees(540.0));
+    }
+
+    #[test]
+    fn test_parse_pulse_count_response() {
+        // Example from the manual: e0 00 00 01 00 e1 (256 pulses)
+        assert_eq!(
+            parse_pulse_count_response(&[0xE0, 0x00, 0x00, 0x01, 0x00, 0xE1]),
+            Ok(PulseCount(256))
+        );
+        assert_eq!(
+            parse_pulse_count_response(&[0xFF, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF, 0xDC]),
+            Ok(PulseCount(-1))
+        );
+        assert_eq!(
+            parse_pulse_count_response(&[0xE0, 0x00, 0x00, 0x01, 0x00, 0xE2]),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            parse_pulse_count_response(&[0xE0, 0x00]),
+            Err(Error::InvalidPacket)
+        );
+    }
+
+    #[test]
+    fn test_subdivision_index_mapping() {
+        // Manual examples: 0x07, 0x1A and 0x4E select 7, 26 and 78; 0x00 selects 256.
+        assert_eq!(microsteps_for_index(0x07), 7);
+        assert_eq!(microsteps_for_index(0x1A), 26);
+        assert_eq!(microsteps_for_index(0x4E), 78);
+        assert_eq!(microsteps_for_index(0x00), 256);
+        assert_eq!(index_for_microsteps(4), Some(0x04));
+
+        for index in 0..=u8::MAX {
+            let microsteps = microsteps_for_index(index);
+            assert_eq!(index_for_microsteps(microsteps), Some(index));
+        }
+        assert_eq!(index_for_microsteps(0), None);
+        assert_eq!(index_for_microsteps(257), None);
     }
 
     #[test]
     fn test_encoder_val_to_degrees() {
-        assert_eq!(encoder_val_to_degrees(0), 0.0);
-        assert_eq!(encoder_val_to_degrees(32768), 180.0);
-        assert_eq!(encoder_val_to_degrees(65535), (65535.0 / 65536.0) * 360.0);
+        assert_eq!(encoder_val_to_degrees(0), Degrees(0.0));
+        assert_eq!(encoder_val_to_degrees(32768), Degrees(180.0));
+        assert_eq!(
+            encoder_val_to_degrees(65535),
+            Degrees((65535.0 / 65536.0) * 360.0)
+        );
     }
 
     #[test]
     fn test_encoder_value_to_degrees() {
         let ev = EncoderValue { carry: 1, value: 0 }; // 1 full rotation
-        assert_eq!(ev.to_degrees(), 360.0);
+        assert_eq!(ev.to_degrees(), Degrees(360.0));
+        assert_eq!(ev.to_revolutions(), Revolutions(1.0));
 
         let ev = EncoderValue {
             carry: -1,
             value: 0,
         }; // -1 full rotation
-        assert_eq!(ev.to_degrees(), -360.0);
+        assert_eq!(ev.to_degrees(), Degrees(-360.0));
 
         let ev = EncoderValue {
             carry: 0,
             value: 32768,
         }; // 180 degrees
-        assert_eq!(ev.to_degrees(), 180.0);
+        assert_eq!(ev.to_degrees(), Degrees(180.0));
+        assert_eq!(ev.to_revolutions(), Revolutions(0.5));
     }
 
     #[test]
@@ -336,7 +617,7 @@ mod tests {
         let res = parse_encoder_response(&data).unwrap();
         assert_eq!(res.carry, 0);
         assert_eq!(res.value, 0x4000);
-        assert_eq!(res.to_degrees(), 90.0);
+        assert_eq!(res.to_degrees(), Degrees(90.0));
     }
 
     #[test]
@@ -354,6 +635,41 @@ mod tests {
         assert!(matches!(res, Err(Error::InvalidPacket)));
     }
 
+    #[test]
+    fn test_parse_ack() {
+        let ack = parse_ack(&[0xFF, 0xE5, 0x00, 0xE5]).unwrap();
+        assert_eq!(ack.address.get(), 0xE5);
+        assert_eq!(ack.response, crate::Response::Failure);
+        assert!(!ack.is_success());
+        assert!(parse_ack(&[0xE0, 0x01, 0xE1]).unwrap().is_success());
+        assert_eq!(parse_ack(&[0xE0, 0x01, 0xE2]), Err(Error::InvalidPacket));
+        assert_eq!(parse_ack(&[0xE0, 0x02, 0xE2]), Err(Error::InvalidPacket));
+        assert_eq!(parse_ack(&[0xE0]), Err(Error::InvalidPacket));
+    }
+
+    #[test]
+    fn test_ack_to_bytes() {
+        let ack = Ack {
+            address: crate::Address::new(0xE3).unwrap(),
+            response: crate::Response::Success,
+        };
+        assert_eq!(ack.to_bytes(), [0xE3, 0x01, 0xE4]);
+        assert_eq!(parse_ack(&ack.to_bytes()), Ok(ack));
+    }
+
+    #[test]
+    fn test_parse_encoder_response_truncated() {
+        let data = [0xE0, 0x00, 0x00, 0x00, 0x00, 0x40];
+        assert!(matches!(
+            parse_encoder_response(&data),
+            Err(Error::InvalidPacket)
+        ));
+        assert!(matches!(
+            parse_encoder_response(&data[..4]),
+            Err(Error::InvalidPacket)
+        ));
+    }
+
     #[test]
     fn test_parse_motor_shaft_angle_error() {
         // Example from documentation: e0 00 B7 97 00 (error 1°)
@@ -432,21 +748,21 @@ mod tests {
         let data = [0xE0, 0x00, 0x00, 0x40, 0x00, 0x20];
         let angle = parse_motor_shaft_angle_response(&data).unwrap();
         assert_eq!(angle.value, 0x4000); // 16384 encoder units
-        assert_eq!(angle.to_degrees(), 90.0);
+        assert_eq!(angle.to_degrees(), Degrees(90.0));
 
         // Test zero angle
         // Checksum: 0xE0 + 0x00 + 0x00 + 0x00 + 0x00 = 0xE0
         let data = [0xE0, 0x00, 0x00, 0x00, 0x00, 0xE0];
         let angle = parse_motor_shaft_angle_response(&data).unwrap();
         assert_eq!(angle.value, 0);
-        assert_eq!(angle.to_degrees(), 0.0);
+        assert_eq!(angle.to_degrees(), Degrees(0.0));
 
         // Test 180° angle (32768 encoder units = 0x8000)
         // Checksum: 0xE0 + 0x00 + 0x00 + 0x80 + 0x00 = 0x160 → low byte 0x60
         let data = [0xE0, 0x00, 0x00, 0x80, 0x00, 0x60];
         let angle = parse_motor_shaft_angle_response(&data).unwrap();
         assert_eq!(angle.value, 0x8000);
-        assert_eq!(angle.to_degrees(), 180.0);
+        assert_eq!(angle.to_degrees(), Degrees(180.0));
 
         // Test negative angle (two's complement)
         // -90° = 65536 - 16384 = 49152 = 0xC000
@@ -454,7 +770,8 @@ mod tests {
         let data = [0xE0, 0xFF, 0xFF, 0xC0, 0x00, 0x9E];
         let angle = parse_motor_shaft_angle_response(&data).unwrap();
         assert_eq!(angle.value, -16384); // -90° in encoder units
-        assert_eq!(angle.to_degrees(), -90.0);
+        assert_eq!(angle.to_degrees(), Degrees(-90.0));
+        assert_eq!(angle.to_revolutions(), Revolutions(-0.25));
     }
 
     #[test]
@@ -463,7 +780,7 @@ mod tests {
         let data = [0xFF, 0xFE, 0xE0, 0x00, 0x00, 0x40, 0x00, 0x20];
         let angle = parse_motor_shaft_angle_response(&data).unwrap();
         assert_eq!(angle.value, 0x4000);
-        assert_eq!(angle.to_degrees(), 90.0);
+        assert_eq!(angle.to_degrees(), Degrees(90.0));
     }
 
     #[test]
@@ -6,23 +6,68 @@
 
 #![no_std]
 
+mod address;
+pub mod analyzer;
+pub mod axis;
+mod batch;
+mod builder;
+#[cfg(feature = "capture")]
+pub mod capture;
+pub mod clock;
+mod command;
+mod config;
+pub mod defaults;
+pub mod diagnostics;
 pub mod enums;
 mod errors;
+pub mod frames;
+#[cfg(feature = "gcode")]
+pub mod gcode;
+mod guard;
 pub mod helpers;
+pub mod motion;
+mod pair;
+mod policy;
+mod profile;
 pub mod response;
-
+mod resync;
+mod servo;
+#[cfg(feature = "servo42d")]
+pub mod servo42d;
+mod settings;
+pub mod telemetry;
+pub mod test_vectors;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod units;
+
+pub use address::Address;
+pub use batch::CommandBatch;
+pub use builder::DriverBuilder;
+pub use command::{Command, CommandBytes, CommandClass};
+pub use config::MotorConfig;
 pub use enums::{
-    BaudRate, EnLogic, MotorType, RotationDirection, SaveClearStatus, ShaftStatus, WorkMode,
-    ZeroMode,
+    BaudRate, EnLogic, MotorType, ProtectionState, ProtocolVariant, RotationDirection,
+    SaveClearStatus, ShaftStatus, WorkMode, ZeroMode,
 };
 pub use errors::Error;
+pub use guard::{MotionGuard, StopTarget};
 pub use helpers::{
-    angle_to_steps, encoder_val_to_degrees, parse_en_pin_status_response, parse_encoder_response,
-    parse_motor_shaft_angle_error, parse_motor_shaft_angle_response, parse_shaft_status_response,
-    parse_success_response, strip_leading_garbage, EnPinStatus, EncoderValue, MotorShaftAngle,
-    ShaftErrValue,
+    angle_to_steps, current_ma_for_index, encoder_val_to_degrees, index_for_current_ma,
+    index_for_microsteps, microsteps_for_index, nearest_index_for_current_ma, parse_ack,
+    parse_en_pin_status_response, parse_encoder_response, parse_motor_shaft_angle_error,
+    parse_motor_shaft_angle_response, parse_pulse_count_response, parse_shaft_status_response,
+    parse_success_response, strip_echo, strip_leading_garbage, Ack, EnPinStatus, EncoderValue,
+    MotorShaftAngle, PulseCount, ShaftErrValue,
 };
+pub use pair::MirroredPair;
+pub use policy::CommandPolicy;
+pub use profile::DeviceProfile;
 pub use response::{InvalidResponse, Response};
+pub use resync::{Resync, ResyncPolicy};
+pub use servo::ServoDriver;
+pub use settings::Settings;
+pub use units::{Degrees, Milliamps, Pid, Pulses, Revolutions, Speed, TorqueLimit};
 
 /// Default hardware address for MKS SERVO42 targets.
 pub const DEFAULT_ADDRESS: u8 = 0xE0;
@@ -35,8 +80,6 @@ pub const MAX_ADDRESS: u8 = 0xE9;
 pub const MAX_SPEED: u8 = 0x7F;
 /// Maximum index for current limit settings.
 pub const MAX_CURRENT_INDEX: u8 = 0x0F;
-/// Maximum index for subdivision (microstepping).
-pub const MAX_SUBDIVISION_INDEX: u8 = 0x08;
 /// Maximum speed index for return-to-zero.
 pub const MAX_ZERO_SPEED: u8 = 0x04;
 
@@ -46,7 +89,46 @@ pub const CURRENT_STEP_MA: u16 = 200;
 /// Maximum torque limit (0x4B0).
 pub const MAX_TORQUE_LIMIT: u16 = 0x4B0;
 
-const CMD_BUFFER_SIZE: usize = 10;
+/// Maximum acceleration (ACC) accepted by `set_acceleration`.
+///
+/// The manual gives no upper bound, only a warning that a too large ACC can damage
+/// the driver board, so this is a conservative crate limit (default is 0x11E).
+pub const MAX_ACCELERATION: u16 = 0x400;
+
+/// Maximum position loop PID coefficient accepted by [`Pid`].
+///
+/// The manual only documents the defaults, so this keeps every coefficient
+/// positive when read back as a signed 16-bit value.
+pub const MAX_PID_COEFFICIENT: u16 = 0x7FFF;
+
+/// Reply timeout that covers ordinary commands, in milliseconds.
+pub const DEFAULT_REPLY_TIMEOUT_MS: u32 = 500;
+
+/// Reply timeout for encoder calibration, in milliseconds.
+///
+/// The board answers only once calibration has finished, which takes 40-60 s.
+pub const CALIBRATION_TIMEOUT_MS: u32 = 70_000;
+
+/// Reply timeout for return to zero, in milliseconds.
+///
+/// The board answers once the shaft reaches zero; the time depends on the
+/// distance and the zero speed, so this is a generous bound.
+pub const HOMING_TIMEOUT_MS: u32 = 60_000;
+
+/// Pause between saving status and re-enabling the motor, in milliseconds.
+///
+/// The board disables itself while it writes flash; the manual gives no
+/// figure, so this leaves a comfortable margin.
+pub const SAVE_SETTLE_MS: u32 = 100;
+
+/// Default frame buffer size of [`Driver`] and [`CommandBytes`].
+pub const CMD_BUFFER_SIZE: usize = 10;
+
+/// Smallest frame buffer accepted by [`CommandBytes`].
+///
+/// Sized for the longest SERVO42D frame even without the `servo42d` feature,
+/// so enabling the feature never rejects a buffer size that compiled before.
+const MAX_FRAME_LEN: usize = 10;
 
 mod cmd {
     pub const READ_ENCODER_VALUE: u8 = 0x30;
@@ -87,12 +169,20 @@ mod cmd {
 
 /// Main driver for communicating with an MKS SERVO42 motor.
 ///
-/// This struct manages the slave address and an internal buffer used to
-/// construct serial commands.
+/// This struct holds the slave address and protocol settings. Each command
+/// builder returns an owned [`CommandBytes`] frame, so several commands can be
+/// prepared at once from a shared reference.
+///
+/// `N` is the frame buffer size, [`CMD_BUFFER_SIZE`] by default. Use
+/// [`with_buffer_size`](Self::with_buffer_size) to pick another one; sizes too
+/// small for the longest frame are rejected at compile time.
 #[derive(Debug, Copy, Clone)]
-pub struct Driver {
-    address: u8,
-    buffer: [u8; CMD_BUFFER_SIZE],
+pub struct Driver<const N: usize = CMD_BUFFER_SIZE> {
+    address: Address,
+    protocol: ProtocolVariant,
+    profile: DeviceProfile,
+    microsteps: Option<u16>,
+    motor_type: MotorType,
 }
 
 type Result<T> = core::result::Result<T, Error>;
@@ -101,81 +191,226 @@ impl Default for Driver {
     /// Creates a new driver with the default address (0xE0).
     fn default() -> Self {
         Self {
-            address: DEFAULT_ADDRESS,
-            buffer: [0; CMD_BUFFER_SIZE],
+            address: Address::DEFAULT,
+            protocol: ProtocolVariant::Servo42C,
+            profile: DeviceProfile::SERVO42C,
+            microsteps: None,
+            motor_type: MotorType::Deg18,
         }
     }
 }
 
 impl Driver {
+    /// Returns a builder for configuring a driver.
+    #[must_use]
+    pub fn builder() -> DriverBuilder {
+        DriverBuilder::new()
+    }
+
     /// Creates a new driver instance with a specific target address.
+    #[deprecated(
+        since = "2.0.0",
+        note = "use `try_with_address`, or `with_raw_address` for non-standard firmware"
+    )]
     #[must_use]
     pub fn with_address(address: u8) -> Self {
+        Self::with_raw_address(address)
+    }
+
+    /// Creates a driver for the board at `address`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `address` is outside `MIN_ADDRESS..=MAX_ADDRESS`.
+    pub fn try_with_address(address: u8) -> Result<Self> {
+        Ok(Self {
+            address: Address::new(address)?,
+            ..Default::default()
+        })
+    }
+
+    /// Creates a driver for `address` without range checks.
+    ///
+    /// For firmware that answers outside the documented `0xE0..=0xE9` range;
+    /// standard boards ignore such frames.
+    #[must_use]
+    pub fn with_raw_address(address: u8) -> Self {
         Self {
-            address,
+            address: Address::from_raw(address),
             ..Default::default()
         }
     }
+}
+
+impl<const N: usize> Driver<N> {
+    /// Converts the driver to one producing frames with an `M`-byte buffer.
+    #[must_use]
+    pub const fn with_buffer_size<const M: usize>(self) -> Driver<M> {
+        Driver {
+            address: self.address,
+            protocol: self.protocol,
+            profile: self.profile,
+            microsteps: self.microsteps,
+            motor_type: self.motor_type,
+        }
+    }
+
+    /// Selects the board family whose limits validate generated commands.
+    #[must_use]
+    pub const fn with_profile(mut self, profile: DeviceProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Returns the board family whose limits validate generated commands.
+    #[must_use]
+    pub const fn profile(&self) -> &DeviceProfile {
+        &self.profile
+    }
+
+    /// Returns the slave address targeted by generated commands.
+    #[must_use]
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Re-targets the driver at a different slave address.
+    pub fn set_address(&mut self, address: Address) {
+        self.address = address;
+    }
+
+    /// Returns the protocol variant used to encode commands.
+    #[must_use]
+    pub const fn protocol(&self) -> ProtocolVariant {
+        self.protocol
+    }
+
+    /// Sets the subdivision the board is known to use, e.g. its factory default.
+    #[must_use]
+    pub const fn with_microsteps(mut self, microsteps: u16) -> Self {
+        self.microsteps = Some(microsteps);
+        self
+    }
+
+    /// Sets the step angle of the attached motor (1.8° unless changed).
+    #[must_use]
+    pub const fn with_motor_type(mut self, motor_type: MotorType) -> Self {
+        self.motor_type = motor_type;
+        self
+    }
+
+    /// Returns the tracked subdivision, if known.
+    #[must_use]
+    pub const fn microsteps(&self) -> Option<u16> {
+        self.microsteps
+    }
+
+    /// Returns the step angle of the attached motor.
+    #[must_use]
+    pub const fn motor_type(&self) -> MotorType {
+        self.motor_type
+    }
+
+    /// Tracks the subdivision set by a command the board has acknowledged.
+    ///
+    /// Call it once the reply to a [`set_subdivision`](Self::set_subdivision)
+    /// frame reports success; other commands are ignored.
+    ///
+    /// ```
+    /// use mks_servo42_rs::{Command, Degrees, Driver};
+    ///
+    /// let mut driver = Driver::default();
+    /// assert_eq!(driver.pulses_for(Degrees(90.0)), None);
+    /// let frame = driver.encode(&Command::SetSubdivision(16))?;
+    /// // ...send `frame` and check the acknowledgement...
+    /// driver.record(&Command::SetSubdivision(16));
+    /// assert_eq!(driver.pulses_for(Degrees(90.0)).map(|p| p.get()), Some(800));
+    /// # Ok::<(), mks_servo42_rs::Error>(())
+    /// ```
+    pub fn record(&mut self, command: &Command) {
+        if let Command::SetSubdivision(index) = *command {
+            self.microsteps = Some(microsteps_for_index(index));
+        }
+    }
+
+    /// Converts an angle to pulses at the tracked subdivision and motor type.
+    ///
+    /// Returns `None` while the subdivision is unknown.
+    #[must_use]
+    pub fn pulses_for(&self, degrees: Degrees) -> Option<Pulses> {
+        let pulses = degrees.0.abs() / 360.0
+            * f32::from(self.motor_type.steps_per_rev())
+            * f32::from(self.microsteps?);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Some(Pulses::new((pulses + 0.5) as u32))
+    }
+
+    /// Converts a pulse count read back from the board to degrees at the
+    /// tracked subdivision and motor type.
+    ///
+    /// Returns `None` while the subdivision is unknown.
+    #[must_use]
+    pub fn degrees_for(&self, count: PulseCount) -> Option<Degrees> {
+        let steps_per_rev = f32::from(self.motor_type.steps_per_rev());
+        Some(count.to_degrees(self.microsteps?, steps_per_rev))
+    }
 
     /// Generates a command to enable or disable the motor.
-    pub fn enable_motor(&mut self, enable: bool) -> &[u8] {
-        self.build_command(&[self.address, cmd::ENABLE_MOTOR, u8::from(enable)])
+    pub fn enable_motor(&self, enable: bool) -> CommandBytes<N> {
+        self.frame(
+            &Command::EnableMotor(enable),
+            &[self.address.get(), cmd::ENABLE_MOTOR, u8::from(enable)],
+        )
     }
 
     /// Generates a command to run the motor at a constant speed.
     ///
     /// # Errors
-    /// Returns `Error::InvalidValue` if speed exceeds `MAX_SPEED`.
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
     pub fn run_with_constant_speed(
-        &mut self,
+        &self,
         direction: RotationDirection,
-        speed: u8,
-    ) -> Result<&[u8]> {
-        if speed > MAX_SPEED {
-            return Err(Error::InvalidValue);
-        }
-        let dir_mask = match direction {
-            RotationDirection::Clockwise => 0x00,
-            RotationDirection::CounterClockwise => 0x80,
-        };
-        Ok(self.build_command(&[self.address, cmd::RUN_WITH_CONSTANT_SPEED, speed | dir_mask]))
+        speed: Speed,
+    ) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
+        Ok(self.build_command(&[
+            self.address.get(),
+            cmd::RUN_WITH_CONSTANT_SPEED,
+            direction_and_speed(direction, speed),
+        ]))
     }
 
     /// Generates a command to stop the motor immediately.
-    pub fn stop(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::STOP])
+    pub fn stop(&self) -> CommandBytes<N> {
+        self.frame(&Command::Stop, &[self.address.get(), cmd::STOP])
     }
 
     /// Generates a command to save or clear the current status.
     ///
     /// This command is used to save or clear the status set by the `set_work_mode` command.
     /// After saving successfully, the driver board will be disabled and needs to be re-enabled.
-    pub fn save_clear_status(&mut self, operation: SaveClearStatus) -> &[u8] {
-        self.build_command(&[self.address, cmd::SAVE_CLEAR_STATUS, operation as u8])
+    pub fn save_clear_status(&self, operation: SaveClearStatus) -> CommandBytes<N> {
+        self.frame(
+            &Command::SaveClearStatus(operation),
+            &[self.address.get(), cmd::SAVE_CLEAR_STATUS, operation as u8],
+        )
     }
 
     /// Generates a command to move the motor to a specific position (relative pulses).
     ///
     /// # Errors
-    /// Returns `Error::InvalidValue` if speed exceeds `MAX_SPEED`.
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
     pub fn run_motor(
-        &mut self,
+        &self,
         direction: RotationDirection,
-        speed: u8,
-        pulses: u32,
-    ) -> Result<&[u8]> {
-        if speed > MAX_SPEED {
-            return Err(Error::InvalidValue);
-        }
-        let dir_mask = match direction {
-            RotationDirection::Clockwise => 0x00,
-            RotationDirection::CounterClockwise => 0x80,
-        };
-        let pulse_bytes = pulses.to_be_bytes();
+        speed: Speed,
+        pulses: Pulses,
+    ) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
+        let pulse_bytes = pulses.get().to_be_bytes();
         Ok(self.build_command(&[
-            self.address,
+            self.address.get(),
             cmd::RUN_MOTOR,
-            speed | dir_mask,
+            direction_and_speed(direction, speed),
             pulse_bytes[0],
             pulse_bytes[1],
             pulse_bytes[2],
@@ -183,146 +418,320 @@ impl Driver {
         ]))
     }
 
+    /// Generates a relative move by `degrees` at the tracked subdivision.
+    ///
+    /// Only the magnitude of `degrees` is used; `direction` picks the sense.
+    /// See [`pulses_for`](Self::pulses_for).
+    ///
+    /// ```
+    /// use mks_servo42_rs::{Degrees, Driver, RotationDirection, Speed};
+    ///
+    /// let driver = Driver::default().with_microsteps(16);
+    /// let frame = driver.run_motor_angle(RotationDirection::Clockwise, Speed::new(2)?, Degrees(90.0))?;
+    /// assert_eq!(frame.as_slice(), &[0xE0, 0xFD, 0x02, 0x00, 0x00, 0x03, 0x20, 0x02]);
+    /// # Ok::<(), mks_servo42_rs::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the subdivision is unknown, or
+    /// `Error::Unsupported` for a SERVO42D driver.
+    pub fn run_motor_angle(
+        &self,
+        direction: RotationDirection,
+        speed: Speed,
+        degrees: Degrees,
+    ) -> Result<CommandBytes<N>> {
+        let pulses = self.pulses_for(degrees).ok_or(Error::InvalidValue)?;
+        self.run_motor(direction, speed, pulses)
+    }
+
     /// Generates a command to trigger encoder calibration.
-    pub fn calibrate_encoder(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::CALIBRATE_ENCODER, 0x00])
+    pub fn calibrate_encoder(&self) -> CommandBytes<N> {
+        self.frame(
+            &Command::CalibrateEncoder,
+            &[self.address.get(), cmd::CALIBRATE_ENCODER, 0x00],
+        )
     }
 
-    /// Generates a command to set the current limit index.
+    /// Generates a command to set the current limit.
+    ///
+    /// The 42C current is rounded down to the nearest `CURRENT_STEP_MA` step.
+    pub fn set_current_limit(&self, current: Milliamps) -> CommandBytes<N> {
+        self.frame(
+            &Command::SetCurrentLimit(current),
+            &[self.address.get(), cmd::SET_CURRENT_LIMIT, current.index()],
+        )
+    }
+
+    /// Generates a command to set the current limit from a value in mA.
+    ///
+    /// Unlike [`set_current_limit`](Self::set_current_limit), the bound is the
+    /// driver's [`DeviceProfile`], so larger boards can use their full range.
+    /// The 42C current is rounded down to the nearest `CURRENT_STEP_MA` step.
     ///
     /// # Errors
-    /// Returns `Error::InvalidValue` if index exceeds `MAX_CURRENT_INDEX`.
-    pub fn set_current_limit(&mut self, index: u8) -> Result<&[u8]> {
-        if index > MAX_CURRENT_INDEX {
+    /// Returns `Error::InvalidValue` if `milliamps` exceeds the profile's `max_current_ma`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn set_current_limit_ma(&self, milliamps: u16) -> Result<CommandBytes<N>> {
+        if milliamps > self.profile.max_current_ma {
             return Err(Error::InvalidValue);
         }
-        Ok(self.build_command(&[self.address, cmd::SET_CURRENT_LIMIT, index]))
+        #[cfg(feature = "servo42d")]
+        if let Some(driver) = self.servo42d() {
+            return driver.set_working_current_ma(milliamps);
+        }
+        let index = (milliamps / CURRENT_STEP_MA) as u8;
+        Ok(self.build_command(&[self.address.get(), cmd::SET_CURRENT_LIMIT, index]))
     }
 
     /// Generates a command to set the subdivision (microstepping) level.
     ///
-    /// # Errors
-    /// Returns `Error::InvalidValue` if index exceeds `MAX_SUBDIVISION_INDEX`.
-    pub fn set_subdivision(&mut self, step_index: u8) -> Result<&[u8]> {
-        if step_index > MAX_SUBDIVISION_INDEX {
-            return Err(Error::InvalidValue);
-        }
-        Ok(self.build_command(&[self.address, cmd::SET_SUBDIVISION, step_index]))
+    /// `step_index` is the microstep count, with `0x00` selecting 256; see
+    /// [`microsteps_for_index`].
+    pub fn set_subdivision(&self, step_index: u8) -> CommandBytes<N> {
+        self.frame(
+            &Command::SetSubdivision(step_index),
+            &[self.address.get(), cmd::SET_SUBDIVISION, step_index],
+        )
     }
 
     /// Generates a command to set the enable logic.
-    pub fn set_enable_logic(&mut self, logic: EnLogic) -> &[u8] {
-        self.build_command(&[self.address, cmd::SET_EN_LOGIC, logic as u8])
+    pub fn set_enable_logic(&self, logic: EnLogic) -> CommandBytes<N> {
+        self.frame(
+            &Command::SetEnableLogic(logic),
+            &[self.address.get(), cmd::SET_EN_LOGIC, logic as u8],
+        )
     }
 
     /// Generates a command to set the motor direction polarity.
-    pub fn set_direction(&mut self, direction: RotationDirection) -> &[u8] {
-        self.build_command(&[self.address, cmd::SET_DIRECTION, direction as u8])
+    pub fn set_direction(&self, direction: RotationDirection) -> CommandBytes<N> {
+        self.frame(
+            &Command::SetDirection(direction),
+            &[self.address.get(), cmd::SET_DIRECTION, direction as u8],
+        )
     }
 
     /// Generates a command to enable or disable automatic screen off.
-    pub fn set_auto_screen_off(&mut self, enable: bool) -> &[u8] {
-        self.build_command(&[self.address, cmd::SET_AUTO_SCREEN_OFF, u8::from(!enable)])
+    pub fn set_auto_screen_off(&self, enable: bool) -> CommandBytes<N> {
+        self.frame(
+            &Command::SetAutoScreenOff(enable),
+            &[
+                self.address.get(),
+                cmd::SET_AUTO_SCREEN_OFF,
+                u8::from(enable),
+            ],
+        )
     }
 
     /// Generates a command to enable or disable stall protection.
-    pub fn set_stall_protection(&mut self, enable: bool) -> &[u8] {
-        self.build_command(&[self.address, cmd::SET_PROTECTION, u8::from(!enable)])
+    pub fn set_stall_protection(&self, enable: bool) -> CommandBytes<N> {
+        self.frame(
+            &Command::SetStallProtection(enable),
+            &[self.address.get(), cmd::SET_PROTECTION, u8::from(enable)],
+        )
     }
 
     /// Generates a command to enable or disable step interpolation.
-    pub fn set_interpolation(&mut self, enable: bool) -> &[u8] {
-        self.build_command(&[self.address, cmd::SET_INTERPOLATION, u8::from(!enable)])
+    pub fn set_interpolation(&self, enable: bool) -> CommandBytes<N> {
+        self.frame(
+            &Command::SetInterpolation(enable),
+            &[self.address.get(), cmd::SET_INTERPOLATION, u8::from(enable)],
+        )
     }
 
     /// Generates a command to set the return-to-zero mode.
-    pub fn set_zero_mode(&mut self, mode: ZeroMode) -> &[u8] {
-        self.build_command(&[self.address, cmd::SET_ZERO_MODE, mode as u8])
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
+    pub fn set_zero_mode(&self, mode: ZeroMode) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
+        Ok(self.build_command(&[self.address.get(), cmd::SET_ZERO_MODE, mode as u8]))
     }
 
     /// Generates a command to set the current position as zero.
-    pub fn set_current_as_zero(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::SET_CURRENT_AS_ZERO, 0x00])
+    pub fn set_current_as_zero(&self) -> CommandBytes<N> {
+        self.frame(
+            &Command::SetCurrentAsZero,
+            &[self.address.get(), cmd::SET_CURRENT_AS_ZERO, 0x00],
+        )
     }
 
     /// Generates a command to set the return-to-zero speed.
     ///
     /// # Errors
-    /// Returns `Error::InvalidValue` if speed index exceeds `MAX_ZERO_SPEED`.
-    pub fn set_zero_speed(&mut self, speed: u8) -> Result<&[u8]> {
+    /// Returns `Error::InvalidValue` if speed index exceeds `MAX_ZERO_SPEED`, or
+    /// `Error::Unsupported` for a SERVO42D driver.
+    pub fn set_zero_speed(&self, speed: u8) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
         if speed > MAX_ZERO_SPEED {
             return Err(Error::InvalidValue);
         }
-        Ok(self.build_command(&[self.address, cmd::SET_ZERO_SPEED, speed]))
+        Ok(self.build_command(&[self.address.get(), cmd::SET_ZERO_SPEED, speed]))
     }
 
     /// Generates a command to initiate return-to-zero sequence.
-    pub fn go_to_zero(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::GO_TO_ZERO, 0x00])
+    pub fn go_to_zero(&self) -> CommandBytes<N> {
+        self.frame(
+            &Command::GoToZero,
+            &[self.address.get(), cmd::GO_TO_ZERO, 0x00],
+        )
     }
 
     /// Generates a command to set the return-to-zero direction.
-    pub fn set_zero_direction(&mut self, direction: RotationDirection) -> &[u8] {
-        self.build_command(&[self.address, cmd::SET_ZERO_DIRECTION, direction as u8])
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
+    pub fn set_zero_direction(&self, direction: RotationDirection) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
+        Ok(self.build_command(&[self.address.get(), cmd::SET_ZERO_DIRECTION, direction as u8]))
     }
 
     /// Generates a command to set the position loop Proportional (Kp) coefficient.
-    pub fn set_position_kp(&mut self, value: u16) -> &[u8] {
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
+    pub fn set_position_kp(&self, value: u16) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
         let bytes = value.to_be_bytes();
-        self.build_command(&[self.address, cmd::SET_POSITION_KP, bytes[0], bytes[1]])
+        Ok(self.build_command(&[self.address.get(), cmd::SET_POSITION_KP, bytes[0], bytes[1]]))
     }
 
     /// Generates a command to set the position loop Integral (Ki) coefficient.
-    pub fn set_position_ki(&mut self, value: u16) -> &[u8] {
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
+    pub fn set_position_ki(&self, value: u16) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
         let bytes = value.to_be_bytes();
-        self.build_command(&[self.address, cmd::SET_POSITION_KI, bytes[0], bytes[1]])
+        Ok(self.build_command(&[self.address.get(), cmd::SET_POSITION_KI, bytes[0], bytes[1]]))
     }
 
     /// Generates a command to set the position loop Derivative (Kd) coefficient.
-    pub fn set_position_kd(&mut self, value: u16) -> &[u8] {
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
+    pub fn set_position_kd(&self, value: u16) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
         let bytes = value.to_be_bytes();
-        self.build_command(&[self.address, cmd::SET_POSITION_KD, bytes[0], bytes[1]])
+        Ok(self.build_command(&[self.address.get(), cmd::SET_POSITION_KD, bytes[0], bytes[1]]))
     }
 
-    /// Generates a command to set the motor acceleration.
-    pub fn set_acceleration(&mut self, value: u16) -> &[u8] {
-        let bytes = value.to_be_bytes();
-        self.build_command(&[self.address, cmd::SET_ACCELERATION, bytes[0], bytes[1]])
+    /// Generates the Kp, Ki and Kd commands for a validated set of coefficients.
+    ///
+    /// The frames are returned in that order; each one is acknowledged separately.
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
+    pub fn set_pid(&self, pid: &Pid) -> Result<[CommandBytes<N>; 3]> {
+        Ok([
+            self.set_position_kp(pid.kp())?,
+            self.set_position_ki(pid.ki())?,
+            self.set_position_kd(pid.kd())?,
+        ])
     }
 
-    /// Generates a command to set the maximum torque limit.
+    /// Generates the commands applying a [`MotorConfig`]: Kp, Ki, Kd, ACC and MaxT.
     ///
     /// # Errors
-    /// Returns `Error::InvalidValue` if value exceeds `MAX_TORQUE_LIMIT`.
-    pub fn set_max_torque(&mut self, value: u16) -> Result<&[u8]> {
-        if value > MAX_TORQUE_LIMIT {
+    /// Returns `Error::InvalidValue` if the acceleration exceeds the profile's
+    /// `max_acceleration`, or `Error::Unsupported` for a SERVO42D driver.
+    pub fn apply_config(&self, config: &MotorConfig) -> Result<[CommandBytes<N>; 5]> {
+        let [kp, ki, kd] = self.set_pid(&config.pid)?;
+        Ok([
+            kp,
+            ki,
+            kd,
+            self.set_acceleration(config.acceleration)?,
+            self.set_max_torque(config.max_torque)?,
+        ])
+    }
+
+    /// Generates the full apply-and-persist sequence for a [`MotorConfig`].
+    ///
+    /// The five [`apply_config`](Self::apply_config) writes are followed by
+    /// `SaveClearStatus::Save` and `enable_motor(true)`, since the board
+    /// disables itself after saving. Send the frames in order, each after the
+    /// previous one is acknowledged, and wait [`SAVE_SETTLE_MS`] before the
+    /// last one.
+    ///
+    /// # Errors
+    /// Same as [`apply_config`](Self::apply_config).
+    pub fn apply_and_save(&self, config: &MotorConfig) -> Result<[CommandBytes<N>; 7]> {
+        let [kp, ki, kd, acc, torque] = self.apply_config(config)?;
+        Ok([
+            kp,
+            ki,
+            kd,
+            acc,
+            torque,
+            self.save_clear_status(SaveClearStatus::Save),
+            self.enable_motor(true),
+        ])
+    }
+
+    /// Generates a command to set the motor acceleration.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if value exceeds the profile's
+    /// `max_acceleration`, or `Error::Unsupported` for a SERVO42D driver.
+    pub fn set_acceleration(&self, value: u16) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
+        if value > self.profile.max_acceleration {
             return Err(Error::InvalidValue);
         }
         let bytes = value.to_be_bytes();
-        Ok(self.build_command(&[self.address, cmd::SET_MAX_TORQUE, bytes[0], bytes[1]]))
+        Ok(self.build_command(&[
+            self.address.get(),
+            cmd::SET_ACCELERATION,
+            bytes[0],
+            bytes[1],
+        ]))
+    }
+
+    /// Generates a command to set the maximum torque limit.
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
+    pub fn set_max_torque(&self, limit: TorqueLimit) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
+        let bytes = limit.get().to_be_bytes();
+        Ok(self.build_command(&[self.address.get(), cmd::SET_MAX_TORQUE, bytes[0], bytes[1]]))
     }
 
     /// Generates a command to read the motor shaft status (Blocked/Unblocked/Error).
-    pub fn read_shaft_status(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::READ_SHAFT_STATUS])
+    pub fn read_shaft_status(&self) -> CommandBytes<N> {
+        self.frame(
+            &Command::ReadShaftStatus,
+            &[self.address.get(), cmd::READ_SHAFT_STATUS],
+        )
     }
 
     /// Generates a command to read the current encoder value.
-    pub fn read_encoder_value(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::READ_ENCODER_VALUE])
+    pub fn read_encoder_value(&self) -> CommandBytes<N> {
+        self.frame(
+            &Command::ReadEncoderValue,
+            &[self.address.get(), cmd::READ_ENCODER_VALUE],
+        )
     }
 
     /// Generates a command to read the total pulse count.
-    pub fn read_pulse_count(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::READ_PULSE_COUNT])
+    pub fn read_pulse_count(&self) -> CommandBytes<N> {
+        self.frame(
+            &Command::ReadPulseCount,
+            &[self.address.get(), cmd::READ_PULSE_COUNT],
+        )
     }
 
     /// Generates a command to read the motor shaft angle.
     ///
     /// Returns a 4-byte signed integer representing the angle in encoder units.
     /// One full rotation corresponds to 0-65535.
-    pub fn read_motor_shaft_angle(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::READ_MOTOR_SHAFT_ANGLE])
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` for a SERVO42D driver.
+    pub fn read_motor_shaft_angle(&self) -> Result<CommandBytes<N>> {
+        self.require_c_frames()?;
+        Ok(self.build_command(&[self.address.get(), cmd::READ_MOTOR_SHAFT_ANGLE]))
     }
 
     /// Generates a command to read the EN pin status.
@@ -331,30 +740,188 @@ impl Driver {
     /// - 0x01: Enable
     /// - 0x02: Disable  
     /// - 0x00: Error
-    pub fn read_en_pin_status(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::READ_EN_PIN_STATUS])
+    pub fn read_en_pin_status(&self) -> CommandBytes<N> {
+        self.frame(
+            &Command::ReadEnPinStatus,
+            &[self.address.get(), cmd::READ_EN_PIN_STATUS],
+        )
     }
 
     /// Generates a command to read the motor shaft angle error.
-    pub fn read_motor_shaft_angle_error(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::READ_MOTOR_SHAFT_ANGLE_ERROR])
+    pub fn read_motor_shaft_angle_error(&self) -> CommandBytes<N> {
+        self.frame(
+            &Command::ReadMotorShaftAngleError,
+            &[self.address.get(), cmd::READ_MOTOR_SHAFT_ANGLE_ERROR],
+        )
     }
 
     /// Generates a command to read the release status of the motor.
-    pub fn read_release_status(&mut self) -> &[u8] {
-        self.build_command(&[self.address, cmd::READ_RELEASE_STATUS])
+    pub fn read_release_status(&self) -> CommandBytes<N> {
+        self.frame(
+            &Command::ReadReleaseStatus,
+            &[self.address.get(), cmd::READ_RELEASE_STATUS],
+        )
     }
 
-    fn build_command(&mut self, cmd: &[u8]) -> &[u8] {
-        let len = cmd.len();
-        self.buffer[..len].copy_from_slice(cmd);
-        self.buffer[len] = calculate_checksum(cmd);
-        &self.buffer[..=len]
+    /// Encodes a typed [`Command`] for this driver's address.
+    ///
+    /// The frame follows the driver's [`ProtocolVariant`], so one binary can
+    /// drive a mixed fleet. The per-command builders follow it too, and those
+    /// without a SERVO42D equivalent return `Error::Unsupported`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the command carries an out-of-range index,
+    /// or `Error::Unsupported` if the protocol has no such command.
+    pub fn encode(&self, command: &Command) -> Result<CommandBytes<N>> {
+        #[cfg(feature = "servo42d")]
+        if let Some(driver) = self.servo42d() {
+            return driver.encode(command);
+        }
+        Ok(match *command {
+            Command::EnableMotor(enable) => self.enable_motor(enable),
+            Command::RunWithConstantSpeed { direction, speed } => {
+                self.run_with_constant_speed(direction, speed)?
+            }
+            Command::Stop => self.stop(),
+            Command::SaveClearStatus(operation) => self.save_clear_status(operation),
+            Command::RunMotor {
+                direction,
+                speed,
+                pulses,
+            } => self.run_motor(direction, speed, pulses)?,
+            Command::CalibrateEncoder => self.calibrate_encoder(),
+            Command::SetCurrentLimit(current) => self.set_current_limit(current),
+            Command::SetSubdivision(step_index) => self.set_subdivision(step_index),
+            Command::SetEnableLogic(logic) => self.set_enable_logic(logic),
+            Command::SetDirection(direction) => self.set_direction(direction),
+            Command::SetAutoScreenOff(enable) => self.set_auto_screen_off(enable),
+            Command::SetStallProtection(enable) => self.set_stall_protection(enable),
+            Command::SetInterpolation(enable) => self.set_interpolation(enable),
+            Command::SetZeroMode(mode) => self.set_zero_mode(mode)?,
+            Command::SetCurrentAsZero => self.set_current_as_zero(),
+            Command::SetZeroSpeed(speed) => self.set_zero_speed(speed)?,
+            Command::GoToZero => self.go_to_zero(),
+            Command::SetZeroDirection(direction) => self.set_zero_direction(direction)?,
+            Command::SetPositionKp(value) => self.set_position_kp(value)?,
+            Command::SetPositionKi(value) => self.set_position_ki(value)?,
+            Command::SetPositionKd(value) => self.set_position_kd(value)?,
+            Command::SetAcceleration(value) => self.set_acceleration(value)?,
+            Command::SetMaxTorque(limit) => self.set_max_torque(limit)?,
+            Command::ReadShaftStatus => self.read_shaft_status(),
+            Command::ReadEncoderValue => self.read_encoder_value(),
+            Command::ReadPulseCount => self.read_pulse_count(),
+            Command::ReadMotorShaftAngle => self.read_motor_shaft_angle()?,
+            Command::ReadEnPinStatus => self.read_en_pin_status(),
+            Command::ReadMotorShaftAngleError => self.read_motor_shaft_angle_error(),
+            Command::ReadReleaseStatus => self.read_release_status(),
+        })
+    }
+
+    /// Encodes a typed [`Command`] into a `heapless::Vec` (feature `heapless`).
+    ///
+    /// # Errors
+    /// Same as [`encode`](Self::encode).
+    #[cfg(feature = "heapless")]
+    pub fn encode_vec(&self, command: &Command) -> Result<heapless::Vec<u8, N>> {
+        self.encode(command).map(CommandBytes::into_vec)
     }
+
+    /// Returns `true` if the board answers commands from this driver.
+    ///
+    /// Only SERVO42D boards have a broadcast address (0x00), and they never
+    /// reply to it; the SERVO42C firmware answers every frame.
+    #[must_use]
+    pub fn expects_reply(&self) -> bool {
+        #[cfg(feature = "servo42d")]
+        if self.protocol == ProtocolVariant::Servo42D {
+            return self.address.get() != servo42d::BROADCAST_ADDRESS;
+        }
+        true
+    }
+
+    /// Returns `true` if the driver's protocol can encode `command`.
+    #[must_use]
+    pub fn supports(&self, command: &Command) -> bool {
+        !matches!(self.encode(command), Err(Error::Unsupported))
+    }
+
+    /// Encodes a typed [`Command`] into the start of `out`, e.g. a DMA TX buffer.
+    ///
+    /// Returns the number of bytes written. `out` is left untouched on error.
+    ///
+    /// # Errors
+    /// Same as [`encode`](Self::encode), or `Error::BufferTooSmall` if `out`
+    /// cannot hold the frame.
+    pub fn encode_into(&self, command: &Command, out: &mut [u8]) -> Result<usize> {
+        self.encode(command)?.write_to(out)
+    }
+
+    /// Returns the SERVO42D encoder for this driver, if it speaks that protocol.
+    #[cfg(feature = "servo42d")]
+    fn servo42d(&self) -> Option<servo42d::Driver<N>> {
+        if self.protocol != ProtocolVariant::Servo42D {
+            return None;
+        }
+        let model =
+            if self.profile.max_current_ma > servo42d::Model::Servo42D.max_working_current_ma() {
+                servo42d::Model::Servo57D
+            } else {
+                servo42d::Model::Servo42D
+            };
+        // Like the C frames, leave gating to the caller's `CommandPolicy`.
+        Some(
+            servo42d::Driver::new(self.address.get())
+                .with_model(model)
+                .with_policy(CommandPolicy::SAFE.allow_destructive())
+                .with_buffer_size::<N>(),
+        )
+    }
+
+    /// Returns `Error::Unsupported` unless the driver speaks 42C frames.
+    const fn require_c_frames(&self) -> Result<()> {
+        #[cfg(feature = "servo42d")]
+        if matches!(self.protocol, ProtocolVariant::Servo42D) {
+            return Err(Error::Unsupported);
+        }
+        Ok(())
+    }
+
+    /// Builds a command both protocols support; `c_frame` is its 42C form.
+    #[cfg_attr(not(feature = "servo42d"), allow(unused_variables))]
+    fn frame(&self, command: &Command, c_frame: &[u8]) -> CommandBytes<N> {
+        #[cfg(feature = "servo42d")]
+        if let Some(driver) = self.servo42d() {
+            // Cannot fail: the policy allows everything, and the SERVO42D
+            // encodes each of these commands, taking any `Milliamps`.
+            if let Ok(frame) = driver.encode(command) {
+                return frame;
+            }
+        }
+        self.build_command(c_frame)
+    }
+
+    fn build_command(&self, cmd: &[u8]) -> CommandBytes<N> {
+        CommandBytes::new(cmd)
+    }
+}
+
+/// Packs direction into the top bit and the speed gear into the low 7 bits.
+const fn direction_and_speed(direction: RotationDirection, speed: Speed) -> u8 {
+    let dir_mask = match direction {
+        RotationDirection::Clockwise => 0x00,
+        RotationDirection::CounterClockwise => 0x80,
+    };
+    speed.get() | dir_mask
 }
 
-fn calculate_checksum(bytes: &[u8]) -> u8 {
-    bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b))
+const fn calculate_checksum(bytes: &[u8]) -> u8 {
+    let mut sum = 0u8;
+    let mut i = 0;
+    while i < bytes.len() {
+        sum = sum.wrapping_add(bytes[i]);
+        i += 1;
+    }
+    sum
 }
 
 #[cfg(test)]
@@ -366,77 +933,480 @@ mod tests {
         assert_eq!(0xD7, calculate_checksum(&[0xE0, 0xF6, 0x01]));
     }
 
+    #[test]
+    fn test_tracked_subdivision() {
+        let mut driver = Driver::default();
+        assert_eq!(driver.microsteps(), None);
+        assert_eq!(driver.degrees_for(PulseCount(3200)), None);
+
+        driver.record(&Command::Stop);
+        assert_eq!(driver.microsteps(), None);
+        driver.record(&Command::SetSubdivision(0));
+        assert_eq!(driver.microsteps(), Some(256));
+        driver.record(&Command::SetSubdivision(32));
+        assert_eq!(driver.microsteps(), Some(32));
+        assert_eq!(driver.pulses_for(Degrees(-45.0)), Some(Pulses::new(800)));
+        assert_eq!(driver.degrees_for(PulseCount(-3200)), Some(Degrees(-180.0)));
+
+        let driver = Driver::default()
+            .with_microsteps(16)
+            .with_motor_type(MotorType::Deg09)
+            .with_buffer_size::<16>();
+        assert_eq!(driver.pulses_for(Degrees(90.0)), Some(Pulses::new(1600)));
+        assert_eq!(driver.motor_type(), MotorType::Deg09);
+    }
+
+    #[test]
+    fn test_run_motor_angle() {
+        let driver = Driver::default();
+        let speed = Speed::new(1).unwrap();
+        assert_eq!(
+            driver
+                .run_motor_angle(RotationDirection::Clockwise, speed, Degrees(1.0))
+                .err(),
+            Some(Error::InvalidValue)
+        );
+        let driver = driver.with_microsteps(4);
+        let frame = driver
+            .run_motor_angle(RotationDirection::CounterClockwise, speed, Degrees(-360.0))
+            .unwrap();
+        assert_eq!(
+            frame.as_slice(),
+            driver
+                .run_motor(RotationDirection::CounterClockwise, speed, Pulses::new(800))
+                .unwrap()
+                .as_slice()
+        );
+    }
+
     #[test]
     fn test_default_address() {
         let driver = Driver::default();
-        assert_eq!(driver.address, DEFAULT_ADDRESS);
+        assert_eq!(driver.address().get(), DEFAULT_ADDRESS);
     }
 
     #[test]
     fn test_with_address() {
-        let driver = Driver::with_address(0xE5);
-        assert_eq!(driver.address, 0xE5);
+        let driver = Driver::try_with_address(0xE5).unwrap();
+        assert_eq!(driver.address().get(), 0xE5);
 
         // Test edge addresses
-        let driver_min = Driver::with_address(MIN_ADDRESS);
-        assert_eq!(driver_min.address, MIN_ADDRESS);
+        let driver_min = Driver::try_with_address(MIN_ADDRESS).unwrap();
+        assert_eq!(driver_min.address().get(), MIN_ADDRESS);
+
+        let driver_max = Driver::try_with_address(MAX_ADDRESS).unwrap();
+        assert_eq!(driver_max.address().get(), MAX_ADDRESS);
+
+        assert_eq!(
+            Driver::try_with_address(MAX_ADDRESS + 1).map(|d| d.address()),
+            Err(Error::InvalidValue)
+        );
+        let raw = Driver::with_raw_address(0x01);
+        assert_eq!(raw.stop().as_slice(), &[0x01, 0xF7, 0xF8]);
+    }
 
-        let driver_max = Driver::with_address(MAX_ADDRESS);
-        assert_eq!(driver_max.address, MAX_ADDRESS);
+    #[test]
+    fn test_commands_are_independent() {
+        let driver = Driver::default();
+        let shared = &driver;
+        // Two prepared commands can be held at once from a shared reference
+        let stop = shared.stop();
+        let disable = shared.enable_motor(false);
+        assert_eq!(stop.as_slice(), &[0xE0, 0xF7, 0xD7]);
+        assert_eq!(disable.as_slice(), &[0xE0, 0xF3, 0x00, 0xD3]);
     }
 
     #[test]
-    fn test_set_subdivision_invalid_value() {
-        let mut driver = Driver::default();
-        // MAX_SUBDIVISION_INDEX is 0x08, so 0x09 should fail
-        let result = driver.set_subdivision(MAX_SUBDIVISION_INDEX + 1);
-        assert!(matches!(result, Err(Error::InvalidValue)));
+    fn test_encode_matches_builders() {
+        let driver = Driver::try_with_address(0xE2).unwrap();
+        let run = Command::RunMotor {
+            direction: RotationDirection::CounterClockwise,
+            speed: Speed::new(0x12).unwrap(),
+            pulses: Pulses::new(0x1234),
+        };
+        assert_eq!(
+            driver.encode(&run),
+            driver.run_motor(
+                RotationDirection::CounterClockwise,
+                Speed::new(0x12).unwrap(),
+                Pulses::new(0x1234)
+            )
+        );
+        assert_eq!(driver.encode(&Command::Stop), Ok(driver.stop()));
+        assert_eq!(
+            driver.encode(&Command::SetZeroSpeed(MAX_ZERO_SPEED + 1)),
+            Err(Error::InvalidValue)
+        );
+    }
 
-        // Valid value should succeed
-        let result = driver.set_subdivision(MAX_SUBDIVISION_INDEX);
-        assert!(result.is_ok());
+    #[test]
+    fn test_with_buffer_size() {
+        let driver = Driver::try_with_address(0xE1)
+            .unwrap()
+            .with_buffer_size::<16>();
+        let cmd: CommandBytes<16> = driver
+            .run_motor(
+                RotationDirection::Clockwise,
+                Speed::new(1).unwrap(),
+                Pulses::new(0x0100),
+            )
+            .unwrap();
+        assert_eq!(
+            cmd.as_slice(),
+            Driver::try_with_address(0xE1)
+                .unwrap()
+                .run_motor(
+                    RotationDirection::Clockwise,
+                    Speed::new(1).unwrap(),
+                    Pulses::new(0x0100)
+                )
+                .unwrap()
+                .as_slice()
+        );
+        assert_eq!(driver.address().get(), 0xE1);
     }
 
+    #[cfg(feature = "heapless")]
     #[test]
-    fn test_set_current_limit_invalid_value() {
-        let mut driver = Driver::default();
-        // MAX_CURRENT_INDEX is 0x0F, so 0x10 should fail
-        let result = driver.set_current_limit(MAX_CURRENT_INDEX + 1);
-        assert!(matches!(result, Err(Error::InvalidValue)));
+    fn test_encode_vec() {
+        let driver = Driver::default();
+        let vec = driver.encode_vec(&Command::ReadEncoderValue).unwrap();
+        assert_eq!(vec.as_slice(), driver.read_encoder_value().as_slice());
+        assert_eq!(vec.capacity(), CMD_BUFFER_SIZE);
+        let vec: heapless::Vec<u8, 16> = driver.with_buffer_size::<16>().stop().into();
+        assert_eq!(vec.as_slice(), &[0xE0, 0xF7, 0xD7]);
+        assert_eq!(
+            driver.encode_vec(&Command::SetZeroSpeed(MAX_ZERO_SPEED + 1)),
+            Err(Error::InvalidValue)
+        );
+    }
 
-        // Valid value should succeed
-        let result = driver.set_current_limit(MAX_CURRENT_INDEX);
-        assert!(result.is_ok());
+    #[test]
+    fn test_supports() {
+        let driver = Driver::default();
+        assert!(driver.supports(&Command::SetPositionKp(1)));
+        assert!(driver.supports(&Command::SetSubdivision(9)));
     }
 
+    #[cfg(feature = "servo42d")]
     #[test]
-    fn test_run_motor_invalid_speed() {
-        let mut driver = Driver::default();
-        // MAX_SPEED is 0x7F, so 0x80 should fail
-        let result = driver.run_motor(RotationDirection::Clockwise, MAX_SPEED + 1, 100);
-        assert!(matches!(result, Err(Error::InvalidValue)));
+    fn test_encode_servo42d() {
+        let driver = Driver::builder()
+            .protocol(ProtocolVariant::Servo42D)
+            .build()
+            .unwrap();
+        let cmd = driver.encode(&Command::Stop).unwrap();
+        assert_eq!(cmd.as_slice(), &[0xFA, 0x01, 0xF7, 0xF2]);
+        assert!(!driver.supports(&Command::SetPositionKp(1)));
+        assert_eq!(
+            driver.encode(&Command::SetPositionKp(1)),
+            Err(Error::Unsupported)
+        );
+        let mut out = [0u8; 4];
+        assert_eq!(driver.encode_into(&Command::Stop, &mut out), Ok(4));
+        assert!(driver.encode(&Command::CalibrateEncoder).is_ok());
+    }
+
+    #[cfg(feature = "servo42d")]
+    #[test]
+    fn test_builders_servo42d() {
+        let driver = Driver::builder()
+            .protocol(ProtocolVariant::Servo42D)
+            .build()
+            .unwrap();
+        assert_eq!(driver.stop().as_slice(), &[0xFA, 0x01, 0xF7, 0xF2]);
+        let shared = [
+            (driver.enable_motor(false), Command::EnableMotor(false)),
+            (driver.go_to_zero(), Command::GoToZero),
+            (driver.set_subdivision(16), Command::SetSubdivision(16)),
+            (driver.read_encoder_value(), Command::ReadEncoderValue),
+            (
+                driver.set_current_limit(Milliamps::MAX),
+                Command::SetCurrentLimit(Milliamps::MAX),
+            ),
+        ];
+        for (frame, command) in shared {
+            assert_eq!(Ok(frame), driver.encode(&command));
+        }
+
+        let speed = Speed::new(1).unwrap();
+        let direction = RotationDirection::Clockwise;
+        assert_eq!(
+            driver.run_motor(direction, speed, Pulses::new(1)),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(
+            driver.run_with_constant_speed(direction, speed),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(
+            driver
+                .with_microsteps(16)
+                .run_motor_angle(direction, speed, Degrees(90.0)),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(driver.set_pid(&Pid::DEFAULT), Err(Error::Unsupported));
+        assert_eq!(
+            driver.apply_config(&MotorConfig::factory()),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(
+            driver.apply_and_save(&MotorConfig::factory()),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(driver.read_motor_shaft_angle(), Err(Error::Unsupported));
+
+        // The D firmware takes the current in mA, up to the profile's limit.
+        assert_eq!(
+            driver.set_current_limit_ma(1600).unwrap().as_slice(),
+            &[0xFA, 0x01, 0x83, 0x06, 0x40, 0xC4]
+        );
+        let driver = driver.with_profile(DeviceProfile::SERVO57D);
+        assert!(driver.set_current_limit_ma(5200).is_ok());
+        assert_eq!(driver.set_current_limit_ma(5201), Err(Error::InvalidValue));
+    }
+
+    #[cfg(feature = "servo42d")]
+    #[test]
+    fn test_broadcast() {
+        let all = Driver::builder()
+            .protocol(ProtocolVariant::Servo42D)
+            .address(servo42d::BROADCAST_ADDRESS)
+            .build()
+            .unwrap();
+        assert_eq!(
+            all.encode(&Command::Stop).unwrap().as_slice(),
+            &[0xFA, 0x00, 0xF7, 0xF1]
+        );
+        assert!(!all.expects_reply());
+        assert!(Driver::default().expects_reply());
+    }
 
-        // Valid speed should succeed
-        let result = driver.run_motor(RotationDirection::Clockwise, MAX_SPEED, 100);
-        assert!(result.is_ok());
+    #[test]
+    fn test_encode_into() {
+        let driver = Driver::default();
+        let mut tx = [0u8; 16];
+        let len = driver
+            .encode_into(
+                &Command::RunWithConstantSpeed {
+                    direction: RotationDirection::Clockwise,
+                    speed: Speed::new(1).unwrap(),
+                },
+                &mut tx,
+            )
+            .unwrap();
+        assert_eq!(&tx[..len], &[0xE0, 0xF6, 0x01, 0xD7]);
+
+        let mut short = [0u8; 2];
+        assert_eq!(
+            driver.encode_into(&Command::ReadEncoderValue, &mut short),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(
+            driver.encode_into(&Command::SetZeroSpeed(MAX_ZERO_SPEED + 1), &mut tx),
+            Err(Error::InvalidValue)
+        );
     }
 
     #[test]
-    fn test_run_with_constant_speed_invalid_speed() {
+    fn test_set_address() {
         let mut driver = Driver::default();
-        // MAX_SPEED is 0x7F, so 0x80 should fail
-        let result = driver.run_with_constant_speed(RotationDirection::Clockwise, MAX_SPEED + 1);
-        assert!(matches!(result, Err(Error::InvalidValue)));
+        driver.set_address(Address::new(0xE3).unwrap());
+        assert_eq!(driver.address(), Address::new(0xE3).unwrap());
 
-        // Valid speed should succeed
-        let result = driver.run_with_constant_speed(RotationDirection::Clockwise, MAX_SPEED);
-        assert!(result.is_ok());
+        // Subsequent commands target the new address: e3 f7 da
+        assert_eq!(driver.stop().as_slice(), &[0xE3, 0xF7, 0xDA]);
+    }
+
+    #[test]
+    fn test_set_subdivision() {
+        let driver = Driver::default();
+        // The byte is the microstep count: 4 microsteps is 0x04.
+        let cmd = driver.set_subdivision(index_for_microsteps(4).unwrap());
+        assert_eq!(cmd.as_slice(), &[0xE0, 0x84, 0x04, 0x68]);
+        // Example from the manual: e0 84 00 64 (256 microsteps)
+        assert_eq!(
+            driver.set_subdivision(0).as_slice(),
+            &[0xE0, 0x84, 0x00, 0x64]
+        );
+        assert_eq!(
+            driver
+                .encode(&Command::SetSubdivision(0x4E))
+                .unwrap()
+                .as_slice(),
+            &[0xE0, 0x84, 0x4E, 0xB2]
+        );
+    }
+
+    #[test]
+    fn test_set_switches() {
+        let driver = Driver::default();
+        // Examples from the manual: 00 disables, 01 enables.
+        assert_eq!(
+            driver.set_auto_screen_off(false).as_slice(),
+            &[0xE0, 0x87, 0x00, 0x67]
+        );
+        assert_eq!(
+            driver.set_auto_screen_off(true).as_slice(),
+            &[0xE0, 0x87, 0x01, 0x68]
+        );
+        assert_eq!(
+            driver.set_stall_protection(false).as_slice(),
+            &[0xE0, 0x88, 0x00, 0x68]
+        );
+        assert_eq!(
+            driver.set_stall_protection(true).as_slice(),
+            &[0xE0, 0x88, 0x01, 0x69]
+        );
+        assert_eq!(
+            driver.set_interpolation(false).as_slice(),
+            &[0xE0, 0x89, 0x00, 0x69]
+        );
+        assert_eq!(
+            driver.set_interpolation(true).as_slice(),
+            &[0xE0, 0x89, 0x01, 0x6A]
+        );
+    }
+
+    #[test]
+    fn test_set_current_limit() {
+        let driver = Driver::default();
+        // Example from the manual: e0 83 06 69 (1200mA)
+        let cmd = driver.set_current_limit(Milliamps::new(1200).unwrap());
+        assert_eq!(cmd.as_slice(), &[0xE0, 0x83, 0x06, 0x69]);
+    }
+
+    #[test]
+    fn test_set_max_torque() {
+        let driver = Driver::default();
+        // Example from the manual: e0 a5 02 58 df (MaxT = 0x258)
+        let cmd = driver
+            .set_max_torque(TorqueLimit::new(0x258).unwrap())
+            .unwrap();
+        assert_eq!(cmd.as_slice(), &[0xE0, 0xA5, 0x02, 0x58, 0xDF]);
+    }
+
+    #[test]
+    fn test_set_pid() {
+        let driver = Driver::default();
+        // Example from the manual: e0 a1 01 20 a2 (Kp = 0x120)
+        let pid = Pid::new(0x120, 1, 0x650).unwrap();
+        let [kp, ki, kd] = driver.set_pid(&pid).unwrap();
+        assert_eq!(kp.as_slice(), &[0xE0, 0xA1, 0x01, 0x20, 0xA2]);
+        assert_eq!(Ok(ki), driver.set_position_ki(1));
+        assert_eq!(Ok(kd), driver.set_position_kd(0x650));
+    }
+
+    #[test]
+    fn test_set_acceleration() {
+        let driver = Driver::default();
+        // Example from the manual: e0 a4 00 80 04 (ACC = 0x80)
+        let cmd = driver.set_acceleration(0x80).unwrap();
+        assert_eq!(cmd.as_slice(), &[0xE0, 0xA4, 0x00, 0x80, 0x04]);
+        assert!(driver.set_acceleration(MAX_ACCELERATION).is_ok());
+        assert_eq!(
+            driver.set_acceleration(MAX_ACCELERATION + 1),
+            Err(Error::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn test_set_current_limit_ma() {
+        let driver = Driver::default();
+        let cmd = driver.set_current_limit_ma(1300).unwrap();
+        assert_eq!(cmd.as_slice(), &[0xE0, 0x83, 0x06, 0x69]);
+        assert_eq!(
+            driver.set_current_limit_ma(3000).unwrap(),
+            driver.set_current_limit(Milliamps::MAX)
+        );
+        assert_eq!(driver.set_current_limit_ma(3200), Err(Error::InvalidValue));
+
+        let driver = driver.with_profile(DeviceProfile::SERVO57C);
+        assert_eq!(driver.profile(), &DeviceProfile::SERVO57C);
+        let cmd = driver.set_current_limit_ma(5200).unwrap();
+        assert_eq!(cmd.as_slice(), &[0xE0, 0x83, 0x1A, 0x7D]);
+        assert_eq!(driver.set_current_limit_ma(5400), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_apply_factory_config() {
+        let driver = Driver::try_with_address(0xE1).unwrap();
+        let frames = driver.apply_config(&MotorConfig::factory()).unwrap();
+        assert_eq!(frames[0].as_slice(), &[0xE1, 0xA1, 0x06, 0x50, 0xD8]);
+        assert_eq!(frames[1].as_slice(), &[0xE1, 0xA2, 0x00, 0x01, 0x84]);
+        assert_eq!(frames[3].as_slice(), &[0xE1, 0xA4, 0x01, 0x1E, 0xA4]);
+        assert_eq!(Ok(frames[4]), driver.set_max_torque(TorqueLimit::MAX));
+
+        let config = MotorConfig {
+            acceleration: MAX_ACCELERATION + 1,
+            ..MotorConfig::factory()
+        };
+        assert_eq!(driver.apply_config(&config), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_apply_and_save() {
+        let driver = Driver::default();
+        let config = MotorConfig::factory();
+        let frames = driver.apply_and_save(&config).unwrap();
+        assert_eq!(frames[..5], driver.apply_config(&config).unwrap());
+        assert_eq!(frames[5].as_slice(), &[0xE0, 0xFF, 0xC8, 0xA7]);
+        assert_eq!(frames[6], driver.enable_motor(true));
+
+        let config = MotorConfig {
+            acceleration: MAX_ACCELERATION + 1,
+            ..config
+        };
+        assert_eq!(driver.apply_and_save(&config), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_run_motor() {
+        let driver = Driver::default();
+        // Example from the manual: e0 fd 01 00 00 0c 80 6a (forward 3200 pulses at speed 1)
+        let speed = Speed::new(1).unwrap();
+        let cmd = driver
+            .run_motor(RotationDirection::Clockwise, speed, Pulses::new(0x0C80))
+            .unwrap();
+        assert_eq!(
+            cmd.as_slice(),
+            &[0xE0, 0xFD, 0x01, 0x00, 0x00, 0x0C, 0x80, 0x6A]
+        );
+
+        // e0 fd 86 00 00 0c 80 ef (reverse at speed 6)
+        let speed = Speed::new(6).unwrap();
+        let cmd = driver
+            .run_motor(
+                RotationDirection::CounterClockwise,
+                speed,
+                Pulses::new(0x0C80),
+            )
+            .unwrap();
+        assert_eq!(
+            cmd.as_slice(),
+            &[0xE0, 0xFD, 0x86, 0x00, 0x00, 0x0C, 0x80, 0xEF]
+        );
+    }
+
+    #[test]
+    fn test_run_with_constant_speed() {
+        let driver = Driver::default();
+        // Examples from the manual: e0 f6 5a 30 / e0 f6 da b0 (speed gear 90)
+        let speed = Speed::new(90).unwrap();
+        let cmd = driver
+            .run_with_constant_speed(RotationDirection::Clockwise, speed)
+            .unwrap();
+        assert_eq!(cmd.as_slice(), &[0xE0, 0xF6, 0x5A, 0x30]);
+        let cmd = driver
+            .run_with_constant_speed(RotationDirection::CounterClockwise, speed)
+            .unwrap();
+        assert_eq!(cmd.as_slice(), &[0xE0, 0xF6, 0xDA, 0xB0]);
     }
 
     #[test]
     fn test_calibrate_encoder() {
         // This command is too slow (40-60s) and dangerous to test on real hardware
-        let mut driver = Driver::default();
+        let driver = Driver::default();
         let cmd = driver.calibrate_encoder();
 
         // Verify command format: [address, cmd::CALIBRATE_ENCODER, 0x00, checksum]
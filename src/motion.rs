@@ -0,0 +1,409 @@
+//! Moves timed to a duration rather than a speed.
+//!
+//! The speed gears are 500 pulses per second apart whatever the subdivision,
+//! so a gear alone rarely makes a move last exactly the requested time.
+//! [`move_over`] plans a move that does: at speeds between two gears it runs
+//! part of the move at each, and below the first gear it splits the move
+//! into evenly spaced bursts. The plan yields each `run_motor` command with
+//! the time to wait before the next:
+//!
+//! ```
+//! use mks_servo42_rs::motion::move_over;
+//! use mks_servo42_rs::{Command, Degrees};
+//!
+//! // A quarter turn in 300 ms at 16 microsteps: 800 pulses, between gears 5 and 6.
+//! let mut total_ms = 0;
+//! for step in move_over(300, Degrees(90.0), 16)? {
+//!     assert!(matches!(step.command, Command::RunMotor { .. }));
+//!     total_ms += step.wait_ms;
+//! }
+//! assert_eq!(total_ms, 300);
+//! # Ok::<(), mks_servo42_rs::Error>(())
+//! ```
+//!
+//! For continuous motion slower than gear 1, such as sidereal tracking, use
+//! [`SlowTracker`].
+
+use crate::{Command, Degrees, Error, Pulses, Result, RotationDirection, Speed, MAX_SPEED};
+
+/// Pulses per second each speed gear adds.
+const PULSES_PER_SECOND_PER_GEAR: f64 = 500.0;
+
+/// Shortest time between two bursts of a slow move, in milliseconds.
+pub const MIN_BURST_INTERVAL_MS: u32 = 50;
+
+/// One command of a [`TimedMove`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedStep {
+    /// The `run_motor` command to send.
+    pub command: Command,
+    /// Time from sending the command to sending the next one, in milliseconds.
+    pub wait_ms: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Plan {
+    /// `slow_pulses` at `gear`, the rest at `gear + 1`.
+    Geared { gear: u8, slow_pulses: u32 },
+    /// `bursts` evenly spaced bursts at gear 1.
+    Bursts { bursts: u32 },
+}
+
+/// A move planned by [`move_over`]; iterates over its [`TimedStep`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedMove {
+    direction: RotationDirection,
+    pulses: u32,
+    duration_ms: u32,
+    plan: Plan,
+    step: u32,
+}
+
+/// Plans a rotation by `degrees` lasting `duration_ms` on a board set to `microsteps`.
+///
+/// Negative angles turn counter-clockwise. Durations come out within a few
+/// milliseconds, on top of the board's acceleration ramps.
+///
+/// # Errors
+/// Returns `Error::InvalidValue` if `duration_ms` or `microsteps` is zero, or
+/// if the move would need more than `MAX_SPEED`.
+pub fn move_over(duration_ms: u32, degrees: Degrees, microsteps: u16) -> Result<TimedMove> {
+    if duration_ms == 0 || microsteps == 0 {
+        return Err(Error::InvalidValue);
+    }
+    let pulses = Pulses::from_degrees(degrees, microsteps).get();
+    // A gear runs 500 pulses per second: p pulses at gear g take 2p / g ms.
+    let twice_pulses = 2 * u64::from(pulses);
+    let duration = u64::from(duration_ms);
+    if twice_pulses > u64::from(MAX_SPEED) * duration {
+        return Err(Error::InvalidValue);
+    }
+    let plan = match u8::try_from(twice_pulses / duration) {
+        Ok(0) => Plan::Bursts {
+            bursts: pulses.min((duration_ms / MIN_BURST_INTERVAL_MS).max(1)),
+        },
+        Ok(gear) if gear < MAX_SPEED => {
+            // Split so that 2a / g + 2(p - a) / (g + 1) = duration.
+            let gear_u64 = u64::from(gear);
+            let slow = gear_u64 * (duration * (gear_u64 + 1) - twice_pulses) / 2;
+            Plan::Geared {
+                gear,
+                slow_pulses: u32::try_from(slow).unwrap_or(pulses).min(pulses),
+            }
+        }
+        _ => Plan::Geared {
+            gear: MAX_SPEED,
+            slow_pulses: pulses,
+        },
+    };
+    let direction = if degrees.0 < 0.0 {
+        RotationDirection::CounterClockwise
+    } else {
+        RotationDirection::Clockwise
+    };
+    Ok(TimedMove {
+        direction,
+        pulses,
+        duration_ms,
+        plan,
+        step: 0,
+    })
+}
+
+impl TimedMove {
+    /// Returns the total number of pulses of the move.
+    #[must_use]
+    pub const fn pulses(&self) -> Pulses {
+        Pulses::new(self.pulses)
+    }
+
+    /// Returns the requested duration in milliseconds.
+    #[must_use]
+    pub const fn duration_ms(&self) -> u32 {
+        self.duration_ms
+    }
+
+    fn run(&self, gear: u8, pulses: u32, wait_ms: u32) -> TimedStep {
+        TimedStep {
+            command: Command::RunMotor {
+                direction: self.direction,
+                speed: Speed::new(gear).unwrap_or(Speed::MAX),
+                pulses: Pulses::new(pulses),
+            },
+            wait_ms,
+        }
+    }
+}
+
+/// Returns the share of `total` falling into part `index` of `parts`.
+fn share(total: u32, index: u32, parts: u32) -> u32 {
+    let at = |i: u32| u64::from(total) * u64::from(i) / u64::from(parts);
+    // At most `total`, so the difference fits.
+    #[allow(clippy::cast_possible_truncation)]
+    let part = (at(index + 1) - at(index)) as u32;
+    part
+}
+
+impl Iterator for TimedMove {
+    type Item = TimedStep;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let step = self.step;
+            self.step += 1;
+            let (gear, pulses, wait_ms) = match self.plan {
+                Plan::Geared { gear, slow_pulses } => {
+                    let (gear, pulses) = match step {
+                        0 => (gear, slow_pulses),
+                        1 => (gear.saturating_add(1), self.pulses - slow_pulses),
+                        _ => return None,
+                    };
+                    let wait_ms = (2 * pulses + u32::from(gear) / 2) / u32::from(gear);
+                    (gear, pulses, wait_ms)
+                }
+                Plan::Bursts { bursts } if step < bursts => (
+                    1,
+                    share(self.pulses, step, bursts),
+                    share(self.duration_ms, step, bursts),
+                ),
+                Plan::Bursts { .. } => return None,
+            };
+            if pulses > 0 {
+                return Some(self.run(gear, pulses, wait_ms));
+            }
+        }
+    }
+}
+
+/// Turns at an average rate below the slowest speed gear.
+///
+/// Gear 1 is 500 pulses per second, far too fast for star tracking or
+/// time-lapse. The tracker works out how many pulses are owed since it
+/// started and emits them as short `run_motor` commands. Pulses are counted
+/// from the start rather than per call, so errors do not accumulate: each
+/// pulse goes out at most `min_interval_ms` (or one poll period) late.
+///
+/// ```
+/// use mks_servo42_rs::motion::SlowTracker;
+/// use mks_servo42_rs::Command;
+///
+/// // Sidereal rate through a 100:1 worm at 16 microsteps: ~3.7 pulses per second.
+/// let mut tracker = SlowTracker::new(360.0 * 100.0 / 86_164.1, 16)?;
+/// assert_eq!(tracker.poll(0), None);
+/// let Some(Command::RunMotor { pulses, .. }) = tracker.poll(1000) else {
+///     panic!("pulses owed");
+/// };
+/// assert_eq!(pulses.get(), 3);
+/// assert_eq!(tracker.next_due_ms(), Some(1078));
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlowTracker {
+    direction: RotationDirection,
+    pulses_per_second: f64,
+    min_interval_ms: u32,
+    started_ms: Option<u64>,
+    last_sent_ms: Option<u64>,
+    emitted: u64,
+}
+
+impl SlowTracker {
+    /// Default shortest time between two commands, in milliseconds.
+    pub const DEFAULT_MIN_INTERVAL_MS: u32 = 100;
+
+    /// Creates a tracker turning `degrees_per_second` on a board set to
+    /// `microsteps`; negative rates turn counter-clockwise.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the rate is zero, not finite or not
+    /// below gear 1 (use `run_with_constant_speed` instead), or if
+    /// `microsteps` is zero.
+    pub fn new(degrees_per_second: f32, microsteps: u16) -> Result<Self> {
+        let pulses_per_second = f64::from(degrees_per_second.abs()) / 360.0
+            * f64::from(crate::helpers::STEPS_PER_REV)
+            * f64::from(microsteps);
+        if !degrees_per_second.is_finite()
+            || pulses_per_second <= 0.0
+            || pulses_per_second >= PULSES_PER_SECOND_PER_GEAR
+        {
+            return Err(Error::InvalidValue);
+        }
+        let direction = if degrees_per_second < 0.0 {
+            RotationDirection::CounterClockwise
+        } else {
+            RotationDirection::Clockwise
+        };
+        Ok(Self {
+            direction,
+            pulses_per_second,
+            min_interval_ms: Self::DEFAULT_MIN_INTERVAL_MS,
+            started_ms: None,
+            last_sent_ms: None,
+            emitted: 0,
+        })
+    }
+
+    /// Sets the shortest time between two commands, bounding the bus load.
+    #[must_use]
+    pub const fn min_interval_ms(mut self, min_interval_ms: u32) -> Self {
+        self.min_interval_ms = min_interval_ms;
+        self
+    }
+
+    /// Returns the number of pulses emitted since the first poll.
+    #[must_use]
+    pub const fn emitted(&self) -> u64 {
+        self.emitted
+    }
+
+    /// Returns the time the next pulse falls due, once started.
+    #[must_use]
+    pub fn next_due_ms(&self) -> Option<u64> {
+        let started_ms = self.started_ms?;
+        #[allow(clippy::cast_precision_loss)]
+        let due = (self.emitted + 1) as f64 * 1000.0 / self.pulses_per_second;
+        // Non-negative, so the cast truncates towards zero; round it up.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let mut due_ms = due as u64;
+        #[allow(clippy::cast_precision_loss)]
+        if (due_ms as f64) < due {
+            due_ms += 1;
+        }
+        Some(started_ms + due_ms)
+    }
+
+    /// Returns the `run_motor` command for the pulses owed at `now_ms`, if any.
+    ///
+    /// The first call only records the start time.
+    pub fn poll(&mut self, now_ms: u64) -> Option<Command> {
+        let started_ms = *self.started_ms.get_or_insert(now_ms);
+        let min_interval_ms = u64::from(self.min_interval_ms);
+        if self
+            .last_sent_ms
+            .is_some_and(|last_ms| now_ms.saturating_sub(last_ms) < min_interval_ms)
+        {
+            return None;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let due = now_ms.saturating_sub(started_ms) as f64 * self.pulses_per_second / 1000.0;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let owed = (due as u64).saturating_sub(self.emitted);
+        let pulses = u32::try_from(owed).unwrap_or(u32::MAX);
+        if pulses == 0 {
+            return None;
+        }
+        self.emitted += u64::from(pulses);
+        self.last_sent_ms = Some(now_ms);
+        Some(Command::RunMotor {
+            direction: self.direction,
+            speed: Speed::new(1).unwrap_or(Speed::MAX),
+            pulses: Pulses::new(pulses),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(plan: TimedMove) -> ([(u8, u32, u32); 8], usize, u32) {
+        let mut out = [(0, 0, 0); 8];
+        let mut len = 0;
+        let mut pulses = 0;
+        for step in plan {
+            let Command::RunMotor {
+                speed,
+                pulses: count,
+                ..
+            } = step.command
+            else {
+                panic!("not a run_motor");
+            };
+            out[len] = (speed.get(), count.get(), step.wait_ms);
+            len += 1;
+            pulses += count.get();
+        }
+        (out, len, pulses)
+    }
+
+    #[test]
+    fn test_geared_move() {
+        // 3200 pulses in 1 s: gear 6.4, split between gears 6 and 7.
+        let (out, len, pulses) = steps(move_over(1000, Degrees(-360.0), 16).unwrap());
+        assert_eq!(len, 2);
+        assert_eq!(pulses, 3200);
+        assert_eq!(out[..2], [(6, 1800, 600), (7, 1400, 400)]);
+
+        // Exactly gear 8: a single segment.
+        let (out, len, _) = steps(move_over(25, Degrees(180.0), 1).unwrap());
+        assert_eq!(out[..len], [(8, 100, 25)]);
+
+        let mut plan = move_over(1000, Degrees(-1.0), 16).unwrap();
+        assert!(matches!(
+            plan.next().unwrap().command,
+            Command::RunMotor {
+                direction: RotationDirection::CounterClockwise,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_slow_move_bursts() {
+        // 80 pulses in 200 ms is below gear 1: a burst every 50 ms.
+        let (out, len, pulses) = steps(move_over(200, Degrees(9.0), 16).unwrap());
+        assert_eq!((len, pulses), (4, 80));
+        assert_eq!(out[..4], [(1, 20, 50); 4]);
+
+        let (out, len, pulses) = steps(move_over(1000, Degrees(0.45), 16).unwrap());
+        assert_eq!((len, pulses), (4, 4));
+        assert_eq!(out[..4], [(1, 1, 250); 4]);
+
+        assert_eq!(move_over(1000, Degrees(0.0), 16).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_move_over_limits() {
+        assert_eq!(move_over(0, Degrees(1.0), 16), Err(Error::InvalidValue));
+        assert_eq!(move_over(10, Degrees(1.0), 0), Err(Error::InvalidValue));
+        // 3200 pulses in 50 ms would need gear 128.
+        assert_eq!(move_over(50, Degrees(360.0), 16), Err(Error::InvalidValue));
+        let (out, len, _) = steps(move_over(51, Degrees(360.0), 16).unwrap());
+        assert_eq!(out[..len], [(125, 1625, 26), (126, 1575, 25)]);
+        let (out, len, _) = steps(move_over(100, Degrees(11430.0), 1).unwrap());
+        assert_eq!(out[..len], [(127, 6350, 100)]);
+    }
+
+    #[test]
+    fn test_slow_tracker() {
+        // 2.25°/s at 1 microstep: a pulse every 800 ms.
+        let mut tracker = SlowTracker::new(-2.25, 1).unwrap().min_interval_ms(1500);
+        assert_eq!(tracker.next_due_ms(), None);
+        assert_eq!(tracker.poll(10_000), None);
+        assert_eq!(tracker.next_due_ms(), Some(10_800));
+        assert_eq!(tracker.poll(10_799), None);
+        assert_eq!(
+            tracker.poll(10_800),
+            Some(Command::RunMotor {
+                direction: RotationDirection::CounterClockwise,
+                speed: Speed::new(1).unwrap(),
+                pulses: Pulses::new(1),
+            })
+        );
+        // Held back by the minimum interval, then catches up.
+        assert_eq!(tracker.poll(11_600), None);
+        assert!(matches!(
+            tracker.poll(12_400),
+            Some(Command::RunMotor { pulses, .. }) if pulses.get() == 2
+        ));
+        assert_eq!(tracker.emitted(), 3);
+        assert_eq!(tracker.next_due_ms(), Some(13_200));
+
+        assert_eq!(SlowTracker::new(0.0, 16), Err(Error::InvalidValue));
+        assert_eq!(SlowTracker::new(f32::NAN, 16), Err(Error::InvalidValue));
+        assert_eq!(SlowTracker::new(1.0, 0), Err(Error::InvalidValue));
+        // 56.25°/s at 16 microsteps is exactly gear 1.
+        assert_eq!(SlowTracker::new(56.25, 16), Err(Error::InvalidValue));
+        assert!(SlowTracker::new(56.2, 16).is_ok());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{parse_ack, Command, CommandBytes, Degrees, Driver, Response, CMD_BUFFER_SIZE};
+
+/// Two motors driving one axis, such as the two sides of a gantry.
+///
+/// Motion commands are encoded for both boards, with the secondary's rotation
+/// reversed when the motors are mounted facing each other. Positions are
+/// compared in the primary's frame to detect racking:
+///
+/// ```
+/// use mks_servo42_rs::{Command, Degrees, Driver, MirroredPair, Pulses, RotationDirection, Speed};
+///
+/// let left = Driver::try_with_address(0xE0)?;
+/// let right = Driver::try_with_address(0xE1)?;
+/// let pair = MirroredPair::new(left, right, true).racking_limit(Degrees(2.0));
+/// let [left, right] = pair.encode(&Command::RunMotor {
+///     direction: RotationDirection::Clockwise,
+///     speed: Speed::new(5)?,
+///     pulses: Pulses::new(3200),
+/// })?;
+/// assert_eq!(left.as_slice()[..3], [0xE0, 0xFD, 0x05]);
+/// assert_eq!(right.as_slice()[..3], [0xE1, 0xFD, 0x85]);
+///
+/// assert!(pair.verify_acks(&[0xE0, 0x01, 0xE1], &[0xE1, 0x01, 0xE2])?);
+/// assert!(pair.is_racked(Degrees(90.0), Degrees(-87.5)));
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MirroredPair<const N: usize = CMD_BUFFER_SIZE> {
+    primary: Driver<N>,
+    secondary: Driver<N>,
+    mirrored: bool,
+    racking_limit: Degrees,
+}
+
+impl<const N: usize> MirroredPair<N> {
+    /// Default largest tolerated difference between the two positions.
+    pub const DEFAULT_RACKING_LIMIT: Degrees = Degrees(5.0);
+
+    /// Pairs two boards; `mirrored` reverses every rotation of `secondary`.
+    #[must_use]
+    pub const fn new(primary: Driver<N>, secondary: Driver<N>, mirrored: bool) -> Self {
+        Self {
+            primary,
+            secondary,
+            mirrored,
+            racking_limit: Self::DEFAULT_RACKING_LIMIT,
+        }
+    }
+
+    /// Sets the largest tolerated difference between the two positions.
+    #[must_use]
+    pub const fn racking_limit(mut self, limit: Degrees) -> Self {
+        self.racking_limit = limit;
+        self
+    }
+
+    /// Returns the primary and secondary drivers.
+    #[must_use]
+    pub const fn drivers(&self) -> [&Driver<N>; 2] {
+        [&self.primary, &self.secondary]
+    }
+
+    /// Encodes `command` for the primary and the secondary board.
+    ///
+    /// # Errors
+    /// Returns the error [`Driver::encode`] reports for either board, or
+    /// `Error::Unsupported` for [`Command::GoToZero`] on a mirrored pair, since
+    /// each board homes in the direction set by its own `SetZeroDirection`.
+    pub fn encode(&self, command: &Command) -> crate::Result<[CommandBytes<N>; 2]> {
+        let secondary = if self.mirrored {
+            mirror(*command)?
+        } else {
+            *command
+        };
+        Ok([
+            self.primary.encode(command)?,
+            self.secondary.encode(&secondary)?,
+        ])
+    }
+
+    /// Checks the replies of both boards to the frames from [`encode`](Self::encode).
+    ///
+    /// Returns `Ok(true)` if both boards reported success.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidPacket` if either reply is not an acknowledgement
+    /// from the expected board.
+    pub fn verify_acks(&self, primary: &[u8], secondary: &[u8]) -> crate::Result<bool> {
+        let mut ok = true;
+        for (driver, reply) in [(&self.primary, primary), (&self.secondary, secondary)] {
+            let ack = parse_ack(reply)?;
+            if ack.address != driver.address() {
+                return Err(crate::Error::InvalidPacket);
+            }
+            ok &= ack.response == Response::Success;
+        }
+        Ok(ok)
+    }
+
+    /// Returns how far the secondary is ahead of the primary, given both
+    /// boards' encoder positions.
+    #[must_use]
+    pub fn racking(&self, primary: Degrees, secondary: Degrees) -> Degrees {
+        let secondary = if self.mirrored { -secondary } else { secondary };
+        secondary - primary
+    }
+
+    /// Returns `true` if the positions differ by more than the racking limit.
+    #[must_use]
+    pub fn is_racked(&self, primary: Degrees, secondary: Degrees) -> bool {
+        self.racking(primary, secondary).abs() > self.racking_limit
+    }
+}
+
+/// Reverses the rotation of a motion command.
+const fn mirror(command: Command) -> crate::Result<Command> {
+    Ok(match command {
+        Command::RunMotor {
+            direction,
+            speed,
+            pulses,
+        } => Command::RunMotor {
+            direction: direction.reversed(),
+            speed,
+            pulses,
+        },
+        Command::RunWithConstantSpeed { direction, speed } => Command::RunWithConstantSpeed {
+            direction: direction.reversed(),
+            speed,
+        },
+        Command::GoToZero => return Err(crate::Error::Unsupported),
+        other => other,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Pulses, RotationDirection, Speed};
+
+    #[test]
+    fn test_mirrored_pair() {
+        let a = Driver::try_with_address(0xE2).unwrap();
+        let b = Driver::try_with_address(0xE3).unwrap();
+        let run = Command::RunWithConstantSpeed {
+            direction: RotationDirection::CounterClockwise,
+            speed: Speed::new(3).unwrap(),
+        };
+        let [left, right] = MirroredPair::new(a, b, false).encode(&run).unwrap();
+        assert_eq!(left.as_slice(), a.encode(&run).unwrap().as_slice());
+        assert_eq!(right.as_slice(), b.encode(&run).unwrap().as_slice());
+
+        let pair = MirroredPair::new(a, b, true);
+        let [_, right] = pair.encode(&run).unwrap();
+        assert_eq!(right.as_slice(), [0xE3, 0xF6, 0x03, 0xDC]);
+        let [_, right] = pair.encode(&Command::EnableMotor(true)).unwrap();
+        assert_eq!(right.as_slice(), [0xE3, 0xF3, 0x01, 0xD7]);
+        let [_, right] = pair
+            .encode(&Command::RunMotor {
+                direction: RotationDirection::CounterClockwise,
+                speed: Speed::new(1).unwrap(),
+                pulses: Pulses::new(1),
+            })
+            .unwrap();
+        assert_eq!(right.as_slice()[2], 0x01);
+        assert_eq!(pair.encode(&Command::GoToZero), Err(Error::Unsupported));
+        assert!(MirroredPair::new(a, b, false)
+            .encode(&Command::GoToZero)
+            .is_ok());
+
+        assert_eq!(
+            pair.verify_acks(&[0xE2, 0x01, 0xE3], &[0xE3, 0x00, 0xE3]),
+            Ok(false)
+        );
+        assert_eq!(
+            pair.verify_acks(&[0xE2, 0x01, 0xE3], &[0xE2, 0x01, 0xE3]),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            pair.verify_acks(&[0xE2, 0x01, 0xE3], &[]),
+            Err(Error::InvalidPacket)
+        );
+
+        assert_eq!(pair.racking(Degrees(10.0), Degrees(-12.0)), Degrees(2.0));
+        assert!(!pair.is_racked(Degrees(10.0), Degrees(-12.0)));
+        assert!(pair.is_racked(Degrees(10.0), Degrees(-4.0)));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{Command, CommandClass, Error, Result};
+
+/// Gate for commands that can leave a board in an unexpected state.
+///
+/// The default policy blocks [`CommandClass::Destructive`] operations: encoder
+/// calibration and, on the SERVO42D, baud rate, slave address and work mode
+/// changes. Opt in with [`allow_destructive`](Self::allow_destructive) where
+/// they are intended:
+///
+/// ```
+/// use mks_servo42_rs::{Command, CommandPolicy, Error};
+///
+/// let policy = CommandPolicy::default();
+/// assert_eq!(policy.check(&Command::CalibrateEncoder), Err(Error::NotPermitted));
+/// assert!(policy.allow_destructive().check(&Command::CalibrateEncoder).is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CommandPolicy {
+    allow_destructive: bool,
+}
+
+impl CommandPolicy {
+    /// Blocks destructive commands.
+    pub const SAFE: Self = Self {
+        allow_destructive: false,
+    };
+
+    /// Returns a policy that also permits destructive commands.
+    #[must_use]
+    pub const fn allow_destructive(mut self) -> Self {
+        self.allow_destructive = true;
+        self
+    }
+
+    /// Returns `true` if the policy permits `command`.
+    #[must_use]
+    pub const fn allows(&self, command: &Command) -> bool {
+        self.allows_class(command.class())
+    }
+
+    /// Returns `true` if the policy permits operations of `class`.
+    #[must_use]
+    pub const fn allows_class(&self, class: CommandClass) -> bool {
+        self.allow_destructive || !matches!(class, CommandClass::Destructive)
+    }
+
+    /// Checks `command` against the policy before it is encoded.
+    ///
+    /// # Errors
+    /// Returns `Error::NotPermitted` if the policy blocks `command`.
+    pub const fn check(&self, command: &Command) -> Result<()> {
+        self.check_class(command.class())
+    }
+
+    /// Checks an operation of `class` that has no [`Command`] variant, such as
+    /// a SERVO42D baud rate change.
+    ///
+    /// # Errors
+    /// Returns `Error::NotPermitted` if the policy blocks `class`.
+    pub const fn check_class(&self, class: CommandClass) -> Result<()> {
+        if self.allows_class(class) {
+            Ok(())
+        } else {
+            Err(Error::NotPermitted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy() {
+        let safe = CommandPolicy::SAFE;
+        assert_eq!(safe, CommandPolicy::default());
+        assert!(safe.allows(&Command::Stop));
+        assert!(safe.allows(&Command::SetSubdivision(2)));
+        assert_eq!(
+            safe.check(&Command::CalibrateEncoder),
+            Err(Error::NotPermitted)
+        );
+        assert_eq!(
+            safe.check_class(CommandClass::Destructive),
+            Err(Error::NotPermitted)
+        );
+        assert_eq!(safe.check_class(CommandClass::Configure), Ok(()));
+        let unlocked = safe.allow_destructive();
+        assert_eq!(unlocked.check(&Command::CalibrateEncoder), Ok(()));
+        assert_eq!(unlocked.check_class(CommandClass::Destructive), Ok(()));
+    }
+}
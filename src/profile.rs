@@ -0,0 +1,141 @@
+use crate::{
+    Command, MotorConfig, ProtocolVariant, CURRENT_STEP_MA, MAX_ACCELERATION, MAX_CURRENT_INDEX,
+};
+
+/// Limits, defaults and command set of one board family.
+///
+/// The SERVO57C uses the same frames as the SERVO42C but drives larger motors.
+/// Both boards share one C-series manual, which gives a single set of factory
+/// tuning values and ACC range, so the two profiles differ only in current.
+/// Select the profile with [`Driver::with_profile`](crate::Driver::with_profile)
+/// so validation follows the target board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeviceProfile {
+    /// Protocol the board speaks, which decides the supported commands.
+    pub protocol: ProtocolVariant,
+    /// Highest working current, in mA.
+    pub max_current_ma: u16,
+    /// Highest acceleration (ACC) accepted by `set_acceleration`; 0 where
+    /// the protocol has no ACC setting.
+    pub max_acceleration: u16,
+    /// Factory tuning written by [`Driver::apply_config`](crate::Driver::apply_config).
+    pub factory: MotorConfig,
+}
+
+impl DeviceProfile {
+    /// MKS SERVO42C: up to 3000 mA.
+    pub const SERVO42C: Self = Self {
+        protocol: ProtocolVariant::Servo42C,
+        max_current_ma: MAX_CURRENT_INDEX as u16 * CURRENT_STEP_MA,
+        max_acceleration: MAX_ACCELERATION,
+        factory: MotorConfig::factory(),
+    };
+
+    /// MKS SERVO57C: up to 5200 mA.
+    pub const SERVO57C: Self = Self {
+        protocol: ProtocolVariant::Servo57,
+        max_current_ma: 5200,
+        max_acceleration: MAX_ACCELERATION,
+        factory: MotorConfig::factory(),
+    };
+
+    /// MKS SERVO42D: up to 3000 mA.
+    ///
+    /// The D firmware has no PID, ACC or torque commands, so `factory` is never
+    /// written to it.
+    #[cfg(feature = "servo42d")]
+    pub const SERVO42D: Self = Self {
+        protocol: ProtocolVariant::Servo42D,
+        max_current_ma: crate::servo42d::Model::Servo42D.max_working_current_ma(),
+        max_acceleration: 0,
+        factory: MotorConfig::factory(),
+    };
+
+    /// MKS SERVO57D: up to 5200 mA; see [`SERVO42D`](Self::SERVO42D).
+    #[cfg(feature = "servo42d")]
+    pub const SERVO57D: Self = Self {
+        protocol: ProtocolVariant::Servo42D,
+        max_current_ma: crate::servo42d::Model::Servo57D.max_working_current_ma(),
+        max_acceleration: 0,
+        factory: MotorConfig::factory(),
+    };
+
+    /// Returns `true` if the board's protocol has an equivalent of `command`.
+    ///
+    /// The C boards support every [`Command`]. The D firmware lacks the speed
+    /// gear moves, the return-to-zero settings, PID, ACC and torque settings
+    /// and the shaft angle read.
+    #[must_use]
+    pub const fn supports(&self, command: &Command) -> bool {
+        match (self.protocol, command) {
+            #[cfg(feature = "servo42d")]
+            (
+                ProtocolVariant::Servo42D,
+                Command::RunWithConstantSpeed { .. }
+                | Command::RunMotor { .. }
+                | Command::SetZeroMode(_)
+                | Command::SetZeroSpeed(_)
+                | Command::SetZeroDirection(_)
+                | Command::SetPositionKp(_)
+                | Command::SetPositionKi(_)
+                | Command::SetPositionKd(_)
+                | Command::SetAcceleration(_)
+                | Command::SetMaxTorque(_)
+                | Command::ReadMotorShaftAngle,
+            ) => false,
+            _ => true,
+        }
+    }
+
+    /// Returns the highest current limit index of the board.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn max_current_index(&self) -> u8 {
+        (self.max_current_ma / CURRENT_STEP_MA) as u8
+    }
+}
+
+impl Default for DeviceProfile {
+    fn default() -> Self {
+        Self::SERVO42C
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles() {
+        assert_eq!(DeviceProfile::SERVO42C.max_current_ma, 3000);
+        assert_eq!(DeviceProfile::SERVO42C.max_current_index(), 0x0F);
+        assert_eq!(DeviceProfile::SERVO57C.max_current_index(), 0x1A);
+        assert_eq!(DeviceProfile::default(), DeviceProfile::SERVO42C);
+        assert_eq!(DeviceProfile::SERVO57C.factory, MotorConfig::factory());
+        assert!(DeviceProfile::SERVO57C.supports(&Command::SetPositionKp(1)));
+    }
+
+    #[cfg(feature = "servo42d")]
+    #[test]
+    fn test_servo_d_profiles() {
+        use crate::servo42d::Driver;
+
+        assert_eq!(DeviceProfile::SERVO42D.max_current_index(), 0x0F);
+        assert_eq!(DeviceProfile::SERVO57D.max_current_ma, 5200);
+        let profile = DeviceProfile::SERVO57D;
+        let driver: Driver = Driver::default();
+        for command in [
+            Command::Stop,
+            Command::ReadEncoderValue,
+            Command::SetPositionKp(1),
+            Command::RunMotor {
+                direction: crate::RotationDirection::Clockwise,
+                speed: crate::Speed::new(1).unwrap(),
+                pulses: crate::Pulses::new(1),
+            },
+        ] {
+            let encodable = driver.encode(&command) != Err(crate::Error::Unsupported);
+            assert_eq!(profile.supports(&command), encodable);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{calculate_checksum, Error, Result, MAX_ADDRESS, MIN_ADDRESS};
+
+/// What a receiver does when its buffer does not start with a valid frame.
+///
+/// Clean wiring rarely loses a byte, so dropping one at a time finds the next
+/// frame soonest. On noisy links skipping to the next address byte or
+/// flushing a whole frame recovers faster, and strict setups may prefer to
+/// stop and report the error:
+///
+/// ```
+/// use mks_servo42_rs::{Resync, ResyncPolicy};
+///
+/// // Line noise before a success acknowledgement.
+/// let buffer = [0x3C, 0x00, 0xE0, 0x01, 0xE1];
+/// let policy = ResyncPolicy::NextAddress;
+/// assert_eq!(policy.take_frame(&buffer, 3)?, Resync::Discard(2));
+/// assert_eq!(policy.take_frame(&buffer[2..], 3)?, Resync::Frame(&[0xE0, 0x01, 0xE1]));
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ResyncPolicy {
+    /// Drop the first byte and try again.
+    #[default]
+    DropByte,
+    /// Drop bytes up to the next one that could be a slave address.
+    NextAddress,
+    /// Drop this many bytes (at least one).
+    Flush(usize),
+    /// Keep the bytes and return the error.
+    Fail,
+}
+
+/// Result of [`ResyncPolicy::take_frame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resync<'a> {
+    /// The buffer starts with a checksum-valid frame of the expected length.
+    Frame(&'a [u8]),
+    /// The buffer holds the start of a frame; wait for more bytes.
+    Incomplete,
+    /// Drop this many bytes from the front of the buffer, then try again.
+    Discard(usize),
+}
+
+impl ResyncPolicy {
+    /// Checks the frame of `len` bytes, checksum last, at the start of
+    /// `buffer` and says how to recover if it is not valid.
+    ///
+    /// # Errors
+    /// With [`Fail`](Self::Fail), returns `Error::InvalidPacket` if the buffer
+    /// does not start with a slave address, or `Error::Checksum` if the frame
+    /// fails its checksum. Returns `Error::InvalidValue` if `len` is below 2.
+    pub fn take_frame(self, buffer: &[u8], len: usize) -> Result<Resync<'_>> {
+        if len < 2 {
+            return Err(Error::InvalidValue);
+        }
+        let Some(&first) = buffer.first() else {
+            return Ok(Resync::Incomplete);
+        };
+        let error = if !is_address(first) {
+            Error::InvalidPacket
+        } else if buffer.len() < len {
+            return Ok(Resync::Incomplete);
+        } else if calculate_checksum(&buffer[..len - 1]) == buffer[len - 1] {
+            return Ok(Resync::Frame(&buffer[..len]));
+        } else {
+            Error::Checksum
+        };
+        let discard = match self {
+            Self::DropByte => 1,
+            Self::NextAddress => buffer[1..]
+                .iter()
+                .position(|&b| is_address(b))
+                .map_or(buffer.len(), |idx| idx + 1),
+            Self::Flush(count) => count.clamp(1, buffer.len()),
+            Self::Fail => return Err(error),
+        };
+        Ok(Resync::Discard(discard))
+    }
+}
+
+const fn is_address(byte: u8) -> bool {
+    byte >= MIN_ADDRESS && byte <= MAX_ADDRESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resync_policy() {
+        let ack = [0xE0, 0x01, 0xE1];
+        for policy in [
+            ResyncPolicy::DropByte,
+            ResyncPolicy::NextAddress,
+            ResyncPolicy::Flush(3),
+            ResyncPolicy::Fail,
+        ] {
+            assert_eq!(policy.take_frame(&ack, 3), Ok(Resync::Frame(&ack)));
+            assert_eq!(policy.take_frame(&ack[..2], 3), Ok(Resync::Incomplete));
+            assert_eq!(policy.take_frame(&[], 3), Ok(Resync::Incomplete));
+        }
+
+        // Bad checksum, with a second address inside the frame.
+        let corrupt = [0xE0, 0xE1, 0x00, 0x00, 0xE1, 0x01, 0xE2];
+        assert_eq!(
+            ResyncPolicy::DropByte.take_frame(&corrupt, 3),
+            Ok(Resync::Discard(1))
+        );
+        assert_eq!(
+            ResyncPolicy::NextAddress.take_frame(&corrupt, 3),
+            Ok(Resync::Discard(1))
+        );
+        assert_eq!(
+            ResyncPolicy::Flush(4).take_frame(&corrupt, 3),
+            Ok(Resync::Discard(4))
+        );
+        assert_eq!(
+            ResyncPolicy::Fail.take_frame(&corrupt, 3),
+            Err(Error::Checksum)
+        );
+
+        // Framing loss: no address at the start.
+        let noise = [0x00, 0x7F, 0x10];
+        assert_eq!(
+            ResyncPolicy::NextAddress.take_frame(&noise, 3),
+            Ok(Resync::Discard(3))
+        );
+        assert_eq!(
+            ResyncPolicy::Flush(0).take_frame(&noise, 3),
+            Ok(Resync::Discard(1))
+        );
+        assert_eq!(
+            ResyncPolicy::Flush(10).take_frame(&noise[..1], 3),
+            Ok(Resync::Discard(1))
+        );
+        assert_eq!(
+            ResyncPolicy::Fail.take_frame(&noise, 3),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            ResyncPolicy::default().take_frame(&ack, 1),
+            Err(Error::InvalidValue)
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{Command, CommandBytes, Pulses, Result, RotationDirection, Speed, CMD_BUFFER_SIZE};
+
+/// Frame builder shared by the MKS board families.
+///
+/// Implemented by [`Driver`](crate::Driver) (SERVO42C/57C, or any protocol
+/// selected with its builder) and, with feature `servo42d`, by
+/// `servo42d::Driver`, so application code can build the common frames
+/// without knowing which board is attached:
+///
+/// ```
+/// use mks_servo42_rs::{Driver, Error, ServoDriver};
+///
+/// fn halt<D: ServoDriver>(driver: &D) -> Result<[u8; 3], Error> {
+///     let frame = ServoDriver::stop(driver)?;
+///     Ok([frame[0], frame[1], frame[2]])
+/// }
+///
+/// assert_eq!(halt(&Driver::default()), Ok([0xE0, 0xF7, 0xD7]));
+/// ```
+///
+/// Only [`encode`](Self::encode) is required; the other methods build the
+/// corresponding [`Command`] through it.
+pub trait ServoDriver<const N: usize = CMD_BUFFER_SIZE> {
+    /// Encodes `command` for the board.
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` if the protocol has no equivalent and
+    /// `Error::InvalidValue` for out-of-range arguments.
+    fn encode(&self, command: &Command) -> Result<CommandBytes<N>>;
+
+    /// Builds the frame energizing (`true`) or releasing (`false`) the motor.
+    ///
+    /// # Errors
+    /// See [`encode`](Self::encode).
+    fn enable(&self, enable: bool) -> Result<CommandBytes<N>> {
+        self.encode(&Command::EnableMotor(enable))
+    }
+
+    /// Builds the frame stopping the motor.
+    ///
+    /// # Errors
+    /// See [`encode`](Self::encode).
+    fn stop(&self) -> Result<CommandBytes<N>> {
+        self.encode(&Command::Stop)
+    }
+
+    /// Builds the frame moving `pulses` in `direction` at speed gear `speed`.
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` on the D firmware, whose moves take RPM
+    /// and absolute targets; use `servo42d::Driver::run_absolute_pulses`.
+    fn move_relative(
+        &self,
+        direction: RotationDirection,
+        speed: Speed,
+        pulses: Pulses,
+    ) -> Result<CommandBytes<N>> {
+        self.encode(&Command::RunMotor {
+            direction,
+            speed,
+            pulses,
+        })
+    }
+
+    /// Builds the frame reading the encoder position.
+    ///
+    /// # Errors
+    /// See [`encode`](Self::encode).
+    fn read_position(&self) -> Result<CommandBytes<N>> {
+        self.encode(&Command::ReadEncoderValue)
+    }
+
+    /// Builds the frame starting the return to zero.
+    ///
+    /// # Errors
+    /// See [`encode`](Self::encode).
+    fn home(&self) -> Result<CommandBytes<N>> {
+        self.encode(&Command::GoToZero)
+    }
+}
+
+impl<const N: usize> ServoDriver<N> for crate::Driver<N> {
+    fn encode(&self, command: &Command) -> Result<CommandBytes<N>> {
+        crate::Driver::encode(self, command)
+    }
+}
+
+#[cfg(feature = "servo42d")]
+impl<const N: usize> ServoDriver<N> for crate::servo42d::Driver<N> {
+    fn encode(&self, command: &Command) -> Result<CommandBytes<N>> {
+        crate::servo42d::Driver::encode(self, command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Driver;
+
+    fn frames<D: ServoDriver>(driver: &D) -> [Result<CommandBytes>; 5] {
+        [
+            driver.enable(true),
+            ServoDriver::stop(driver),
+            driver.move_relative(
+                RotationDirection::Clockwise,
+                Speed::new(1).unwrap(),
+                Pulses::new(3200),
+            ),
+            driver.read_position(),
+            driver.home(),
+        ]
+    }
+
+    #[test]
+    fn test_servo_driver_c() {
+        let driver = Driver::default();
+        let [enable, stop, run, read, home] = frames(&driver);
+        assert_eq!(enable, Ok(driver.enable_motor(true)));
+        assert_eq!(stop, Ok(driver.stop()));
+        assert_eq!(read, Ok(driver.read_encoder_value()));
+        assert_eq!(home, Ok(driver.go_to_zero()));
+        assert_eq!(
+            run.unwrap().as_slice(),
+            &[0xE0, 0xFD, 0x01, 0x00, 0x00, 0x0C, 0x80, 0x6A]
+        );
+    }
+
+    #[cfg(feature = "servo42d")]
+    #[test]
+    fn test_servo_driver_d() {
+        let driver = crate::servo42d::Driver::default();
+        let [enable, stop, run, read, home] = frames(&driver);
+        assert_eq!(enable.unwrap()[..3], [0xFA, 0x01, 0xF3]);
+        assert_eq!(stop.unwrap()[2], 0xF7);
+        assert_eq!(run, Err(crate::Error::Unsupported));
+        assert_eq!(read.unwrap()[2], 0x31);
+        assert_eq!(home.unwrap()[2], 0x91);
+    }
+}
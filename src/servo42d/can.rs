@@ -0,0 +1,316 @@
+//! CAN framing for the SERVO42D protocol (feature `can`).
+//!
+//! On CAN the target is selected by the 11-bit standard identifier and the
+//! payload is `[code, data.., crc]`, where `crc` is the 8-bit sum of the
+//! identifier, the code and the data. Frames are converted from the serial
+//! frames built by [`Driver`], and replies are converted back to serial form,
+//! so every command and parser of the serial protocol is reused. The CAN
+//! identifier, set with [`Driver::set_can_id`], is separate from the 8-bit
+//! slave address, so it is passed explicitly:
+//!
+//! ```
+//! use mks_servo42_rs::servo42d::{can, parse_speed, Driver};
+//!
+//! let frame = can::CanFrame::from_serial(0x123, Driver::default().read_speed().as_slice()).unwrap();
+//! assert_eq!((frame.id(), frame.data()), (0x123, &[0x32, 0x55][..]));
+//!
+//! let mut reply = [0u8; 10];
+//! let len = can::reply_to_serial(0x01, &[0x32, 0x01, 0x2C, 0x60], &mut reply).unwrap();
+//! assert_eq!(parse_speed(&reply[..len]), Ok(300));
+//! ```
+
+use super::{cmd, Driver, DOWNLINK_HEADER, MAX_FRAME_LEN, UPLINK_HEADER};
+use crate::{calculate_checksum, Command, CommandBytes, CommandClass, Error, Result};
+
+/// Highest standard (11-bit) CAN identifier.
+pub const MAX_CAN_ID: u16 = 0x7FF;
+
+/// Largest CAN payload.
+const MAX_DLC: usize = 8;
+
+/// CAN bus bitrate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum CanBitrate {
+    /// 125 kbit/s.
+    Kbps125 = 0x00,
+    /// 250 kbit/s.
+    Kbps250 = 0x01,
+    /// 500 kbit/s (factory default).
+    #[default]
+    Kbps500 = 0x02,
+    /// 1 Mbit/s.
+    Mbps1 = 0x03,
+}
+
+impl<const N: usize> Driver<N> {
+    /// Generates a command setting the CAN bus bitrate.
+    ///
+    /// The new bitrate takes effect after the board restarts; a host left on
+    /// the old bitrate loses the board, as with
+    /// [`set_baud_rate`](Self::set_baud_rate). Parse the reply with
+    /// [`parse_response`](super::parse_response).
+    ///
+    /// # Errors
+    /// Returns `Error::NotPermitted` unless the policy allows destructive commands.
+    pub fn set_can_bitrate(&self, bitrate: CanBitrate) -> Result<CommandBytes<N>> {
+        self.policy.check_class(CommandClass::Destructive)?;
+        Ok(self.build_command(cmd::SET_CAN_BITRATE, &[bitrate as u8]))
+    }
+
+    /// Generates a command setting the board's CAN identifier.
+    ///
+    /// Frames for the old identifier go unanswered afterwards, as with
+    /// [`set_slave_address`](Self::set_slave_address). Parse the reply with
+    /// [`parse_response`](super::parse_response).
+    ///
+    /// # Errors
+    /// Returns `Error::NotPermitted` unless the policy allows destructive
+    /// commands, and `Error::InvalidValue` if `id` exceeds [`MAX_CAN_ID`].
+    pub fn set_can_id(&self, id: u16) -> Result<CommandBytes<N>> {
+        self.policy.check_class(CommandClass::Destructive)?;
+        if id > MAX_CAN_ID {
+            return Err(Error::InvalidValue);
+        }
+        Ok(self.build_command(cmd::SET_CAN_ID, &id.to_be_bytes()))
+    }
+}
+
+/// A CAN data frame carrying one SERVO42D command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanFrame {
+    id: u16,
+    len: u8,
+    data: [u8; MAX_DLC],
+}
+
+impl CanFrame {
+    /// Builds a frame for `id` carrying function `code` and its `data`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `id` exceeds [`MAX_CAN_ID`] or the
+    /// payload does not fit in eight bytes.
+    pub fn new(id: u16, code: u8, data: &[u8]) -> Result<Self> {
+        if id > MAX_CAN_ID || data.len() + 2 > MAX_DLC {
+            return Err(Error::InvalidValue);
+        }
+        let mut frame = Self {
+            id,
+            len: 0,
+            data: [0; MAX_DLC],
+        };
+        frame.data[0] = code;
+        frame.data[1..=data.len()].copy_from_slice(data);
+        let crc = crc(id, &frame.data[..=data.len()]);
+        frame.data[data.len() + 1] = crc;
+        // Bounded by MAX_DLC above.
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            frame.len = (data.len() + 2) as u8;
+        }
+        Ok(frame)
+    }
+
+    /// Converts a serial command frame `[0xFA, address, code, data.., checksum]`
+    /// into a frame for CAN identifier `id`; the serial address is dropped.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidPacket` if `frame` is not a valid command frame,
+    /// or `Error::InvalidValue` if `id` exceeds [`MAX_CAN_ID`].
+    pub fn from_serial(id: u16, frame: &[u8]) -> Result<Self> {
+        let Some((&checksum, body)) = frame.split_last() else {
+            return Err(Error::InvalidPacket);
+        };
+        if body.len() < 3
+            || frame.len() > MAX_FRAME_LEN
+            || body[0] != DOWNLINK_HEADER
+            || calculate_checksum(body) != checksum
+        {
+            return Err(Error::InvalidPacket);
+        }
+        Self::new(id, body[2], &body[3..])
+    }
+
+    /// Returns the CAN identifier.
+    #[must_use]
+    pub const fn id(&self) -> u16 {
+        self.id
+    }
+
+    /// Returns the data length code.
+    #[must_use]
+    pub const fn dlc(&self) -> u8 {
+        self.len
+    }
+
+    /// Returns the payload, `[code, data.., crc]`.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data[..usize::from(self.len)]
+    }
+}
+
+/// Encodes a typed [`Command`] as a CAN frame for CAN identifier `id`.
+///
+/// # Errors
+/// Same as [`Driver::encode`], or `Error::InvalidValue` if `id` exceeds
+/// [`MAX_CAN_ID`].
+pub fn encode<const N: usize>(driver: &Driver<N>, id: u16, command: &Command) -> Result<CanFrame> {
+    CanFrame::from_serial(id, driver.encode(command)?.as_slice())
+}
+
+/// Converts a CAN reply into a serial reply frame `[0xFB, address, code, data.., checksum]`
+/// for the serial parsers, writing it to the start of `out`.
+///
+/// The address byte carries the low eight bits of `id`. Returns the number of
+/// bytes written.
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if the payload is empty, too long or fails
+/// its CRC, and `Error::BufferTooSmall` if `out` cannot hold the frame.
+pub fn reply_to_serial(id: u16, data: &[u8], out: &mut [u8]) -> Result<usize> {
+    let Some((&received, body)) = data.split_last() else {
+        return Err(Error::InvalidPacket);
+    };
+    if body.is_empty() || data.len() > MAX_DLC || crc(id, body) != received {
+        return Err(Error::InvalidPacket);
+    }
+    let len = body.len() + 3;
+    let out = out.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+    let [_, address] = id.to_be_bytes();
+    out[0] = UPLINK_HEADER;
+    out[1] = address;
+    out[2..len - 1].copy_from_slice(body);
+    out[len - 1] = calculate_checksum(&out[..len - 1]);
+    Ok(len)
+}
+
+/// CRC byte of a CAN payload: `(id + sum(body)) & 0xFF`.
+fn crc(id: u16, body: &[u8]) -> u8 {
+    let [_, low] = id.to_be_bytes();
+    calculate_checksum(body).wrapping_add(low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::servo42d::{parse_motor_status, MotorStatus};
+    use crate::CommandPolicy;
+
+    #[test]
+    fn test_from_serial() {
+        let driver: Driver = Driver::new(0x02);
+        let frame = CanFrame::from_serial(0x02, driver.go_home().as_slice()).unwrap();
+        assert_eq!(frame.id(), 0x02);
+        assert_eq!(frame.dlc(), 2);
+        assert_eq!(frame.data(), &[0x91, 0x93]);
+
+        let frame = CanFrame::from_serial(
+            0x02,
+            driver
+                .run_absolute_pulses(600, 2, 0x4000)
+                .unwrap()
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(
+            frame.data(),
+            &[0xFE, 0x02, 0x58, 0x02, 0x00, 0x40, 0x00, 0x9C]
+        );
+
+        // An identifier above the 8-bit serial address range.
+        let frame = CanFrame::from_serial(MAX_CAN_ID, driver.go_home().as_slice()).unwrap();
+        assert_eq!(frame.id(), MAX_CAN_ID);
+        assert_eq!(frame.data(), &[0x91, 0x90]);
+        assert_eq!(
+            CanFrame::from_serial(MAX_CAN_ID + 1, driver.go_home().as_slice()),
+            Err(Error::InvalidValue)
+        );
+
+        assert_eq!(
+            CanFrame::from_serial(0x01, &[0xFA, 0x01, 0x91, 0x8D]),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            CanFrame::from_serial(0x01, &[0xFB, 0x01, 0x91, 0x8D]),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(CanFrame::from_serial(0x01, &[]), Err(Error::InvalidPacket));
+    }
+
+    #[test]
+    fn test_set_can_bitrate() {
+        let driver: Driver = Driver::default();
+        assert_eq!(
+            driver.set_can_bitrate(CanBitrate::Mbps1),
+            Err(Error::NotPermitted)
+        );
+        let driver = driver.with_policy(CommandPolicy::SAFE.allow_destructive());
+        assert_eq!(
+            driver
+                .set_can_bitrate(CanBitrate::Mbps1)
+                .unwrap()
+                .as_slice(),
+            &[0xFA, 0x01, 0x8A, 0x03, 0x88]
+        );
+        assert_eq!(CanBitrate::default(), CanBitrate::Kbps500);
+    }
+
+    #[test]
+    fn test_set_can_id() {
+        let driver: Driver = Driver::default();
+        assert_eq!(driver.set_can_id(0x123), Err(Error::NotPermitted));
+        let driver = driver.with_policy(CommandPolicy::SAFE.allow_destructive());
+        assert_eq!(
+            driver.set_can_id(0x123).unwrap().as_slice(),
+            &[0xFA, 0x01, 0x8B, 0x01, 0x23, 0xAA]
+        );
+        assert!(driver.set_can_id(MAX_CAN_ID).is_ok());
+        assert_eq!(driver.set_can_id(MAX_CAN_ID + 1), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_new() {
+        let frame = CanFrame::new(0x123, 0x31, &[]).unwrap();
+        assert_eq!(frame.data(), &[0x31, 0x54]);
+        assert_eq!(
+            CanFrame::new(MAX_CAN_ID + 1, 0x31, &[]),
+            Err(Error::InvalidValue)
+        );
+        assert_eq!(CanFrame::new(0x01, 0x31, &[0; 7]), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_encode() {
+        let driver: Driver = Driver::default();
+        let frame = encode(&driver, 0x01, &Command::EnableMotor(true)).unwrap();
+        assert_eq!(frame.data(), &[0xF3, 0x01, 0xF5]);
+        let frame = encode(&driver, 0x123, &Command::EnableMotor(true)).unwrap();
+        assert_eq!((frame.id(), frame.data()), (0x123, &[0xF3, 0x01, 0x17][..]));
+        assert_eq!(
+            encode(&driver, 0x01, &Command::SetPositionKp(1)),
+            Err(Error::Unsupported)
+        );
+    }
+
+    #[test]
+    fn test_reply_to_serial() {
+        let mut out = [0u8; MAX_FRAME_LEN];
+        let len = reply_to_serial(0x01, &[0xF1, 0x01, 0xF3], &mut out).unwrap();
+        assert_eq!(&out[..len], &[0xFB, 0x01, 0xF1, 0x01, 0xEE]);
+        assert_eq!(parse_motor_status(&out[..len]), Ok(MotorStatus::Stopped));
+
+        assert_eq!(
+            reply_to_serial(0x01, &[0xF1, 0x01, 0xF4], &mut out),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            reply_to_serial(0x01, &[0xF3], &mut out),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            reply_to_serial(0x01, &[0xF1, 0x01, 0xF3], &mut out[..4]),
+            Err(Error::BufferTooSmall)
+        );
+    }
+}
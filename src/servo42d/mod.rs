@@ -0,0 +1,1114 @@
+//! MKS SERVO42D / SERVO57D serial protocol (feature `servo42d`).
+//!
+//! The D firmware frames every command as `[0xFA, address, code, data.., checksum]`
+//! and every reply as `[0xFB, address, code, data.., checksum]`, where the
+//! checksum is the 8-bit sum of all preceding bytes. Addresses run from 0x01
+//! (default) to 0xFF; 0x00 is the broadcast address.
+//!
+//! ```
+//! use mks_servo42_rs::servo42d::Driver;
+//!
+//! let driver = Driver::default();
+//! assert_eq!(driver.read_parameter(0x82).as_slice(), &[0xFA, 0x01, 0x00, 0x82, 0x7D]);
+//! ```
+
+#[cfg(feature = "can")]
+pub mod can;
+mod reply;
+
+pub use reply::{
+    parse_absolute_axis_status, parse_absolute_pulses_status, parse_go_home_status,
+    parse_hold_current, parse_home_status, parse_io_ports, parse_motor_status, parse_reply,
+    parse_response, parse_speed, parse_subdivision, parse_work_mode, parse_working_current_ma,
+    HomeStatus, IoPorts, MotorStatus, MoveStatus, Reply,
+};
+
+use crate::enums::{BaudRate, RotationDirection};
+use crate::{Command, CommandBytes, CommandClass, CommandPolicy, Error, Result, CMD_BUFFER_SIZE};
+
+/// First byte of every command frame.
+pub const DOWNLINK_HEADER: u8 = 0xFA;
+/// First byte of every reply frame.
+pub const UPLINK_HEADER: u8 = 0xFB;
+/// Address every board on the bus listens to; boards do not reply to it.
+pub const BROADCAST_ADDRESS: u8 = 0x00;
+/// Factory default slave address.
+pub const DEFAULT_ADDRESS: u8 = 0x01;
+
+/// Highest speed accepted by motion commands, in RPM.
+pub const MAX_SPEED: u16 = 3000;
+/// Largest magnitude of a signed 24-bit position argument.
+pub const MAX_POSITION: i32 = 0x7F_FFFF;
+
+/// Longest frame: header, address, code, six data bytes and checksum.
+pub(crate) const MAX_FRAME_LEN: usize = 10;
+const _: () = assert!(MAX_FRAME_LEN <= crate::MAX_FRAME_LEN);
+
+mod cmd {
+    pub const READ_PARAMETER: u8 = 0x00;
+    pub const READ_ENCODER_VALUE: u8 = 0x31;
+    pub const READ_PULSE_COUNT: u8 = 0x33;
+    pub const READ_ANGLE_ERROR: u8 = 0x39;
+    pub const READ_EN_PIN_STATUS: u8 = 0x3A;
+    pub const READ_RELEASE_STATUS: u8 = 0x3D;
+    pub const READ_SHAFT_STATUS: u8 = 0x3E;
+    pub const CALIBRATE_ENCODER: u8 = 0x80;
+    pub const SET_WORK_MODE: u8 = 0x82;
+    pub const SET_WORKING_CURRENT: u8 = 0x83;
+    pub const SET_SUBDIVISION: u8 = 0x84;
+    pub const SET_ENABLE_LOGIC: u8 = 0x85;
+    pub const SET_DIRECTION: u8 = 0x86;
+    pub const SET_AUTO_SCREEN_OFF: u8 = 0x87;
+    pub const SET_STALL_PROTECTION: u8 = 0x88;
+    pub const SET_INTERPOLATION: u8 = 0x89;
+    pub const SET_BAUD_RATE: u8 = 0x8A;
+    #[cfg(feature = "can")]
+    pub const SET_CAN_BITRATE: u8 = 0x8A;
+    #[cfg(feature = "can")]
+    pub const SET_CAN_ID: u8 = 0x8B;
+    pub const SET_SLAVE_ADDRESS: u8 = 0x8B;
+    pub const SET_REPLY_MODE: u8 = 0x8C;
+    pub const SET_GROUP_ADDRESS: u8 = 0x8D;
+    pub const SET_KEY_LOCK: u8 = 0x8F;
+    pub const READ_SPEED: u8 = 0x32;
+    pub const READ_IO_PORTS: u8 = 0x34;
+    pub const WRITE_OUTPUTS: u8 = 0x36;
+    pub const READ_HOME_STATUS: u8 = 0x3B;
+    pub const SET_HOME_PARAMS: u8 = 0x90;
+    pub const GO_HOME: u8 = 0x91;
+    pub const SET_AXIS_ZERO: u8 = 0x92;
+    pub const SET_HOLD_CURRENT: u8 = 0x9B;
+    pub const QUERY_MOTOR_STATUS: u8 = 0xF1;
+    pub const ENABLE_MOTOR: u8 = 0xF3;
+    pub const RUN_ABSOLUTE_AXIS: u8 = 0xF5;
+    pub const RUN_SPEED_MODE: u8 = 0xF6;
+    pub const STOP: u8 = 0xF7;
+    pub const RUN_ABSOLUTE_PULSES: u8 = 0xFE;
+    pub const SAVE_CLEAR_STATUS: u8 = 0xFF;
+}
+
+/// Output levels to write with [`Driver::write_outputs`].
+///
+/// `None` leaves the corresponding pin unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Outputs {
+    /// Level to drive OUT_1 to.
+    pub out_1: Option<bool>,
+    /// Level to drive OUT_2 to.
+    pub out_2: Option<bool>,
+}
+
+impl Outputs {
+    /// Encodes the outputs as the command's data byte: bits 6/7 select OUT_1/OUT_2
+    /// for writing and bits 2/3 carry their levels, matching [`IoPorts`].
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        const fn pin(level: Option<bool>, select: u8, value: u8) -> u8 {
+            match level {
+                None => 0,
+                Some(false) => select,
+                Some(true) => select | value,
+            }
+        }
+        pin(self.out_1, 1 << 6, 1 << 2) | pin(self.out_2, 1 << 7, 1 << 3)
+    }
+}
+
+/// Control interface and loop mode of the D firmware.
+///
+/// Unlike the 42C [`WorkMode`](crate::enums::WorkMode), the D firmware pairs
+/// the pulse (`Cr`) or serial (`Sr`) interface with one of three loop modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WorkModeD {
+    /// Pulse interface, open loop.
+    CrOpen = 0x00,
+    /// Pulse interface, closed loop.
+    CrClose = 0x01,
+    /// Pulse interface, vector FOC.
+    CrVfoc = 0x02,
+    /// Serial interface, open loop.
+    SrOpen = 0x03,
+    /// Serial interface, closed loop.
+    SrClose = 0x04,
+    /// Serial interface, vector FOC.
+    SrVfoc = 0x05,
+}
+
+impl WorkModeD {
+    /// Returns `true` if the board keeps accepting serial motion commands in this mode.
+    ///
+    /// Switching a board driven over UART or RS485 to a pulse (`Cr`) mode hands
+    /// motion control to the STEP/DIR pins.
+    #[must_use]
+    pub const fn is_serial(self) -> bool {
+        matches!(self, Self::SrOpen | Self::SrClose | Self::SrVfoc)
+    }
+}
+
+impl TryFrom<u8> for WorkModeD {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0x00 => Ok(Self::CrOpen),
+            0x01 => Ok(Self::CrClose),
+            0x02 => Ok(Self::CrVfoc),
+            0x03 => Ok(Self::SrOpen),
+            0x04 => Ok(Self::SrClose),
+            0x05 => Ok(Self::SrVfoc),
+            _ => Err(Error::InvalidValue),
+        }
+    }
+}
+
+/// Board model, which bounds some settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Model {
+    /// MKS SERVO42D.
+    #[default]
+    Servo42D,
+    /// MKS SERVO57D.
+    Servo57D,
+}
+
+impl Model {
+    /// Returns the highest working current the board accepts, in mA.
+    #[must_use]
+    pub const fn max_working_current_ma(self) -> u16 {
+        match self {
+            Self::Servo42D => 3000,
+            Self::Servo57D => 5200,
+        }
+    }
+}
+
+/// How the board answers commands, written with [`Driver::set_reply_mode`].
+///
+/// Code that moves the bytes should consult [`expects_reply`](Self::expects_reply)
+/// and [`expects_completion`](Self::expects_completion) before waiting for a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplyMode {
+    /// Acknowledge every command with a reply frame.
+    pub respond: bool,
+    /// Actively send a second frame when a motion command completes.
+    pub active: bool,
+}
+
+impl ReplyMode {
+    /// Factory setting: acknowledge commands and report motion completion.
+    pub const DEFAULT: Self = Self {
+        respond: true,
+        active: true,
+    };
+
+    /// Returns `true` if a command is acknowledged with a reply frame.
+    #[must_use]
+    pub const fn expects_reply(self) -> bool {
+        self.respond
+    }
+
+    /// Returns `true` if a motion command is followed by a completion frame.
+    #[must_use]
+    pub const fn expects_completion(self) -> bool {
+        self.respond && self.active
+    }
+}
+
+impl Default for ReplyMode {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Input level that triggers the home switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TriggerLevel {
+    /// The switch pulls the input low.
+    Low = 0x00,
+    /// The switch drives the input high.
+    High = 0x01,
+}
+
+/// Homing configuration written with [`Driver::set_home_params`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HomeParams {
+    /// Home switch trigger level.
+    pub trigger_level: TriggerLevel,
+    /// Direction to search for the home switch.
+    pub direction: RotationDirection,
+    /// Homing speed in RPM (0-[`MAX_SPEED`]).
+    pub speed: u16,
+    /// Stop on the end-limit switches while the motor runs.
+    pub end_limit: bool,
+}
+
+/// Holding current as a percentage of the working current (10%-90%).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HoldCurrent(u8);
+
+impl HoldCurrent {
+    /// Lowest holding current (10%).
+    pub const MIN: Self = Self(0);
+    /// Highest holding current (90%).
+    pub const MAX: Self = Self(8);
+
+    /// Creates a holding current from a percentage.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` unless `percent` is a multiple of 10 from 10 to 90.
+    pub const fn from_percent(percent: u8) -> Result<Self> {
+        if percent < 10 || percent > 90 || !percent.is_multiple_of(10) {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self(percent / 10 - 1))
+    }
+
+    /// Returns the holding current as a percentage.
+    #[must_use]
+    pub const fn percent(self) -> u8 {
+        (self.0 + 1) * 10
+    }
+}
+
+/// Command builder for MKS SERVO42D / SERVO57D boards.
+///
+/// Mirrors [`crate::Driver`]: builders take `&self` and return owned frames.
+/// Commands that can leave a board unreachable (baud rate, slave address and
+/// work mode changes, encoder calibration) are refused until the driver's
+/// [`CommandPolicy`] allows them:
+///
+/// ```
+/// use mks_servo42_rs::enums::BaudRate;
+/// use mks_servo42_rs::servo42d::Driver;
+/// use mks_servo42_rs::{CommandPolicy, Error};
+///
+/// let driver: Driver = Driver::default();
+/// assert_eq!(driver.set_baud_rate(BaudRate::Baud115200), Err(Error::NotPermitted));
+/// let driver = driver.with_policy(CommandPolicy::SAFE.allow_destructive());
+/// assert!(driver.set_baud_rate(BaudRate::Baud115200).is_ok());
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Driver<const N: usize = CMD_BUFFER_SIZE> {
+    address: u8,
+    model: Model,
+    policy: CommandPolicy,
+}
+
+impl Default for Driver {
+    /// Creates a driver for the default address (0x01).
+    fn default() -> Self {
+        Self::new(DEFAULT_ADDRESS)
+    }
+}
+
+impl Driver {
+    /// Creates a driver for a [`Model::Servo42D`] at `address`;
+    /// [`BROADCAST_ADDRESS`] targets every board.
+    #[must_use]
+    pub const fn new(address: u8) -> Self {
+        Self {
+            address,
+            model: Model::Servo42D,
+            policy: CommandPolicy::SAFE,
+        }
+    }
+
+    /// Creates a driver addressing every board on the bus at once.
+    ///
+    /// Boards execute broadcast commands without replying, so a motion command
+    /// built by this driver starts all axes together.
+    #[must_use]
+    pub const fn broadcast() -> Self {
+        Self::new(BROADCAST_ADDRESS)
+    }
+}
+
+impl<const N: usize> Driver<N> {
+    /// Converts the driver to one producing frames with an `M`-byte buffer.
+    #[must_use]
+    pub const fn with_buffer_size<const M: usize>(self) -> Driver<M> {
+        Driver {
+            address: self.address,
+            model: self.model,
+            policy: self.policy,
+        }
+    }
+
+    /// Sets the board model used to validate model-specific limits.
+    #[must_use]
+    pub const fn with_model(mut self, model: Model) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Returns the board model used to validate model-specific limits.
+    #[must_use]
+    pub const fn model(&self) -> Model {
+        self.model
+    }
+
+    /// Sets the policy gating destructive commands.
+    #[must_use]
+    pub const fn with_policy(mut self, policy: CommandPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the policy gating destructive commands.
+    #[must_use]
+    pub const fn policy(&self) -> CommandPolicy {
+        self.policy
+    }
+
+    /// Returns the slave address targeted by generated commands.
+    #[must_use]
+    pub const fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Re-targets the driver at a different slave address.
+    pub fn set_address(&mut self, address: u8) {
+        self.address = address;
+    }
+
+    /// Returns `true` if generated commands address every board at once.
+    #[must_use]
+    pub const fn is_broadcast(&self) -> bool {
+        self.address == BROADCAST_ADDRESS
+    }
+
+    /// Returns `true` if a command from this driver is acknowledged, given the
+    /// boards' reply `mode`.
+    ///
+    /// Broadcast commands are never answered, so a transport should send them
+    /// and move on instead of waiting for a reply that cannot arrive.
+    #[must_use]
+    pub const fn expects_reply(&self, mode: ReplyMode) -> bool {
+        !self.is_broadcast() && mode.expects_reply()
+    }
+
+    /// Generates a command reading back the stored setting of configuration command `code`.
+    ///
+    /// The board replies with a frame carrying `code` and the setting's current
+    /// data bytes, as they would be sent to change it.
+    pub fn read_parameter(&self, code: u8) -> CommandBytes<N> {
+        self.build_command(cmd::READ_PARAMETER, &[code])
+    }
+
+    /// Generates a command reading back the work mode; parse with [`parse_work_mode`].
+    pub fn read_work_mode(&self) -> CommandBytes<N> {
+        self.read_parameter(cmd::SET_WORK_MODE)
+    }
+
+    /// Generates a command reading back the working current; parse with
+    /// [`parse_working_current_ma`].
+    pub fn read_working_current(&self) -> CommandBytes<N> {
+        self.read_parameter(cmd::SET_WORKING_CURRENT)
+    }
+
+    /// Generates a command reading back the subdivision; parse with [`parse_subdivision`].
+    pub fn read_subdivision(&self) -> CommandBytes<N> {
+        self.read_parameter(cmd::SET_SUBDIVISION)
+    }
+
+    /// Generates a command reading back the holding current; parse with
+    /// [`parse_hold_current`].
+    pub fn read_hold_current(&self) -> CommandBytes<N> {
+        self.read_parameter(cmd::SET_HOLD_CURRENT)
+    }
+
+    /// Generates a command selecting the work mode.
+    ///
+    /// Parse the reply with [`parse_response`], then confirm the switch with
+    /// [`read_work_mode`](Self::read_work_mode). Keep the previous mode at hand:
+    /// if the board stops answering, re-sending it is the only rollback short
+    /// of the on-board menu. See [`WorkModeD::is_serial`].
+    ///
+    /// # Errors
+    /// Returns `Error::NotPermitted` unless the policy allows destructive commands.
+    pub fn set_work_mode(&self, mode: WorkModeD) -> Result<CommandBytes<N>> {
+        self.policy.check_class(CommandClass::Destructive)?;
+        Ok(self.build_command(cmd::SET_WORK_MODE, &[mode as u8]))
+    }
+
+    /// Generates a command setting the working current in mA.
+    ///
+    /// Parse the reply with [`parse_response`].
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `current_ma` exceeds the model's
+    /// [`max_working_current_ma`](Model::max_working_current_ma).
+    pub fn set_working_current_ma(&self, current_ma: u16) -> Result<CommandBytes<N>> {
+        if current_ma > self.model.max_working_current_ma() {
+            return Err(Error::InvalidValue);
+        }
+        Ok(self.build_command(cmd::SET_WORKING_CURRENT, &current_ma.to_be_bytes()))
+    }
+
+    /// Generates a command setting the UART baud rate of an RS485 board.
+    ///
+    /// The board answers at the old rate; reconfigure the host port with
+    /// [`BaudRate::bps`] afterwards and verify with any read. If the
+    /// board does not answer, probe the rates in [`BaudRate::SCAN_ORDER`].
+    /// Parse the reply with [`parse_response`].
+    ///
+    /// # Errors
+    /// Returns `Error::NotPermitted` unless the policy allows destructive commands.
+    pub fn set_baud_rate(&self, baud_rate: BaudRate) -> Result<CommandBytes<N>> {
+        self.policy.check_class(CommandClass::Destructive)?;
+        Ok(self.build_command(cmd::SET_BAUD_RATE, &[baud_rate as u8]))
+    }
+
+    /// Generates a command changing the board's slave address to `new_address`.
+    ///
+    /// The board acknowledges from the old address. Then re-target the driver
+    /// with [`set_address`](Self::set_address) and probe the new address with
+    /// any read before relying on it; if it stays silent, the board still
+    /// answers at one of the addresses 1-255. Parse the reply with [`parse_response`].
+    ///
+    /// # Errors
+    /// Returns `Error::NotPermitted` unless the policy allows destructive
+    /// commands, and `Error::InvalidValue` if `new_address` is [`BROADCAST_ADDRESS`].
+    pub fn set_slave_address(&self, new_address: u8) -> Result<CommandBytes<N>> {
+        self.policy.check_class(CommandClass::Destructive)?;
+        if new_address == BROADCAST_ADDRESS {
+            return Err(Error::InvalidValue);
+        }
+        Ok(self.build_command(cmd::SET_SLAVE_ADDRESS, &[new_address]))
+    }
+
+    /// Generates a command selecting whether and how the board answers commands.
+    ///
+    /// Parse the reply with [`parse_response`]; when `respond` is being disabled
+    /// the board may not send one.
+    pub fn set_reply_mode(&self, mode: ReplyMode) -> CommandBytes<N> {
+        self.build_command(
+            cmd::SET_REPLY_MODE,
+            &[u8::from(mode.respond), u8::from(mode.active)],
+        )
+    }
+
+    /// Generates a command assigning the board to the group `group_address`.
+    ///
+    /// Every board of a group also accepts commands sent to the group address,
+    /// so a [`Driver::new(group_address)`](Driver::new) starts their moves
+    /// simultaneously. Parse the reply with [`parse_response`].
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `group_address` is [`BROADCAST_ADDRESS`].
+    pub fn set_group_address(&self, group_address: u8) -> Result<CommandBytes<N>> {
+        if group_address == BROADCAST_ADDRESS {
+            return Err(Error::InvalidValue);
+        }
+        Ok(self.build_command(cmd::SET_GROUP_ADDRESS, &[group_address]))
+    }
+
+    /// Generates a command locking (`true`) or unlocking the board's keys.
+    ///
+    /// A locked board ignores its on-device menu. Parse the reply with [`parse_response`].
+    pub fn set_key_lock(&self, lock: bool) -> CommandBytes<N> {
+        self.build_command(cmd::SET_KEY_LOCK, &[u8::from(lock)])
+    }
+
+    /// Generates a command reading the real-time motor speed.
+    ///
+    /// Parse the reply with [`parse_speed`].
+    pub fn read_speed(&self) -> CommandBytes<N> {
+        self.build_command(cmd::READ_SPEED, &[])
+    }
+
+    /// Generates a command reading the IN_1/IN_2/OUT_1/OUT_2 port levels.
+    ///
+    /// Parse the reply with [`parse_io_ports`].
+    pub fn read_io_ports(&self) -> CommandBytes<N> {
+        self.build_command(cmd::READ_IO_PORTS, &[])
+    }
+
+    /// Generates a command driving the OUT pins, e.g. for a brake release or a lamp.
+    ///
+    /// Parse the reply with [`parse_response`].
+    pub fn write_outputs(&self, outputs: Outputs) -> CommandBytes<N> {
+        self.build_command(cmd::WRITE_OUTPUTS, &[outputs.bits()])
+    }
+
+    /// Generates a command reading the progress of a homing run.
+    ///
+    /// Parse the reply with [`parse_home_status`].
+    pub fn read_home_status(&self) -> CommandBytes<N> {
+        self.build_command(cmd::READ_HOME_STATUS, &[])
+    }
+
+    /// Generates a command configuring homing.
+    ///
+    /// Parse the reply with [`parse_response`].
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the homing speed exceeds [`MAX_SPEED`].
+    pub fn set_home_params(&self, params: &HomeParams) -> Result<CommandBytes<N>> {
+        if params.speed > MAX_SPEED {
+            return Err(Error::InvalidValue);
+        }
+        let [speed_high, speed_low] = params.speed.to_be_bytes();
+        Ok(self.build_command(
+            cmd::SET_HOME_PARAMS,
+            &[
+                params.trigger_level as u8,
+                params.direction as u8,
+                speed_high,
+                speed_low,
+                u8::from(params.end_limit),
+            ],
+        ))
+    }
+
+    /// Generates a command starting a homing run with the configured [`HomeParams`].
+    ///
+    /// Parse the start and completion replies with [`parse_go_home_status`], or
+    /// poll [`read_home_status`](Self::read_home_status).
+    pub fn go_home(&self) -> CommandBytes<N> {
+        self.build_command(cmd::GO_HOME, &[])
+    }
+
+    /// Generates a command making the current position the axis zero.
+    ///
+    /// Parse the reply with [`parse_response`].
+    pub fn set_axis_to_zero(&self) -> CommandBytes<N> {
+        self.build_command(cmd::SET_AXIS_ZERO, &[])
+    }
+
+    /// Generates a command setting the holding current used while the motor is idle.
+    ///
+    /// Parse the reply with [`parse_response`].
+    pub fn set_hold_current(&self, current: HoldCurrent) -> CommandBytes<N> {
+        self.build_command(cmd::SET_HOLD_CURRENT, &[current.0])
+    }
+
+    /// Generates a command querying the motor run status.
+    ///
+    /// Parse the reply with [`parse_motor_status`].
+    pub fn query_motor_status(&self) -> CommandBytes<N> {
+        self.build_command(cmd::QUERY_MOTOR_STATUS, &[])
+    }
+
+    /// Generates a command running the motor at a constant `speed` in RPM.
+    ///
+    /// Unlike the 42C gear, the D firmware takes a real RPM value
+    /// (0-[`MAX_SPEED`]) and ramps to it with `acceleration` (0 disables ramping).
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `speed` exceeds [`MAX_SPEED`].
+    pub fn run_speed_mode(
+        &self,
+        direction: RotationDirection,
+        speed: u16,
+        acceleration: u8,
+    ) -> Result<CommandBytes<N>> {
+        if speed > MAX_SPEED {
+            return Err(Error::InvalidValue);
+        }
+        let dir_mask = match direction {
+            RotationDirection::Clockwise => 0x00,
+            RotationDirection::CounterClockwise => 0x80,
+        };
+        let [speed_high, speed_low] = speed.to_be_bytes();
+        Ok(self.build_command(
+            cmd::RUN_SPEED_MODE,
+            &[dir_mask | speed_high, speed_low, acceleration],
+        ))
+    }
+
+    /// Generates a command moving to the absolute position `pulses`.
+    ///
+    /// `speed` is in RPM (0-[`MAX_SPEED`]) and `acceleration` is the firmware
+    /// acceleration level (0 disables ramping). The board replies once when the
+    /// move starts and again when it ends; parse both with
+    /// [`parse_absolute_pulses_status`].
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `speed` exceeds [`MAX_SPEED`] or
+    /// `pulses` exceeds [`MAX_POSITION`] in magnitude.
+    pub fn run_absolute_pulses(
+        &self,
+        speed: u16,
+        acceleration: u8,
+        pulses: i32,
+    ) -> Result<CommandBytes<N>> {
+        self.build_position_command(cmd::RUN_ABSOLUTE_PULSES, speed, acceleration, pulses)
+    }
+
+    /// Generates a command moving to the absolute axis coordinate `axis`.
+    ///
+    /// The axis counts encoder units (0x4000 per turn) from the zero point.
+    /// `speed` and `acceleration` are as for
+    /// [`run_absolute_pulses`](Self::run_absolute_pulses). Parse the start and
+    /// completion replies with [`parse_absolute_axis_status`].
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `speed` exceeds [`MAX_SPEED`] or
+    /// `axis` exceeds [`MAX_POSITION`] in magnitude.
+    pub fn run_absolute_axis(
+        &self,
+        speed: u16,
+        acceleration: u8,
+        axis: i32,
+    ) -> Result<CommandBytes<N>> {
+        self.build_position_command(cmd::RUN_ABSOLUTE_AXIS, speed, acceleration, axis)
+    }
+
+    /// Encodes a typed [`Command`] shared with the 42C protocol.
+    ///
+    /// Commands whose D-series equivalent takes the same arguments are
+    /// translated to their D function codes; settings are bounded by the
+    /// driver's [`Model`]. The C-only speed gear, PID, acceleration, torque,
+    /// zero-mode and shaft-angle commands have no equivalent.
+    ///
+    /// # Errors
+    /// Returns `Error::NotPermitted` for commands the policy blocks,
+    /// `Error::Unsupported` for commands without a D equivalent and
+    /// `Error::InvalidValue` for out-of-range arguments.
+    pub fn encode(&self, command: &Command) -> Result<CommandBytes<N>> {
+        self.policy.check(command)?;
+        let (code, data): (u8, &[u8]) = match *command {
+            Command::EnableMotor(enable) => (cmd::ENABLE_MOTOR, &[u8::from(enable)]),
+            Command::Stop => (cmd::STOP, &[]),
+            Command::SaveClearStatus(operation) => (cmd::SAVE_CLEAR_STATUS, &[operation as u8]),
+            Command::CalibrateEncoder => (cmd::CALIBRATE_ENCODER, &[0x00]),
+            Command::SetCurrentLimit(current) => return self.set_working_current_ma(current.get()),
+            // Both firmwares take the microstep count, with 0 meaning 256.
+            Command::SetSubdivision(step_index) => (cmd::SET_SUBDIVISION, &[step_index]),
+            Command::SetEnableLogic(logic) => (cmd::SET_ENABLE_LOGIC, &[logic as u8]),
+            Command::SetDirection(direction) => (cmd::SET_DIRECTION, &[direction as u8]),
+            Command::SetAutoScreenOff(enable) => (cmd::SET_AUTO_SCREEN_OFF, &[u8::from(enable)]),
+            Command::SetStallProtection(enable) => (cmd::SET_STALL_PROTECTION, &[u8::from(enable)]),
+            Command::SetInterpolation(enable) => (cmd::SET_INTERPOLATION, &[u8::from(enable)]),
+            Command::SetCurrentAsZero => (cmd::SET_AXIS_ZERO, &[]),
+            Command::GoToZero => (cmd::GO_HOME, &[]),
+            Command::ReadShaftStatus => (cmd::READ_SHAFT_STATUS, &[]),
+            Command::ReadEncoderValue => (cmd::READ_ENCODER_VALUE, &[]),
+            Command::ReadPulseCount => (cmd::READ_PULSE_COUNT, &[]),
+            Command::ReadEnPinStatus => (cmd::READ_EN_PIN_STATUS, &[]),
+            Command::ReadMotorShaftAngleError => (cmd::READ_ANGLE_ERROR, &[]),
+            Command::ReadReleaseStatus => (cmd::READ_RELEASE_STATUS, &[]),
+            Command::RunWithConstantSpeed { .. }
+            | Command::RunMotor { .. }
+            | Command::SetZeroMode(_)
+            | Command::SetZeroSpeed(_)
+            | Command::SetZeroDirection(_)
+            | Command::SetPositionKp(_)
+            | Command::SetPositionKi(_)
+            | Command::SetPositionKd(_)
+            | Command::SetAcceleration(_)
+            | Command::SetMaxTorque(_)
+            | Command::ReadMotorShaftAngle => return Err(Error::Unsupported),
+        };
+        Ok(self.build_command(code, data))
+    }
+
+    fn build_position_command(
+        &self,
+        code: u8,
+        speed: u16,
+        acceleration: u8,
+        position: i32,
+    ) -> Result<CommandBytes<N>> {
+        if speed > MAX_SPEED || position.unsigned_abs() > MAX_POSITION.unsigned_abs() {
+            return Err(Error::InvalidValue);
+        }
+        let [speed_high, speed_low] = speed.to_be_bytes();
+        let [_, position_high, position_mid, position_low] = position.to_be_bytes();
+        Ok(self.build_command(
+            code,
+            &[
+                speed_high,
+                speed_low,
+                acceleration,
+                position_high,
+                position_mid,
+                position_low,
+            ],
+        ))
+    }
+
+    fn build_command(&self, code: u8, data: &[u8]) -> CommandBytes<N> {
+        let mut payload = [0; MAX_FRAME_LEN - 1];
+        payload[0] = DOWNLINK_HEADER;
+        payload[1] = self.address;
+        payload[2] = code;
+        payload[3..3 + data.len()].copy_from_slice(data);
+        CommandBytes::new(&payload[..3 + data.len()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_layout() {
+        let driver = Driver::new(0x02);
+        let cmd = driver.read_parameter(0x83);
+        assert_eq!(cmd.as_slice(), &[0xFA, 0x02, 0x00, 0x83, 0x7F]);
+    }
+
+    #[test]
+    fn test_set_work_mode() {
+        let driver: Driver = Driver::default();
+        assert_eq!(
+            driver.set_work_mode(WorkModeD::SrVfoc),
+            Err(Error::NotPermitted)
+        );
+        let driver = driver.with_policy(CommandPolicy::SAFE.allow_destructive());
+        assert_eq!(
+            driver.set_work_mode(WorkModeD::SrVfoc).unwrap().as_slice(),
+            &[0xFA, 0x01, 0x82, 0x05, 0x82]
+        );
+        assert_eq!(
+            driver.set_work_mode(WorkModeD::CrOpen).unwrap().as_slice()[3],
+            0x00
+        );
+    }
+
+    #[test]
+    fn test_read_back_commands() {
+        let driver: Driver = Driver::default();
+        assert_eq!(driver.read_work_mode(), driver.read_parameter(0x82));
+        assert_eq!(driver.read_working_current(), driver.read_parameter(0x83));
+        assert_eq!(driver.read_subdivision(), driver.read_parameter(0x84));
+        assert_eq!(driver.read_hold_current(), driver.read_parameter(0x9B));
+        assert_eq!(WorkModeD::try_from(0x04), Ok(WorkModeD::SrClose));
+        assert_eq!(WorkModeD::try_from(0x06), Err(Error::InvalidValue));
+        assert!(WorkModeD::SrVfoc.is_serial());
+        assert!(!WorkModeD::CrClose.is_serial());
+    }
+
+    #[test]
+    fn test_set_working_current_ma() {
+        let driver: Driver = Driver::default();
+        assert_eq!(
+            driver.set_working_current_ma(1600).unwrap().as_slice(),
+            &[0xFA, 0x01, 0x83, 0x06, 0x40, 0xC4]
+        );
+        assert!(driver.set_working_current_ma(3000).is_ok());
+        assert_eq!(
+            driver.set_working_current_ma(3001),
+            Err(Error::InvalidValue)
+        );
+        let driver = driver.with_model(Model::Servo57D);
+        assert_eq!(driver.model(), Model::Servo57D);
+        assert!(driver.set_working_current_ma(5200).is_ok());
+        assert_eq!(
+            driver.set_working_current_ma(5201),
+            Err(Error::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn test_set_baud_rate() {
+        let driver: Driver = Driver::default();
+        assert_eq!(
+            driver.set_baud_rate(BaudRate::Baud256000),
+            Err(Error::NotPermitted)
+        );
+        let driver = driver.with_policy(CommandPolicy::SAFE.allow_destructive());
+        assert_eq!(
+            driver
+                .set_baud_rate(BaudRate::Baud256000)
+                .unwrap()
+                .as_slice(),
+            &[0xFA, 0x01, 0x8A, 0x07, 0x8C]
+        );
+        assert_eq!(BaudRate::Baud256000.bps(), 256_000);
+        assert_eq!(BaudRate::Baud38400.bps(), 38400);
+    }
+
+    #[test]
+    fn test_set_reply_mode() {
+        let driver: Driver = Driver::default();
+        assert_eq!(
+            driver.set_reply_mode(ReplyMode::default()).as_slice(),
+            &[0xFA, 0x01, 0x8C, 0x01, 0x01, 0x89]
+        );
+        let passive = ReplyMode {
+            respond: true,
+            active: false,
+        };
+        assert_eq!(
+            driver.set_reply_mode(passive).as_slice(),
+            &[0xFA, 0x01, 0x8C, 0x01, 0x00, 0x88]
+        );
+        assert!(passive.expects_reply());
+        assert!(!passive.expects_completion());
+        let silent = ReplyMode {
+            respond: false,
+            active: true,
+        };
+        assert!(!silent.expects_reply());
+        assert!(!silent.expects_completion());
+    }
+
+    #[test]
+    fn test_set_slave_address() {
+        let mut driver: Driver = Driver::default();
+        assert_eq!(driver.set_slave_address(0x02), Err(Error::NotPermitted));
+        driver = driver.with_policy(CommandPolicy::SAFE.allow_destructive());
+        assert_eq!(
+            driver.set_slave_address(0x02).unwrap().as_slice(),
+            &[0xFA, 0x01, 0x8B, 0x02, 0x88]
+        );
+        assert_eq!(
+            driver.set_slave_address(BROADCAST_ADDRESS),
+            Err(Error::InvalidValue)
+        );
+        driver.set_address(0x02);
+        assert_eq!(driver.read_work_mode().as_slice()[1], 0x02);
+    }
+
+    #[test]
+    fn test_group_and_broadcast() {
+        let driver: Driver = Driver::default();
+        assert_eq!(
+            driver.set_group_address(0x10).unwrap().as_slice(),
+            &[0xFA, 0x01, 0x8D, 0x10, 0x98]
+        );
+        assert_eq!(
+            driver.set_group_address(BROADCAST_ADDRESS),
+            Err(Error::InvalidValue)
+        );
+
+        let all = Driver::broadcast();
+        assert_eq!(all.address(), BROADCAST_ADDRESS);
+        assert!(all.is_broadcast());
+        assert!(!all.expects_reply(ReplyMode::DEFAULT));
+        assert!(driver.expects_reply(ReplyMode::DEFAULT));
+        assert!(!driver.expects_reply(ReplyMode {
+            respond: false,
+            active: true,
+        }));
+        assert_eq!(
+            all.run_absolute_axis(600, 2, 0).unwrap().as_slice()[..3],
+            [0xFA, 0x00, 0xF5]
+        );
+    }
+
+    #[test]
+    fn test_set_key_lock() {
+        let driver: Driver = Driver::default();
+        assert_eq!(
+            driver.set_key_lock(true).as_slice(),
+            &[0xFA, 0x01, 0x8F, 0x01, 0x8B]
+        );
+        assert_eq!(
+            driver.set_key_lock(false).as_slice(),
+            &[0xFA, 0x01, 0x8F, 0x00, 0x8A]
+        );
+    }
+
+    #[test]
+    fn test_read_speed() {
+        let cmd = Driver::default().read_speed();
+        assert_eq!(cmd.as_slice(), &[0xFA, 0x01, 0x32, 0x2D]);
+    }
+
+    #[test]
+    fn test_read_io_ports() {
+        let cmd = Driver::default().read_io_ports();
+        assert_eq!(cmd.as_slice(), &[0xFA, 0x01, 0x34, 0x2F]);
+    }
+
+    #[test]
+    fn test_write_outputs() {
+        let driver: Driver = Driver::default();
+        let outputs = Outputs {
+            out_1: Some(true),
+            out_2: None,
+        };
+        assert_eq!(outputs.bits(), 0x44);
+        assert_eq!(
+            driver.write_outputs(outputs).as_slice(),
+            &[0xFA, 0x01, 0x36, 0x44, 0x75]
+        );
+        let outputs = Outputs {
+            out_1: Some(false),
+            out_2: Some(true),
+        };
+        assert_eq!(outputs.bits(), 0xC8);
+        assert_eq!(Outputs::default().bits(), 0x00);
+    }
+
+    #[test]
+    fn test_read_home_status() {
+        let cmd = Driver::default().read_home_status();
+        assert_eq!(cmd.as_slice(), &[0xFA, 0x01, 0x3B, 0x36]);
+    }
+
+    #[test]
+    fn test_set_home_params() {
+        let driver: Driver = Driver::default();
+        let mut params = HomeParams {
+            trigger_level: TriggerLevel::Low,
+            direction: RotationDirection::CounterClockwise,
+            speed: 60,
+            end_limit: true,
+        };
+        assert_eq!(
+            driver.set_home_params(&params).unwrap().as_slice(),
+            &[0xFA, 0x01, 0x90, 0x00, 0x01, 0x00, 0x3C, 0x01, 0xC9]
+        );
+        params.speed = MAX_SPEED + 1;
+        assert_eq!(driver.set_home_params(&params), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_homing_commands() {
+        let driver: Driver = Driver::default();
+        assert_eq!(driver.go_home().as_slice(), &[0xFA, 0x01, 0x91, 0x8C]);
+        assert_eq!(
+            driver.set_axis_to_zero().as_slice(),
+            &[0xFA, 0x01, 0x92, 0x8D]
+        );
+    }
+
+    #[test]
+    fn test_hold_current() {
+        assert_eq!(HoldCurrent::from_percent(10), Ok(HoldCurrent::MIN));
+        assert_eq!(HoldCurrent::from_percent(90), Ok(HoldCurrent::MAX));
+        assert_eq!(HoldCurrent::from_percent(50).unwrap().percent(), 50);
+        for percent in [0, 5, 55, 100] {
+            assert_eq!(HoldCurrent::from_percent(percent), Err(Error::InvalidValue));
+        }
+        let current = HoldCurrent::from_percent(50).unwrap();
+        assert_eq!(
+            Driver::default().set_hold_current(current).as_slice(),
+            &[0xFA, 0x01, 0x9B, 0x04, 0x9A]
+        );
+    }
+
+    #[test]
+    fn test_query_motor_status() {
+        let cmd = Driver::default().query_motor_status();
+        assert_eq!(cmd.as_slice(), &[0xFA, 0x01, 0xF1, 0xEC]);
+    }
+
+    #[test]
+    fn test_run_speed_mode() {
+        let driver: Driver = Driver::default();
+        let cmd = driver
+            .run_speed_mode(RotationDirection::CounterClockwise, 640, 2)
+            .unwrap();
+        assert_eq!(cmd.as_slice(), &[0xFA, 0x01, 0xF6, 0x82, 0x80, 0x02, 0xF5]);
+        let cmd = driver
+            .run_speed_mode(RotationDirection::Clockwise, MAX_SPEED, 0)
+            .unwrap();
+        assert_eq!(&cmd.as_slice()[3..6], &[0x0B, 0xB8, 0x00]);
+        assert_eq!(
+            driver.run_speed_mode(RotationDirection::Clockwise, MAX_SPEED + 1, 0),
+            Err(Error::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn test_run_absolute_pulses() {
+        let driver: Driver = Driver::default();
+        let cmd = driver.run_absolute_pulses(600, 2, 0x4000).unwrap();
+        assert_eq!(
+            cmd.as_slice(),
+            &[0xFA, 0x01, 0xFE, 0x02, 0x58, 0x02, 0x00, 0x40, 0x00, 0x95]
+        );
+        let cmd = driver.run_absolute_pulses(0, 0, -1).unwrap();
+        assert_eq!(&cmd.as_slice()[6..9], &[0xFF, 0xFF, 0xFF]);
+        assert_eq!(
+            driver.run_absolute_pulses(MAX_SPEED + 1, 2, 0),
+            Err(Error::InvalidValue)
+        );
+        assert_eq!(
+            driver.run_absolute_pulses(600, 2, MAX_POSITION + 1),
+            Err(Error::InvalidValue)
+        );
+        assert!(driver.run_absolute_pulses(600, 2, -MAX_POSITION).is_ok());
+    }
+
+    #[test]
+    fn test_run_absolute_axis() {
+        let driver: Driver = Driver::default();
+        let cmd = driver.run_absolute_axis(600, 2, -0x4000).unwrap();
+        assert_eq!(
+            cmd.as_slice(),
+            &[0xFA, 0x01, 0xF5, 0x02, 0x58, 0x02, 0xFF, 0xC0, 0x00, 0x0B]
+        );
+        assert_eq!(
+            driver.run_absolute_axis(600, 2, -MAX_POSITION - 1),
+            Err(Error::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        use crate::enums::{EnLogic, SaveClearStatus};
+        use crate::Milliamps;
+
+        let driver: Driver = Driver::default();
+        let cases: [(Command, &[u8]); 8] = [
+            (Command::EnableMotor(true), &[0xFA, 0x01, 0xF3, 0x01, 0xEF]),
+            (Command::Stop, &[0xFA, 0x01, 0xF7, 0xF2]),
+            (
+                Command::SaveClearStatus(SaveClearStatus::Save),
+                &[0xFA, 0x01, 0xFF, 0xC8, 0xC2],
+            ),
+            (Command::SetSubdivision(16), &[0xFA, 0x01, 0x84, 0x10, 0x8F]),
+            (Command::SetSubdivision(0), &[0xFA, 0x01, 0x84, 0x00, 0x7F]),
+            (
+                Command::SetEnableLogic(EnLogic::AlwaysOn),
+                &[0xFA, 0x01, 0x85, 0x02, 0x82],
+            ),
+            (Command::GoToZero, &[0xFA, 0x01, 0x91, 0x8C]),
+            (Command::ReadEncoderValue, &[0xFA, 0x01, 0x31, 0x2C]),
+        ];
+        for (command, expected) in cases {
+            assert_eq!(driver.encode(&command).unwrap().as_slice(), expected);
+        }
+        // Settings carry the same data byte as on the 42C: 00 disables, 01 enables.
+        let servo42c = crate::Driver::default();
+        for command in [
+            Command::SetAutoScreenOff(true),
+            Command::SetStallProtection(false),
+            Command::SetInterpolation(true),
+            Command::SetSubdivision(4),
+        ] {
+            let data = driver.encode(&command).unwrap()[3];
+            assert_eq!(data, servo42c.encode(&command).unwrap()[2]);
+        }
+        assert_eq!(
+            driver.encode(&Command::SetStallProtection(true)).unwrap()[3],
+            0x01
+        );
+        let current = Milliamps::new(1600).unwrap();
+        assert_eq!(
+            driver.encode(&Command::SetCurrentLimit(current)),
+            driver.set_working_current_ma(1600)
+        );
+        assert_eq!(
+            driver.encode(&Command::CalibrateEncoder),
+            Err(Error::NotPermitted)
+        );
+        assert_eq!(
+            driver.encode(&Command::SetPositionKp(1)),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(
+            driver.encode(&Command::ReadMotorShaftAngle),
+            Err(Error::Unsupported)
+        );
+    }
+
+    #[test]
+    fn test_address() {
+        let mut driver = Driver::default();
+        assert_eq!(driver.address(), DEFAULT_ADDRESS);
+        driver.set_address(BROADCAST_ADDRESS);
+        assert_eq!(driver.read_parameter(0x00)[1], BROADCAST_ADDRESS);
+        let wide: Driver<16> = driver.with_buffer_size();
+        assert_eq!(wide.address(), BROADCAST_ADDRESS);
+    }
+}
@@ -0,0 +1,477 @@
+use super::{cmd, HoldCurrent, WorkModeD, UPLINK_HEADER};
+use crate::{calculate_checksum, Error, Response};
+
+/// A checksum-verified reply frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reply<'a> {
+    /// Address of the answering board.
+    pub address: u8,
+    /// Command code the reply answers.
+    pub code: u8,
+    /// Data bytes between the code and the checksum.
+    pub data: &'a [u8],
+}
+
+/// Finds the first valid reply to `code` carrying `len` data bytes.
+///
+/// Leading garbage and frames failing the checksum are skipped.
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no such reply is found in `data`.
+pub fn parse_reply(data: &[u8], code: u8, len: usize) -> Result<Reply<'_>, Error> {
+    let frame_len = len + 4;
+    for (start, window) in data.windows(frame_len).enumerate() {
+        if window[0] != UPLINK_HEADER || window[2] != code {
+            continue;
+        }
+        if calculate_checksum(&window[..frame_len - 1]) != window[frame_len - 1] {
+            continue;
+        }
+        return Ok(Reply {
+            address: window[1],
+            code,
+            data: &data[start + 3..start + 3 + len],
+        });
+    }
+    Err(Error::InvalidPacket)
+}
+
+/// Parses the success/failure reply of a write command with function `code`.
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_response(data: &[u8], code: u8) -> Result<Response, Error> {
+    let reply = parse_reply(data, code, 1)?;
+    Response::try_from(reply.data[0]).map_err(|_| Error::InvalidPacket)
+}
+
+/// Parses the reply to [`read_speed`](super::Driver::read_speed).
+///
+/// Returns the speed in RPM; negative values mean counterclockwise rotation.
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_speed(data: &[u8]) -> Result<i16, Error> {
+    let reply = parse_reply(data, cmd::READ_SPEED, 2)?;
+    Ok(i16::from_be_bytes([reply.data[0], reply.data[1]]))
+}
+
+/// IO port levels reported by [`read_io_ports`](super::Driver::read_io_ports).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IoPorts(u8);
+
+impl IoPorts {
+    const IN_1: u8 = 1 << 0;
+    const IN_2: u8 = 1 << 1;
+    const OUT_1: u8 = 1 << 2;
+    const OUT_2: u8 = 1 << 3;
+
+    /// Creates port levels from the raw status byte.
+    #[must_use]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw status byte.
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if IN_1 is high.
+    #[must_use]
+    pub const fn in_1(self) -> bool {
+        self.0 & Self::IN_1 != 0
+    }
+
+    /// Returns `true` if IN_2 is high.
+    #[must_use]
+    pub const fn in_2(self) -> bool {
+        self.0 & Self::IN_2 != 0
+    }
+
+    /// Returns `true` if OUT_1 is high.
+    #[must_use]
+    pub const fn out_1(self) -> bool {
+        self.0 & Self::OUT_1 != 0
+    }
+
+    /// Returns `true` if OUT_2 is high.
+    #[must_use]
+    pub const fn out_2(self) -> bool {
+        self.0 & Self::OUT_2 != 0
+    }
+}
+
+/// Parses the reply to [`read_io_ports`](super::Driver::read_io_ports).
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_io_ports(data: &[u8]) -> Result<IoPorts, Error> {
+    let reply = parse_reply(data, cmd::READ_IO_PORTS, 1)?;
+    Ok(IoPorts::from_bits(reply.data[0]))
+}
+
+/// Homing progress reported by [`read_home_status`](super::Driver::read_home_status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HomeStatus {
+    /// Homing is in progress.
+    Going,
+    /// Homing succeeded.
+    Success,
+    /// Homing failed.
+    Failed,
+}
+
+impl TryFrom<u8> for HomeStatus {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::Going),
+            0x01 => Ok(Self::Success),
+            0x02 => Ok(Self::Failed),
+            _ => Err(Error::InvalidPacket),
+        }
+    }
+}
+
+/// Parses the reply to [`read_home_status`](super::Driver::read_home_status).
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_home_status(data: &[u8]) -> Result<HomeStatus, Error> {
+    let reply = parse_reply(data, cmd::READ_HOME_STATUS, 1)?;
+    HomeStatus::try_from(reply.data[0])
+}
+
+/// Parses the reply to [`read_work_mode`](super::Driver::read_work_mode).
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_work_mode(data: &[u8]) -> Result<WorkModeD, Error> {
+    let reply = parse_reply(data, cmd::SET_WORK_MODE, 1)?;
+    WorkModeD::try_from(reply.data[0]).map_err(|_| Error::InvalidPacket)
+}
+
+/// Parses the reply to [`read_working_current`](super::Driver::read_working_current), in mA.
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_working_current_ma(data: &[u8]) -> Result<u16, Error> {
+    let reply = parse_reply(data, cmd::SET_WORKING_CURRENT, 2)?;
+    Ok(u16::from_be_bytes([reply.data[0], reply.data[1]]))
+}
+
+/// Parses the reply to [`read_subdivision`](super::Driver::read_subdivision),
+/// in microsteps per full step.
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_subdivision(data: &[u8]) -> Result<u16, Error> {
+    let reply = parse_reply(data, cmd::SET_SUBDIVISION, 1)?;
+    // The firmware stores 256 microsteps as 0.
+    Ok(match reply.data[0] {
+        0 => 256,
+        microsteps => u16::from(microsteps),
+    })
+}
+
+/// Parses the reply to [`read_hold_current`](super::Driver::read_hold_current).
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_hold_current(data: &[u8]) -> Result<HoldCurrent, Error> {
+    let reply = parse_reply(data, cmd::SET_HOLD_CURRENT, 1)?;
+    if reply.data[0] > HoldCurrent::MAX.0 {
+        return Err(Error::InvalidPacket);
+    }
+    Ok(HoldCurrent(reply.data[0]))
+}
+
+/// Motor run status reported by the 0xF1 query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MotorStatus {
+    /// The query failed.
+    Failed,
+    /// The motor is stopped.
+    Stopped,
+    /// The motor is accelerating.
+    Accelerating,
+    /// The motor is decelerating.
+    Decelerating,
+    /// The motor runs at full speed.
+    Running,
+    /// The motor is homing.
+    Homing,
+    /// The encoder is being calibrated.
+    Calibrating,
+}
+
+impl MotorStatus {
+    /// Returns `true` if the motor is stopped, i.e. the last move completed.
+    #[must_use]
+    pub const fn is_stopped(self) -> bool {
+        matches!(self, Self::Stopped)
+    }
+}
+
+impl TryFrom<u8> for MotorStatus {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::Failed),
+            0x01 => Ok(Self::Stopped),
+            0x02 => Ok(Self::Accelerating),
+            0x03 => Ok(Self::Decelerating),
+            0x04 => Ok(Self::Running),
+            0x05 => Ok(Self::Homing),
+            0x06 => Ok(Self::Calibrating),
+            _ => Err(Error::InvalidPacket),
+        }
+    }
+}
+
+/// Parses the reply to [`query_motor_status`](super::Driver::query_motor_status).
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_motor_status(data: &[u8]) -> Result<MotorStatus, Error> {
+    let reply = parse_reply(data, cmd::QUERY_MOTOR_STATUS, 1)?;
+    MotorStatus::try_from(reply.data[0])
+}
+
+/// Progress of a position move, reported when it starts and when it ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveStatus {
+    /// The board rejected the move.
+    Failed,
+    /// The move started.
+    Started,
+    /// The move completed.
+    Completed,
+    /// The move was stopped by an end-limit switch.
+    EndLimitStopped,
+}
+
+impl TryFrom<u8> for MoveStatus {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::Failed),
+            0x01 => Ok(Self::Started),
+            0x02 => Ok(Self::Completed),
+            0x03 => Ok(Self::EndLimitStopped),
+            _ => Err(Error::InvalidPacket),
+        }
+    }
+}
+
+/// Parses a reply to [`run_absolute_pulses`](super::Driver::run_absolute_pulses).
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_absolute_pulses_status(data: &[u8]) -> Result<MoveStatus, Error> {
+    parse_move_status(data, cmd::RUN_ABSOLUTE_PULSES)
+}
+
+/// Parses a reply to [`run_absolute_axis`](super::Driver::run_absolute_axis).
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_absolute_axis_status(data: &[u8]) -> Result<MoveStatus, Error> {
+    parse_move_status(data, cmd::RUN_ABSOLUTE_AXIS)
+}
+
+/// Parses a reply to [`go_home`](super::Driver::go_home).
+///
+/// # Errors
+/// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+pub fn parse_go_home_status(data: &[u8]) -> Result<MoveStatus, Error> {
+    parse_move_status(data, cmd::GO_HOME)
+}
+
+fn parse_move_status(data: &[u8], code: u8) -> Result<MoveStatus, Error> {
+    let reply = parse_reply(data, code, 1)?;
+    MoveStatus::try_from(reply.data[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reply() {
+        let data = [0x00, 0xFB, 0x01, 0xF1, 0x01, 0xEE];
+        let reply = parse_reply(&data, 0xF1, 1).unwrap();
+        assert_eq!(reply.address, 0x01);
+        assert_eq!(reply.data, &[0x01]);
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            parse_response(&[0xFB, 0x01, 0x36, 0x01, 0x33], 0x36),
+            Ok(Response::Success)
+        );
+        assert_eq!(
+            parse_response(&[0xFB, 0x01, 0x36, 0x00, 0x32], 0x36),
+            Ok(Response::Failure)
+        );
+        assert_eq!(
+            parse_response(&[0xFB, 0x01, 0x36, 0x02, 0x34], 0x36),
+            Err(Error::InvalidPacket)
+        );
+    }
+
+    #[test]
+    fn test_parse_speed() {
+        assert_eq!(parse_speed(&[0xFB, 0x01, 0x32, 0x01, 0x2C, 0x5B]), Ok(300));
+        assert_eq!(parse_speed(&[0xFB, 0x01, 0x32, 0xFE, 0xD4, 0x00]), Ok(-300));
+        assert_eq!(
+            parse_speed(&[0xFB, 0x01, 0x32, 0x01, 0x2C]),
+            Err(Error::InvalidPacket)
+        );
+    }
+
+    #[test]
+    fn test_parse_io_ports() {
+        let ports = parse_io_ports(&[0xFB, 0x01, 0x34, 0x05, 0x35]).unwrap();
+        assert!(ports.in_1());
+        assert!(!ports.in_2());
+        assert!(ports.out_1());
+        assert!(!ports.out_2());
+        assert_eq!(ports.bits(), 0x05);
+        assert!(IoPorts::from_bits(0x0A).out_2());
+        assert_eq!(
+            parse_io_ports(&[0xFB, 0x01, 0x34, 0x05, 0x36]),
+            Err(Error::InvalidPacket)
+        );
+    }
+
+    #[test]
+    fn test_parse_home_status() {
+        assert_eq!(
+            parse_home_status(&[0xFB, 0x01, 0x3B, 0x00, 0x37]),
+            Ok(HomeStatus::Going)
+        );
+        assert_eq!(
+            parse_home_status(&[0xFB, 0x01, 0x3B, 0x02, 0x39]),
+            Ok(HomeStatus::Failed)
+        );
+        assert_eq!(
+            parse_home_status(&[0xFB, 0x01, 0x3B, 0x03, 0x3A]),
+            Err(Error::InvalidPacket)
+        );
+    }
+
+    #[test]
+    fn test_parse_read_back() {
+        assert_eq!(
+            parse_work_mode(&[0xFB, 0x01, 0x82, 0x05, 0x83]),
+            Ok(WorkModeD::SrVfoc)
+        );
+        assert_eq!(
+            parse_work_mode(&[0xFB, 0x01, 0x82, 0x06, 0x84]),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            parse_working_current_ma(&[0xFB, 0x01, 0x83, 0x06, 0x40, 0xC5]),
+            Ok(1600)
+        );
+        assert_eq!(parse_subdivision(&[0xFB, 0x01, 0x84, 0x10, 0x90]), Ok(16));
+        assert_eq!(parse_subdivision(&[0xFB, 0x01, 0x84, 0x00, 0x80]), Ok(256));
+        assert_eq!(
+            parse_hold_current(&[0xFB, 0x01, 0x9B, 0x04, 0x9B]).map(HoldCurrent::percent),
+            Ok(50)
+        );
+        assert_eq!(
+            parse_hold_current(&[0xFB, 0x01, 0x9B, 0x09, 0xA0]),
+            Err(Error::InvalidPacket)
+        );
+    }
+
+    #[test]
+    fn test_parse_motor_status() {
+        assert_eq!(
+            parse_motor_status(&[0xFB, 0x01, 0xF1, 0x01, 0xEE]),
+            Ok(MotorStatus::Stopped)
+        );
+        assert_eq!(
+            parse_motor_status(&[0xFB, 0x01, 0xF1, 0x05, 0xF2]),
+            Ok(MotorStatus::Homing)
+        );
+        assert_eq!(
+            parse_motor_status(&[0xFB, 0x01, 0xF1, 0x07, 0xF4]),
+            Err(Error::InvalidPacket)
+        );
+        assert!(MotorStatus::Stopped.is_stopped());
+        assert!(!MotorStatus::Running.is_stopped());
+    }
+
+    #[test]
+    fn test_parse_absolute_pulses_status() {
+        assert_eq!(
+            parse_absolute_pulses_status(&[0xFB, 0x01, 0xFE, 0x01, 0xFB]),
+            Ok(MoveStatus::Started)
+        );
+        assert_eq!(
+            parse_absolute_pulses_status(&[0xFB, 0x01, 0xFE, 0x02, 0xFC]),
+            Ok(MoveStatus::Completed)
+        );
+        assert_eq!(
+            parse_absolute_pulses_status(&[0xFB, 0x01, 0xFE, 0x03, 0xFD]),
+            Ok(MoveStatus::EndLimitStopped)
+        );
+        assert_eq!(
+            parse_absolute_pulses_status(&[0xFB, 0x01, 0xF1, 0x02, 0xEF]),
+            Err(Error::InvalidPacket)
+        );
+    }
+
+    #[test]
+    fn test_parse_absolute_axis_status() {
+        assert_eq!(
+            parse_absolute_axis_status(&[0xFB, 0x01, 0xF5, 0x02, 0xF3]),
+            Ok(MoveStatus::Completed)
+        );
+        assert_eq!(
+            parse_absolute_axis_status(&[0xFB, 0x01, 0xF5, 0x00, 0xF1]),
+            Ok(MoveStatus::Failed)
+        );
+        assert_eq!(
+            parse_absolute_axis_status(&[0xFB, 0x01, 0xFE, 0x02, 0xFC]),
+            Err(Error::InvalidPacket)
+        );
+    }
+
+    #[test]
+    fn test_parse_go_home_status() {
+        assert_eq!(
+            parse_go_home_status(&[0xFB, 0x01, 0x91, 0x01, 0x8E]),
+            Ok(MoveStatus::Started)
+        );
+        assert_eq!(
+            parse_go_home_status(&[0xFB, 0x01, 0x91, 0x02, 0x8F]),
+            Ok(MoveStatus::Completed)
+        );
+    }
+
+    #[test]
+    fn test_parse_reply_rejects_bad_frames() {
+        assert_eq!(
+            parse_reply(&[0xFB, 0x01, 0xF1, 0x01, 0xEF], 0xF1, 1),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            parse_reply(&[0xFB, 0x01, 0xF1, 0x01, 0xEE], 0x30, 1),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            parse_reply(&[0xFB, 0x01], 0xF1, 1),
+            Err(Error::InvalidPacket)
+        );
+    }
+}
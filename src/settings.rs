@@ -0,0 +1,140 @@
+use crate::helpers::parse_ack;
+use crate::{
+    Command, EnLogic, Milliamps, Response, Result, RotationDirection, TorqueLimit, ZeroMode,
+};
+
+/// Settings last applied to one board, as far as this side of the link knows.
+///
+/// The firmware cannot report most settings back, so the values are recorded
+/// from the configuration commands the board acknowledged. `None` means the
+/// setting has not been written since the cache was created:
+///
+/// ```
+/// use mks_servo42_rs::{Command, RotationDirection, Settings};
+///
+/// let mut settings = Settings::default();
+/// assert_eq!(settings.direction, None);
+///
+/// let command = Command::SetDirection(RotationDirection::CounterClockwise);
+/// settings.record_reply(&command, &[0xE0, 0x01, 0xE1])?;
+/// assert_eq!(settings.direction, Some(RotationDirection::CounterClockwise));
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+///
+/// The subdivision is not cached here: the [`Driver`](crate::Driver) tracks
+/// it for its angle to pulse conversions, so record an acknowledged
+/// `SetSubdivision` with [`Driver::record`](crate::Driver::record).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Settings {
+    /// Working current limit.
+    pub current: Option<Milliamps>,
+    /// Direction polarity.
+    pub direction: Option<RotationDirection>,
+    /// EN pin logic.
+    pub enable_logic: Option<EnLogic>,
+    /// Automatic screen off.
+    pub auto_screen_off: Option<bool>,
+    /// Stall protection.
+    pub stall_protection: Option<bool>,
+    /// Subdivision interpolation.
+    pub interpolation: Option<bool>,
+    /// Return-to-zero mode.
+    pub zero_mode: Option<ZeroMode>,
+    /// Return-to-zero speed.
+    pub zero_speed: Option<u8>,
+    /// Return-to-zero direction.
+    pub zero_direction: Option<RotationDirection>,
+    /// Position loop Kp.
+    pub kp: Option<u16>,
+    /// Position loop Ki.
+    pub ki: Option<u16>,
+    /// Position loop Kd.
+    pub kd: Option<u16>,
+    /// Acceleration (ACC).
+    pub acceleration: Option<u16>,
+    /// Maximum torque.
+    pub max_torque: Option<TorqueLimit>,
+}
+
+impl Settings {
+    /// Records a configuration command the board has acknowledged.
+    ///
+    /// Commands that change no stored setting, including `SetSubdivision`,
+    /// are ignored.
+    pub fn record(&mut self, command: &Command) {
+        match *command {
+            Command::SetCurrentLimit(current) => self.current = Some(current),
+            Command::SetEnableLogic(logic) => self.enable_logic = Some(logic),
+            Command::SetDirection(direction) => self.direction = Some(direction),
+            Command::SetAutoScreenOff(enable) => self.auto_screen_off = Some(enable),
+            Command::SetStallProtection(enable) => self.stall_protection = Some(enable),
+            Command::SetInterpolation(enable) => self.interpolation = Some(enable),
+            Command::SetZeroMode(mode) => self.zero_mode = Some(mode),
+            Command::SetZeroSpeed(speed) => self.zero_speed = Some(speed),
+            Command::SetZeroDirection(direction) => self.zero_direction = Some(direction),
+            Command::SetPositionKp(value) => self.kp = Some(value),
+            Command::SetPositionKi(value) => self.ki = Some(value),
+            Command::SetPositionKd(value) => self.kd = Some(value),
+            Command::SetAcceleration(value) => self.acceleration = Some(value),
+            Command::SetMaxTorque(limit) => self.max_torque = Some(limit),
+            _ => {}
+        }
+    }
+
+    /// Parses the acknowledgement of `command` and records it if the board
+    /// reported success.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidPacket` if `reply` holds no valid acknowledgement.
+    pub fn record_reply(&mut self, command: &Command, reply: &[u8]) -> Result<Response> {
+        let ack = parse_ack(reply)?;
+        if ack.is_success() {
+            self.record(command);
+        }
+        Ok(ack.response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn test_record() {
+        let mut settings = Settings::default();
+        settings.record(&Command::SetPositionKp(0x120));
+        settings.record(&Command::SetDirection(RotationDirection::CounterClockwise));
+        settings.record(&Command::SetSubdivision(0x7F));
+        settings.record(&Command::Stop);
+        // The subdivision is left to `Driver::record`.
+        assert_eq!(
+            settings,
+            Settings {
+                kp: Some(0x120),
+                direction: Some(RotationDirection::CounterClockwise),
+                ..Settings::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_record_reply() {
+        let mut settings = Settings::default();
+        let command = Command::SetAcceleration(0x80);
+        assert_eq!(
+            settings.record_reply(&command, &[0xE0, 0x00, 0xE0]),
+            Ok(Response::Failure)
+        );
+        assert_eq!(settings.acceleration, None);
+        assert_eq!(
+            settings.record_reply(&command, &[0xE0, 0x01]),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(
+            settings.record_reply(&command, &[0xE0, 0x01, 0xE1]),
+            Ok(Response::Success)
+        );
+        assert_eq!(settings.acceleration, Some(0x80));
+    }
+}
@@ -0,0 +1,1865 @@
+//! Scheduled polling of read commands.
+//!
+//! [`Poller`] does no I/O itself: the caller asks it which frame to send next,
+//! transmits it, and feeds the reply back. Parsed samples go to a callback.
+//!
+//! ```
+//! use mks_servo42_rs::telemetry::{Channel, Poller, Reading};
+//! use mks_servo42_rs::Address;
+//!
+//! let mut poller: Poller = Poller::new(Address::DEFAULT);
+//! poller.schedule_hz(Channel::Encoder, 50)?;
+//! poller.schedule_hz(Channel::ShaftStatus, 5)?;
+//!
+//! let now_ms = 0;
+//! let frame = poller.poll(now_ms).unwrap();
+//! assert_eq!(frame, [0xE0, 0x30, 0x10]);
+//!
+//! // ...send `frame`, then read the reply...
+//! let reply = [0xE0, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x20];
+//! poller.handle_response(now_ms, &reply, &mut |sample| {
+//!     assert!(matches!(sample.reading, Reading::Encoder(_)));
+//! })?;
+//! # Ok::<(), mks_servo42_rs::Error>(())
+//! ```
+
+use crate::enums::RotationDirection;
+use crate::enums::ShaftStatus;
+use crate::frames::ReadFrames;
+use crate::helpers::{
+    parse_en_pin_status_response, parse_encoder_response, parse_motor_shaft_angle_error,
+    parse_motor_shaft_angle_response, parse_pulse_count_response, parse_shaft_status_response,
+    parse_success_response, EnPinStatus, EncoderValue, MotorShaftAngle, PulseCount, ShaftErrValue,
+};
+use crate::units::{Degrees, Pulses, Revolutions, Speed};
+use crate::{Address, Command, Error, Response};
+
+/// A read command the poller can schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// `read_encoder_value` (0x30).
+    Encoder,
+    /// `read_pulse_count` (0x33).
+    PulseCount,
+    /// `read_motor_shaft_angle` (0x36).
+    MotorShaftAngle,
+    /// `read_motor_shaft_angle_error` (0x39).
+    AngleError,
+    /// `read_en_pin_status` (0x3A).
+    EnPinStatus,
+    /// `read_release_status` (0x3D).
+    ReleaseStatus,
+    /// `read_shaft_status` (0x3E).
+    ShaftStatus,
+}
+
+impl Channel {
+    /// Returns this channel's frame from a set of pre-encoded read frames.
+    #[must_use]
+    pub const fn frame(self, reads: &ReadFrames) -> [u8; 3] {
+        match self {
+            Self::Encoder => reads.encoder_value,
+            Self::PulseCount => reads.pulse_count,
+            Self::MotorShaftAngle => reads.motor_shaft_angle,
+            Self::AngleError => reads.motor_shaft_angle_error,
+            Self::EnPinStatus => reads.en_pin_status,
+            Self::ReleaseStatus => reads.release_status,
+            Self::ShaftStatus => reads.shaft_status,
+        }
+    }
+
+    /// Returns the length of a reply to this channel's read command.
+    #[must_use]
+    pub const fn reply_len(self) -> usize {
+        match self {
+            Self::Encoder => 8,
+            Self::PulseCount | Self::MotorShaftAngle => 6,
+            // Includes the undocumented trailing 0x00.
+            Self::AngleError => 5,
+            Self::EnPinStatus | Self::ReleaseStatus | Self::ShaftStatus => 3,
+        }
+    }
+
+    /// Parses a reply to this channel's read command.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidPacket` if no valid reply is found in `data`.
+    pub fn parse(self, data: &[u8]) -> Result<Reading, Error> {
+        Ok(match self {
+            Self::Encoder => Reading::Encoder(parse_encoder_response(data)?),
+            Self::PulseCount => Reading::PulseCount(parse_pulse_count_response(data)?),
+            Self::MotorShaftAngle => {
+                Reading::MotorShaftAngle(parse_motor_shaft_angle_response(data)?)
+            }
+            Self::AngleError => Reading::AngleError(parse_motor_shaft_angle_error(data)?),
+            Self::EnPinStatus => Reading::EnPinStatus(parse_en_pin_status_response(data)?),
+            Self::ReleaseStatus => Reading::ReleaseStatus(parse_success_response(data)?),
+            Self::ShaftStatus => Reading::ShaftStatus(parse_shaft_status_response(data)?),
+        })
+    }
+}
+
+/// A parsed reply to a scheduled read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    /// Reply to [`Channel::Encoder`].
+    Encoder(EncoderValue),
+    /// Reply to [`Channel::PulseCount`].
+    PulseCount(PulseCount),
+    /// Reply to [`Channel::MotorShaftAngle`].
+    MotorShaftAngle(MotorShaftAngle),
+    /// Reply to [`Channel::AngleError`].
+    AngleError(ShaftErrValue),
+    /// Reply to [`Channel::EnPinStatus`].
+    EnPinStatus(EnPinStatus),
+    /// Reply to [`Channel::ReleaseStatus`].
+    ReleaseStatus(Response),
+    /// Reply to [`Channel::ShaftStatus`].
+    ShaftStatus(ShaftStatus),
+}
+
+/// A status frame received without a request of its own, such as the
+/// completion notice of an earlier `run_motor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnsolicitedFrame {
+    /// Board that sent the frame.
+    pub address: Address,
+    /// Status byte: 0 failed, 1 started or succeeded, 2 move complete.
+    pub status: u8,
+}
+
+impl UnsolicitedFrame {
+    /// Parses a three-byte status frame.
+    fn parse(frame: &[u8]) -> Option<Self> {
+        let &[address, status, checksum] = frame else {
+            return None;
+        };
+        let valid = status <= 2 && address.wrapping_add(status) == checksum;
+        let address = Address::new(address).ok().filter(|_| valid)?;
+        Some(Self { address, status })
+    }
+}
+
+/// Splits status frames received before or after the reply to `channel`
+/// off `data`, passing each to `sink`, and returns the remaining bytes.
+///
+/// A frame is only split off while `data` is longer than the reply, and only
+/// if the bytes left still parse as a reply to `channel`.
+///
+/// ```
+/// use mks_servo42_rs::telemetry::{split_unsolicited, Channel};
+///
+/// // A late "move complete" arrives ahead of the angle error reply.
+/// let data = [0xE0, 0x02, 0xE2, 0xE0, 0x00, 0x10, 0xF0, 0x00];
+/// let mut late = None;
+/// let reply = split_unsolicited(Channel::AngleError, &data, &mut |frame| late = Some(frame))?;
+/// assert_eq!(reply, [0xE0, 0x00, 0x10, 0xF0, 0x00]);
+/// assert_eq!(late.map(|frame| frame.status), Some(2));
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+///
+/// # Errors
+/// Returns `Error::Ambiguous` if splitting a frame off either end leaves a
+/// valid reply and the two replies differ, as with two status frames in
+/// answer to a three-byte read.
+pub fn split_unsolicited<'a>(
+    channel: Channel,
+    data: &'a [u8],
+    sink: &mut impl FnMut(UnsolicitedFrame),
+) -> Result<&'a [u8], Error> {
+    let mut data = data;
+    while data.len() > channel.reply_len() {
+        let split = |frame: &[u8], rest: &'a [u8]| {
+            let frame = UnsolicitedFrame::parse(frame)?;
+            let reading = channel.parse(rest).ok()?;
+            Some((frame, rest, reading))
+        };
+        let (head, rest) = data.split_at(3);
+        let (front, tail) = data.split_at(data.len() - 3);
+        let (frame, rest) = match (split(head, rest), split(tail, front)) {
+            (Some((_, _, first)), Some((_, _, last))) if first != last => {
+                return Err(Error::Ambiguous);
+            }
+            (Some((frame, rest, _)), _) | (None, Some((frame, rest, _))) => (frame, rest),
+            (None, None) => break,
+        };
+        sink(frame);
+        data = rest;
+    }
+    Ok(data)
+}
+
+/// A reading together with the time its reply was handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    /// Caller-supplied timestamp in milliseconds.
+    pub timestamp_ms: u64,
+    /// The parsed reading.
+    pub reading: Reading,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    channel: Channel,
+    period_ms: u32,
+    next_due_ms: u64,
+}
+
+/// Sequences read commands according to per-channel periods.
+///
+/// Only one read is in flight at a time; when several channels are due, the
+/// most overdue one goes first. `M` is the maximum number of scheduled channels.
+#[derive(Debug, Clone)]
+pub struct Poller<const M: usize = 8> {
+    reads: ReadFrames,
+    slots: [Option<Slot>; M],
+    pending: Option<Channel>,
+}
+
+impl<const M: usize> Poller<M> {
+    /// Creates a poller with an empty schedule for the board at `address`.
+    #[must_use]
+    pub const fn new(address: Address) -> Self {
+        Self {
+            reads: ReadFrames::new(address),
+            slots: [None; M],
+            pending: None,
+        }
+    }
+
+    /// Polls `channel` every `period_ms`, replacing its previous period if any.
+    ///
+    /// The first read is due immediately.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `period_ms` is zero, or
+    /// `Error::BufferTooSmall` if all `M` slots are taken.
+    pub fn schedule(&mut self, channel: Channel, period_ms: u32) -> Result<(), Error> {
+        if period_ms == 0 {
+            return Err(Error::InvalidValue);
+        }
+        let slot = Slot {
+            channel,
+            period_ms,
+            next_due_ms: 0,
+        };
+        if let Some(existing) = self
+            .slots
+            .iter_mut()
+            .flatten()
+            .find(|slot| slot.channel == channel)
+        {
+            existing.period_ms = period_ms;
+            return Ok(());
+        }
+        let free = self
+            .slots
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(Error::BufferTooSmall)?;
+        *free = Some(slot);
+        Ok(())
+    }
+
+    /// Polls `channel` at `hz` reads per second.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `hz` is zero or above 1000.
+    pub fn schedule_hz(&mut self, channel: Channel, hz: u32) -> Result<(), Error> {
+        if hz == 0 {
+            return Err(Error::InvalidValue);
+        }
+        self.schedule(channel, 1000 / hz)
+    }
+
+    /// Removes `channel` from the schedule.
+    pub fn unschedule(&mut self, channel: Channel) {
+        for slot in &mut self.slots {
+            if slot.is_some_and(|slot| slot.channel == channel) {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Returns the next frame to send at `now_ms`, if a read is due.
+    ///
+    /// Returns `None` while a previous read is still awaiting its reply.
+    pub fn poll(&mut self, now_ms: u64) -> Option<[u8; 3]> {
+        if self.pending.is_some() {
+            return None;
+        }
+        let slot = self
+            .slots
+            .iter_mut()
+            .flatten()
+            .filter(|slot| slot.next_due_ms <= now_ms)
+            .min_by_key(|slot| slot.next_due_ms)?;
+
+        let next_due_ms = slot.next_due_ms + u64::from(slot.period_ms);
+        // Skip missed periods instead of bursting to catch up
+        slot.next_due_ms = if next_due_ms <= now_ms {
+            now_ms + u64::from(slot.period_ms)
+        } else {
+            next_due_ms
+        };
+        self.pending = Some(slot.channel);
+        Some(slot.channel.frame(&self.reads))
+    }
+
+    /// Parses the reply to the in-flight read and passes the sample to `sink`.
+    ///
+    /// The read is completed even if parsing fails.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidPacket` if no read is in flight or `data` holds no
+    /// valid reply, and `Error::Ambiguous` if a late status frame cannot be
+    /// told apart from the reply (see [`split_unsolicited`]).
+    pub fn handle_response(
+        &mut self,
+        now_ms: u64,
+        data: &[u8],
+        sink: &mut impl FnMut(Sample),
+    ) -> Result<(), Error> {
+        self.handle_response_with_unsolicited(now_ms, data, sink, &mut |_| {})
+    }
+
+    /// Like [`handle_response`](Self::handle_response), but also passes the
+    /// status frames received around the reply to `unsolicited`; see
+    /// [`split_unsolicited`].
+    ///
+    /// # Errors
+    /// Same as [`handle_response`](Self::handle_response).
+    pub fn handle_response_with_unsolicited(
+        &mut self,
+        now_ms: u64,
+        data: &[u8],
+        sink: &mut impl FnMut(Sample),
+        unsolicited: &mut impl FnMut(UnsolicitedFrame),
+    ) -> Result<(), Error> {
+        let channel = self.pending.take().ok_or(Error::InvalidPacket)?;
+        let reading = channel.parse(split_unsolicited(channel, data, unsolicited)?)?;
+        sink(Sample {
+            timestamp_ms: now_ms,
+            reading,
+        });
+        Ok(())
+    }
+
+    /// Abandons the in-flight read, e.g. after a receive timeout.
+    pub fn cancel_pending(&mut self) -> Option<Channel> {
+        self.pending.take()
+    }
+
+    /// Returns the channel whose reply is awaited.
+    #[must_use]
+    pub const fn pending(&self) -> Option<Channel> {
+        self.pending
+    }
+}
+
+/// Sends a fixed list of reads one after another and keeps every outcome.
+///
+/// Used for one-shot checks such as [`HealthCheck`](crate::diagnostics::HealthCheck).
+/// Like [`Poller`] it does no I/O: send [`next_frame`](Self::next_frame), then
+/// report the reply or a timeout.
+#[derive(Debug, Clone)]
+pub struct ReadSequence<const K: usize> {
+    reads: ReadFrames,
+    channels: [Channel; K],
+    results: [Option<Result<Reading, Error>>; K],
+    index: usize,
+}
+
+impl<const K: usize> ReadSequence<K> {
+    /// Creates a sequence reading `channels` in order from the board at `address`.
+    #[must_use]
+    pub const fn new(address: Address, channels: [Channel; K]) -> Self {
+        Self {
+            reads: ReadFrames::new(address),
+            channels,
+            results: [None; K],
+            index: 0,
+        }
+    }
+
+    /// Returns the frame to send next, or `None` once every read completed.
+    #[must_use]
+    pub fn next_frame(&self) -> Option<[u8; 3]> {
+        let channel = self.channels.get(self.index)?;
+        Some(channel.frame(&self.reads))
+    }
+
+    /// Parses the reply to the current read and moves on to the next one.
+    pub fn on_reply(&mut self, data: &[u8]) {
+        if let Some(channel) = self.channels.get(self.index) {
+            let reading = split_unsolicited(*channel, data, &mut |_| {})
+                .and_then(|reply| channel.parse(reply));
+            self.results[self.index] = Some(reading);
+            self.index += 1;
+        }
+    }
+
+    /// Records that the current read got no reply and moves on to the next one.
+    pub fn on_timeout(&mut self) {
+        if self.index < K {
+            self.results[self.index] = Some(Err(Error::Timeout));
+            self.index += 1;
+        }
+    }
+
+    /// Returns `true` once every read completed.
+    #[must_use]
+    pub const fn is_done(&self) -> bool {
+        self.index >= K
+    }
+
+    /// Returns the outcome of `channel`, or `None` if it was not read (yet).
+    #[must_use]
+    pub fn result(&self, channel: Channel) -> Option<Result<Reading, Error>> {
+        let position = self.channels.iter().position(|&c| c == channel)?;
+        self.results[position]
+    }
+}
+
+/// A condition detected by [`Supervisor`] from successive readings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The shaft status changed to blocked.
+    ShaftBlocked,
+    /// The shaft status changed back to unblocked.
+    ShaftUnblocked,
+    /// The shaft blocked while stall protection is enabled, so the firmware
+    /// has released the motor.
+    ProtectionTripped,
+    /// The angle error rose above the configured limit.
+    LargeFollowingError(ShaftErrValue),
+    /// The angle error fell back within the configured limit.
+    FollowingErrorCleared,
+}
+
+/// Thresholds and firmware settings used by [`Supervisor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SupervisorConfig {
+    /// Largest tolerated angle error magnitude, in encoder units (182 ≈ 1°).
+    pub max_following_error: u16,
+    /// Whether stall protection is enabled on the board.
+    pub stall_protection: bool,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_following_error: 182 * 10,
+            stall_protection: true,
+        }
+    }
+}
+
+/// Turns periodic status samples into edge-triggered [`Event`]s.
+///
+/// Each condition is reported once when it starts and once when it clears.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Supervisor {
+    config: SupervisorConfig,
+    blocked: bool,
+    following_error: bool,
+}
+
+impl Supervisor {
+    /// Creates a supervisor with the given thresholds.
+    #[must_use]
+    pub const fn new(config: SupervisorConfig) -> Self {
+        Self {
+            config,
+            blocked: false,
+            following_error: false,
+        }
+    }
+
+    /// Inspects a sample and passes any resulting events to `sink`.
+    pub fn observe(&mut self, sample: &Sample, sink: &mut impl FnMut(Event)) {
+        match sample.reading {
+            Reading::ShaftStatus(status) => {
+                let blocked = status == ShaftStatus::Blocked;
+                if blocked && !self.blocked {
+                    sink(Event::ShaftBlocked);
+                    if self.config.stall_protection {
+                        sink(Event::ProtectionTripped);
+                    }
+                } else if !blocked && self.blocked && status == ShaftStatus::Unblocked {
+                    sink(Event::ShaftUnblocked);
+                }
+                if status != ShaftStatus::Error {
+                    self.blocked = blocked;
+                }
+            }
+            Reading::AngleError(error) => {
+                let large = error.value.unsigned_abs() > self.config.max_following_error;
+                if large && !self.following_error {
+                    sink(Event::LargeFollowingError(error));
+                } else if !large && self.following_error {
+                    sink(Event::FollowingErrorCleared);
+                }
+                self.following_error = large;
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns `true` while the last known shaft status is blocked.
+    #[must_use]
+    pub const fn is_blocked(&self) -> bool {
+        self.blocked
+    }
+}
+
+/// Link state reported by [`Watchdog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    /// The last keepalive (or other reply) was answered.
+    Responsive,
+    /// Too many consecutive keepalives went unanswered.
+    Unresponsive,
+}
+
+/// Keepalive that detects a board that stopped answering.
+///
+/// The watchdog hands out a harmless EN pin status read every period. The
+/// caller reports whether a reply arrived; after `max_missed` consecutive
+/// timeouts the board is flagged [`Health::Unresponsive`].
+#[derive(Debug, Clone, Copy)]
+pub struct Watchdog {
+    frame: [u8; 3],
+    period_ms: u32,
+    max_missed: u8,
+    missed: u8,
+    next_due_ms: u64,
+    block_motion: bool,
+}
+
+impl Watchdog {
+    /// Creates a watchdog pinging `address` every `period_ms`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `period_ms` or `max_missed` is zero.
+    pub const fn new(address: Address, period_ms: u32, max_missed: u8) -> Result<Self, Error> {
+        if period_ms == 0 || max_missed == 0 {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            frame: crate::frames::read_en_pin_status(address),
+            period_ms,
+            max_missed,
+            missed: 0,
+            next_due_ms: 0,
+            block_motion: false,
+        })
+    }
+
+    /// Makes [`motion_allowed`](Self::motion_allowed) return `false` while unresponsive.
+    #[must_use]
+    pub const fn block_motion_when_unresponsive(mut self, block: bool) -> Self {
+        self.block_motion = block;
+        self
+    }
+
+    /// Returns the keepalive frame if one is due at `now_ms`.
+    pub fn poll(&mut self, now_ms: u64) -> Option<[u8; 3]> {
+        if now_ms < self.next_due_ms {
+            return None;
+        }
+        self.next_due_ms = now_ms + u64::from(self.period_ms);
+        Some(self.frame)
+    }
+
+    /// Records that the board answered, either the keepalive or any other command.
+    ///
+    /// Replies also postpone the next keepalive, so a busy link is not pinged.
+    pub fn on_reply(&mut self, now_ms: u64) {
+        self.missed = 0;
+        self.next_due_ms = now_ms + u64::from(self.period_ms);
+    }
+
+    /// Records that a keepalive went unanswered.
+    pub fn on_timeout(&mut self) {
+        self.missed = self.missed.saturating_add(1);
+    }
+
+    /// Returns the current link state.
+    #[must_use]
+    pub const fn health(&self) -> Health {
+        if self.missed >= self.max_missed {
+            Health::Unresponsive
+        } else {
+            Health::Responsive
+        }
+    }
+
+    /// Returns the number of consecutive unanswered keepalives.
+    #[must_use]
+    pub const fn missed(&self) -> u8 {
+        self.missed
+    }
+
+    /// Returns `false` if motion commands should be held back.
+    #[must_use]
+    pub const fn motion_allowed(&self) -> bool {
+        !self.block_motion || matches!(self.health(), Health::Responsive)
+    }
+}
+
+/// When a move counts as finished.
+///
+/// A position counts once it is within `tolerance` of the target; the move is
+/// finished after `settle_samples` consecutive such readings, and given up on
+/// after `timeout_ms`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SettleCriteria {
+    /// Largest accepted distance from the target.
+    pub tolerance: Degrees,
+    /// Consecutive in-tolerance readings required.
+    pub settle_samples: u8,
+    /// Time after which the move is reported as timed out, in milliseconds.
+    pub timeout_ms: u32,
+}
+
+impl SettleCriteria {
+    /// Within 3° for three readings, giving up after 5 s.
+    pub const DEFAULT: Self = Self {
+        tolerance: Degrees(3.0),
+        settle_samples: 3,
+        timeout_ms: 5000,
+    };
+
+    /// Returns `true` if `position` is within tolerance of `target`.
+    #[must_use]
+    pub fn within(&self, target: Degrees, position: Degrees) -> bool {
+        (position - target).abs() <= self.tolerance
+    }
+}
+
+impl Default for SettleCriteria {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Progress of a move towards its target; see [`SettleTracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Settle {
+    /// Not yet settled within tolerance.
+    Moving,
+    /// Settled within tolerance.
+    InPosition,
+    /// The timeout passed before the position settled.
+    TimedOut,
+}
+
+/// Decides from position readings when a move has finished.
+///
+/// ```
+/// use mks_servo42_rs::telemetry::{Settle, SettleCriteria, SettleTracker};
+/// use mks_servo42_rs::Degrees;
+///
+/// let mut tracker = SettleTracker::new(SettleCriteria::DEFAULT, Degrees(90.0), 0);
+/// assert_eq!(tracker.observe(10, Degrees(45.0)), Settle::Moving);
+/// assert_eq!(tracker.observe(20, Degrees(89.0)), Settle::Moving);
+/// assert_eq!(tracker.observe(30, Degrees(90.5)), Settle::Moving);
+/// assert_eq!(tracker.observe(40, Degrees(90.2)), Settle::InPosition);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SettleTracker {
+    criteria: SettleCriteria,
+    target: Degrees,
+    started_ms: u64,
+    in_tolerance: u8,
+}
+
+impl SettleTracker {
+    /// Starts tracking a move to `target` commanded at `now_ms`.
+    #[must_use]
+    pub const fn new(criteria: SettleCriteria, target: Degrees, now_ms: u64) -> Self {
+        Self {
+            criteria,
+            target,
+            started_ms: now_ms,
+            in_tolerance: 0,
+        }
+    }
+
+    /// Feeds a position read at `now_ms` and returns the move's progress.
+    pub fn observe(&mut self, now_ms: u64, position: Degrees) -> Settle {
+        if self.criteria.within(self.target, position) {
+            self.in_tolerance = self.in_tolerance.saturating_add(1);
+        } else {
+            self.in_tolerance = 0;
+        }
+        if self.in_tolerance >= self.criteria.settle_samples {
+            Settle::InPosition
+        } else if now_ms.saturating_sub(self.started_ms) >= u64::from(self.criteria.timeout_ms) {
+            Settle::TimedOut
+        } else {
+            Settle::Moving
+        }
+    }
+}
+
+/// Verdict of [`verify_move`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveOutcome {
+    /// The shaft moved by the expected amount, within tolerance.
+    Ok,
+    /// The shaft fell short of the expected move, or went the wrong way.
+    Undershoot,
+    /// The shaft moved further than expected.
+    Overshoot,
+    /// The shaft stayed put although a move was expected.
+    NoMotion,
+}
+
+/// Result of [`verify_move`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveReport {
+    /// The verdict.
+    pub outcome: MoveOutcome,
+    /// Rotation measured by the encoder, clockwise positive.
+    pub measured: Degrees,
+    /// `measured` minus the expected rotation.
+    pub error: Degrees,
+}
+
+/// Compares a commanded rotation with the encoder readings taken before and
+/// after the move.
+///
+/// The readings include the multi-turn carry, so moves across the encoder's
+/// zero and moves of several turns are measured correctly.
+///
+/// ```
+/// use mks_servo42_rs::telemetry::{verify_move, MoveOutcome};
+/// use mks_servo42_rs::{Degrees, EncoderValue};
+///
+/// let before = EncoderValue { carry: 0, value: 0xF000 };
+/// let after = EncoderValue { carry: 1, value: 0x1000 };
+/// let report = verify_move(before, after, Degrees(45.0), Degrees(1.0));
+/// assert_eq!(report.outcome, MoveOutcome::Ok);
+/// assert_eq!(report.measured, Degrees(45.0));
+/// ```
+#[must_use]
+pub fn verify_move(
+    before: EncoderValue,
+    after: EncoderValue,
+    expected_delta: Degrees,
+    tolerance: Degrees,
+) -> MoveReport {
+    let ticks =
+        |reading: EncoderValue| i64::from(reading.carry) * 65_536 + i64::from(reading.value);
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    let measured = Degrees((ticks(after) - ticks(before)) as f64 as f32 / 65_536.0 * 360.0);
+    let error = measured - expected_delta;
+    let outcome = if error.abs() <= tolerance {
+        MoveOutcome::Ok
+    } else if measured.abs() <= tolerance {
+        MoveOutcome::NoMotion
+    } else if error.0 * expected_delta.0 < 0.0 {
+        MoveOutcome::Undershoot
+    } else {
+        MoveOutcome::Overshoot
+    };
+    MoveReport {
+        outcome,
+        measured,
+        error,
+    }
+}
+
+/// Next step of a [`PositionCorrector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Correction {
+    /// Within tolerance (or less than a pulse away); nothing to send.
+    Done,
+    /// Send this move, let it finish, then read the position again.
+    Move(Command),
+    /// Still out of tolerance after the allowed corrections.
+    GaveUp,
+}
+
+/// Outer position loop closing the gap the firmware leaves after a move.
+///
+/// After a move, read the encoder and pass the position to
+/// [`next`](Self::next); it returns a small corrective `run_motor` until the
+/// shaft is within tolerance or `max_corrections` have been spent. Positions
+/// are in the encoder's frame, which counts up when the shaft turns clockwise
+/// with the default direction setting.
+///
+/// ```
+/// use mks_servo42_rs::telemetry::{Correction, PositionCorrector};
+/// use mks_servo42_rs::{Command, Degrees, Speed};
+///
+/// let mut corrector = PositionCorrector::new(Degrees(90.0), Degrees(0.5), 16, Speed::new(1)?, 3);
+/// let Correction::Move(Command::RunMotor { pulses, .. }) = corrector.next(Degrees(88.0)) else {
+///     panic!("expected a correction");
+/// };
+/// assert_eq!(pulses.get(), 18);
+/// assert_eq!(corrector.next(Degrees(89.8)), Correction::Done);
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionCorrector {
+    target: Degrees,
+    tolerance: Degrees,
+    microsteps: u16,
+    speed: Speed,
+    max_corrections: u8,
+    corrections: u8,
+}
+
+impl PositionCorrector {
+    /// Creates a corrector for a move to `target` on a board set to
+    /// `microsteps`, correcting at `speed` at most `max_corrections` times.
+    #[must_use]
+    pub const fn new(
+        target: Degrees,
+        tolerance: Degrees,
+        microsteps: u16,
+        speed: Speed,
+        max_corrections: u8,
+    ) -> Self {
+        Self {
+            target,
+            tolerance,
+            microsteps,
+            speed,
+            max_corrections,
+            corrections: 0,
+        }
+    }
+
+    /// Returns the number of corrections issued so far.
+    #[must_use]
+    pub const fn corrections(&self) -> u8 {
+        self.corrections
+    }
+
+    /// Decides the next step from the measured `position`.
+    pub fn next(&mut self, position: Degrees) -> Correction {
+        let error = self.target - position;
+        let pulses = Pulses::from_degrees(error, self.microsteps);
+        if error.abs() <= self.tolerance || pulses.get() == 0 {
+            return Correction::Done;
+        }
+        if self.corrections >= self.max_corrections {
+            return Correction::GaveUp;
+        }
+        self.corrections += 1;
+        let direction = if error.0 < 0.0 {
+            RotationDirection::CounterClockwise
+        } else {
+            RotationDirection::Clockwise
+        };
+        Correction::Move(Command::RunMotor {
+            direction,
+            speed: self.speed,
+            pulses,
+        })
+    }
+}
+
+/// Software speed loop holding the measured RPM on target under load.
+///
+/// Start the motor with [`start`](Self::start), then feed encoder positions
+/// (e.g. [`EncoderValue::to_revolutions`]) as they are read. When the measured
+/// speed drifts more than one speed gear from the target, the loop steps the
+/// gear and returns the constant-speed command to send:
+///
+/// ```
+/// use mks_servo42_rs::telemetry::SpeedHold;
+/// use mks_servo42_rs::{Command, RotationDirection, Revolutions};
+///
+/// // 60 RPM at 16 microsteps is speed gear 6 (9.375 RPM per gear).
+/// let mut hold = SpeedHold::new(RotationDirection::Clockwise, 60.0, 16)?;
+/// let start = hold.start();
+/// assert!(matches!(start, Command::RunWithConstantSpeed { speed, .. } if speed.get() == 6));
+/// assert_eq!(hold.observe(0, Revolutions(0.0)), None);
+/// // Under load the shaft only manages 40 RPM: one gear up.
+/// let nudge = hold.observe(1500, Revolutions(1.0));
+/// assert!(matches!(nudge, Some(Command::RunWithConstantSpeed { speed, .. }) if speed.get() == 7));
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeedHold {
+    direction: RotationDirection,
+    target_rpm: f32,
+    microsteps: u16,
+    speed: Speed,
+    last: Option<(u64, Revolutions)>,
+    measured_rpm: Option<f32>,
+}
+
+impl SpeedHold {
+    /// Creates a loop holding `target_rpm` in `direction` on a board set to `microsteps`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `target_rpm` has no speed gear at `microsteps`.
+    pub fn new(
+        direction: RotationDirection,
+        target_rpm: f32,
+        microsteps: u16,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            direction,
+            target_rpm,
+            microsteps,
+            speed: Speed::from_rpm(target_rpm, microsteps)?,
+            last: None,
+            measured_rpm: None,
+        })
+    }
+
+    /// Returns the command starting the motor at the current speed gear.
+    #[must_use]
+    pub const fn start(&self) -> Command {
+        Command::RunWithConstantSpeed {
+            direction: self.direction,
+            speed: self.speed,
+        }
+    }
+
+    /// Returns the speed gear currently commanded.
+    #[must_use]
+    pub const fn speed(&self) -> Speed {
+        self.speed
+    }
+
+    /// Returns the speed measured between the last two positions, in RPM.
+    #[must_use]
+    pub const fn measured_rpm(&self) -> Option<f32> {
+        self.measured_rpm
+    }
+
+    /// Feeds the shaft position read at `now_ms` and returns a new speed
+    /// command if the gear should change.
+    pub fn observe(&mut self, now_ms: u64, position: Revolutions) -> Option<Command> {
+        let (last_ms, last_position) = self.last.replace((now_ms, position))?;
+        let elapsed_ms = now_ms.checked_sub(last_ms).filter(|&ms| ms > 0)?;
+        #[allow(clippy::cast_precision_loss)]
+        let rpm = (position - last_position).abs().0 * 60_000.0 / elapsed_ms as f32;
+        self.measured_rpm = Some(rpm);
+
+        let gear_rpm = Speed::new(1).ok()?.to_rpm(self.microsteps);
+        let gear = self.speed.get();
+        let gear = if rpm + gear_rpm < self.target_rpm {
+            gear.saturating_add(1)
+        } else if rpm > self.target_rpm + gear_rpm {
+            gear.saturating_sub(1).max(1)
+        } else {
+            return None;
+        };
+        let speed = Speed::new(gear).ok()?;
+        if speed == self.speed {
+            return None;
+        }
+        self.speed = speed;
+        Some(self.start())
+    }
+}
+
+/// Angle error at which a 1.8° motor delivers its peak torque, in encoder
+/// units: one full step, a quarter of an electrical period.
+pub const FULL_LOAD_ANGLE_ERROR: u16 = 328;
+
+/// Qualitative band of a [`LoadEstimator`] reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadLevel {
+    /// Below 25% of peak torque.
+    Light,
+    /// 25% to 60% of peak torque.
+    Moderate,
+    /// 60% up to peak torque.
+    Heavy,
+    /// At peak torque: the shaft is jammed or about to slip.
+    Overload,
+}
+
+impl LoadLevel {
+    /// Returns the band of a load in percent of peak torque.
+    #[must_use]
+    pub fn from_percent(percent: f32) -> Self {
+        if percent >= 100.0 {
+            Self::Overload
+        } else if percent >= 60.0 {
+            Self::Heavy
+        } else if percent >= 25.0 {
+            Self::Moderate
+        } else {
+            Self::Light
+        }
+    }
+}
+
+/// Estimates the shaft load from angle error readings.
+///
+/// The closed loop holds the shaft behind its commanded angle by an error
+/// that grows with the torque it has to produce, reaching peak torque at
+/// `full_load_error`. The estimator smooths the error magnitude over recent
+/// readings and scales it linearly, which is coarse near peak torque but
+/// enough to spot jams or changes in material without extra sensors:
+///
+/// ```
+/// use mks_servo42_rs::telemetry::{LoadEstimator, LoadLevel};
+/// use mks_servo42_rs::ShaftErrValue;
+///
+/// let mut load = LoadEstimator::default();
+/// assert_eq!(load.observe(ShaftErrValue { value: -82 }), 25.0);
+/// assert_eq!(load.level(), Some(LoadLevel::Moderate));
+/// for _ in 0..20 {
+///     load.observe(ShaftErrValue { value: 400 });
+/// }
+/// assert_eq!(load.level(), Some(LoadLevel::Overload));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadEstimator {
+    full_load_error: u16,
+    smoothed_error: Option<f32>,
+}
+
+impl LoadEstimator {
+    /// Creates an estimator reaching 100% at an error of `full_load_error`
+    /// encoder units; see [`FULL_LOAD_ANGLE_ERROR`].
+    #[must_use]
+    pub const fn new(full_load_error: u16) -> Self {
+        Self {
+            full_load_error: if full_load_error == 0 {
+                1
+            } else {
+                full_load_error
+            },
+            smoothed_error: None,
+        }
+    }
+
+    /// Feeds an angle error reading and returns the estimated load in percent
+    /// of peak torque.
+    pub fn observe(&mut self, error: ShaftErrValue) -> f32 {
+        let error = f32::from(error.value.unsigned_abs());
+        // Exponential average over roughly the last four readings.
+        let smoothed = self
+            .smoothed_error
+            .map_or(error, |smoothed| smoothed + (error - smoothed) / 4.0);
+        self.smoothed_error = Some(smoothed);
+        self.load_percent(smoothed)
+    }
+
+    /// Returns the estimated load in percent of peak torque, once a reading
+    /// has been fed.
+    #[must_use]
+    pub fn percent(&self) -> Option<f32> {
+        self.smoothed_error.map(|error| self.load_percent(error))
+    }
+
+    /// Returns the band of the estimated load, once a reading has been fed.
+    #[must_use]
+    pub fn level(&self) -> Option<LoadLevel> {
+        self.percent().map(LoadLevel::from_percent)
+    }
+
+    /// Forgets past readings, e.g. after the motor was stopped.
+    pub const fn reset(&mut self) {
+        self.smoothed_error = None;
+    }
+
+    fn load_percent(&self, error: f32) -> f32 {
+        (error * 100.0 / f32::from(self.full_load_error)).min(100.0)
+    }
+}
+
+impl Default for LoadEstimator {
+    fn default() -> Self {
+        Self::new(FULL_LOAD_ANGLE_ERROR)
+    }
+}
+
+/// Extends the board's 32-bit pulse counter to 64 bits.
+///
+/// The counter wraps after 2³¹ pulses in either direction. Feed every
+/// `read_pulse_count` result to [`update`](Self::update); as long as fewer
+/// than 2³¹ pulses arrive between two reads, wraps are detected and the
+/// total keeps counting:
+///
+/// ```
+/// use mks_servo42_rs::telemetry::PulseAccumulator;
+///
+/// let mut odometer = PulseAccumulator::new();
+/// assert_eq!(odometer.update(i32::MAX - 10), i64::from(i32::MAX) - 10);
+/// assert_eq!(odometer.update(i32::MIN + 9), i64::from(i32::MAX) + 10);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PulseAccumulator {
+    last: Option<i32>,
+    total: i64,
+}
+
+impl PulseAccumulator {
+    /// Creates an accumulator that takes its total from the first reading.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            last: None,
+            total: 0,
+        }
+    }
+
+    /// Feeds a raw pulse count and returns the accumulated total.
+    pub fn update(&mut self, count: i32) -> i64 {
+        self.total = match self.last {
+            Some(last) => self.total.wrapping_add(i64::from(count.wrapping_sub(last))),
+            None => i64::from(count),
+        };
+        self.last = Some(count);
+        self.total
+    }
+
+    /// Returns the accumulated total, zero before the first reading.
+    #[must_use]
+    pub const fn total(&self) -> i64 {
+        self.total
+    }
+
+    /// Starts over, e.g. after the board was reset or rezeroed.
+    pub const fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// Caps the command rate to one board.
+///
+/// The firmware drops motion commands when flooded with reads, so route every
+/// frame for a board through one limiter. Check [`ready`](Self::ready) before
+/// asking a [`Poller`] for its next frame, so no scheduled read is lost:
+///
+/// ```
+/// use mks_servo42_rs::telemetry::{Channel, Poller, RateLimiter};
+/// use mks_servo42_rs::Address;
+///
+/// let mut poller: Poller = Poller::new(Address::DEFAULT);
+/// poller.schedule(Channel::Encoder, 1)?;
+/// let mut limiter = RateLimiter::new(10)?;
+/// let mut sent = 0;
+/// for now_ms in 0..100 {
+///     if limiter.ready(now_ms) {
+///         if let Some(_frame) = poller.poll(now_ms) {
+///             limiter.record(now_ms);
+///             poller.cancel_pending();
+///             sent += 1;
+///         }
+///     }
+/// }
+/// assert_eq!(sent, 10);
+/// # Ok::<(), mks_servo42_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RateLimiter {
+    min_interval_ms: u32,
+    last_ms: Option<u64>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing one frame every `min_interval_ms`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `min_interval_ms` is zero.
+    pub const fn new(min_interval_ms: u32) -> Result<Self, Error> {
+        if min_interval_ms == 0 {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            min_interval_ms,
+            last_ms: None,
+        })
+    }
+
+    /// Creates a limiter allowing at most `hz` frames per second.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `hz` is zero or above 1000.
+    pub const fn from_hz(hz: u32) -> Result<Self, Error> {
+        if hz == 0 {
+            return Err(Error::InvalidValue);
+        }
+        Self::new(1000 / hz)
+    }
+
+    /// Returns `true` if a frame may be sent at `now_ms`.
+    #[must_use]
+    pub const fn ready(&self, now_ms: u64) -> bool {
+        match self.last_ms {
+            Some(last_ms) => now_ms >= last_ms + self.min_interval_ms as u64,
+            None => true,
+        }
+    }
+
+    /// Records that a frame was sent at `now_ms`.
+    pub fn record(&mut self, now_ms: u64) {
+        self.last_ms = Some(now_ms);
+    }
+
+    /// Records a frame at `now_ms` and returns `true` if the limit allows it.
+    pub fn try_acquire(&mut self, now_ms: u64) -> bool {
+        let ready = self.ready(now_ms);
+        if ready {
+            self.record(now_ms);
+        }
+        ready
+    }
+}
+
+/// Link health counters, kept by the transport.
+///
+/// All counters saturate. Compare `replies` with `frames_sent` for the answer
+/// rate; rising `resyncs` or `checksum_failures` point at noise or a baud
+/// mismatch:
+///
+/// ```
+/// use mks_servo42_rs::telemetry::LinkMetrics;
+/// use mks_servo42_rs::{parse_ack, Error};
+///
+/// let mut metrics = LinkMetrics::default();
+/// metrics.on_sent();
+/// metrics.on_result(&parse_ack(&[0xE0, 0x01, 0xE1]));
+/// metrics.on_sent();
+/// metrics.on_result::<()>(&Err(Error::Timeout));
+/// assert_eq!((metrics.frames_sent, metrics.replies, metrics.timeouts), (2, 1, 1));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LinkMetrics {
+    /// Frames transmitted, retries included.
+    pub frames_sent: u32,
+    /// Replies that parsed successfully.
+    pub replies: u32,
+    /// Replies rejected with `Error::Checksum`.
+    pub checksum_failures: u32,
+    /// Replies rejected as malformed for any other reason.
+    pub invalid_replies: u32,
+    /// Replies that never arrived.
+    pub timeouts: u32,
+    /// Commands sent again after a failed exchange.
+    pub retries: u32,
+    /// Times the receiver discarded bytes to find the next frame.
+    pub resyncs: u32,
+}
+
+impl LinkMetrics {
+    /// Counts a transmitted frame.
+    pub fn on_sent(&mut self) {
+        self.frames_sent = self.frames_sent.saturating_add(1);
+    }
+
+    /// Counts the outcome of one exchange, as returned by a parser or the transport.
+    pub fn on_result<T>(&mut self, result: &Result<T, Error>) {
+        let counter = match result {
+            Ok(_) => &mut self.replies,
+            Err(Error::Checksum) => &mut self.checksum_failures,
+            Err(Error::Timeout) => &mut self.timeouts,
+            Err(_) => &mut self.invalid_replies,
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    /// Counts a retried command.
+    pub fn on_retry(&mut self) {
+        self.retries = self.retries.saturating_add(1);
+    }
+
+    /// Counts a receiver resynchronization.
+    pub fn on_resync(&mut self) {
+        self.resyncs = self.resyncs.saturating_add(1);
+    }
+}
+
+/// A timestamped shaft position.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Position {
+    /// Caller-supplied timestamp in milliseconds.
+    pub timestamp_ms: u64,
+    /// Multi-turn shaft position.
+    pub degrees: Degrees,
+}
+
+/// Fixed-size ring buffer of the last `N` shaft positions.
+///
+/// Once full, each new position overwrites the oldest one.
+#[derive(Debug, Clone)]
+pub struct PositionHistory<const N: usize> {
+    positions: [Position; N],
+    start: usize,
+    len: usize,
+}
+
+impl<const N: usize> PositionHistory<N> {
+    /// Creates an empty history.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            positions: [Position {
+                timestamp_ms: 0,
+                degrees: Degrees(0.0),
+            }; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Appends a position, dropping the oldest one when full.
+    pub fn push(&mut self, position: Position) {
+        if N == 0 {
+            return;
+        }
+        let end = (self.start + self.len) % N;
+        self.positions[end] = position;
+        if self.len == N {
+            self.start = (self.start + 1) % N;
+        } else {
+            self.len += 1;
+        }
+    }
+
+    /// Records the position carried by an encoder or shaft angle sample.
+    ///
+    /// Returns `false` for samples that carry no position.
+    pub fn record(&mut self, sample: &Sample) -> bool {
+        let degrees = match sample.reading {
+            Reading::Encoder(value) => value.to_degrees(),
+            Reading::MotorShaftAngle(angle) => angle.to_degrees(),
+            _ => return false,
+        };
+        self.push(Position {
+            timestamp_ms: sample.timestamp_ms,
+            degrees,
+        });
+        true
+    }
+
+    /// Returns the number of stored positions.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no position is stored.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the most recent position.
+    #[must_use]
+    pub fn latest(&self) -> Option<Position> {
+        let last = self.len.checked_sub(1)?;
+        Some(self.positions[(self.start + last) % N])
+    }
+
+    /// Returns the position with the smallest angle.
+    #[must_use]
+    pub fn min(&self) -> Option<Position> {
+        self.iter()
+            .reduce(|a, b| if b.degrees < a.degrees { b } else { a })
+    }
+
+    /// Returns the position with the largest angle.
+    #[must_use]
+    pub fn max(&self) -> Option<Position> {
+        self.iter()
+            .reduce(|a, b| if b.degrees > a.degrees { b } else { a })
+    }
+
+    /// Iterates over the stored positions, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        (0..self.len).map(move |i| self.positions[(self.start + i) % N])
+    }
+
+    /// Removes and returns the oldest position.
+    pub fn pop_oldest(&mut self) -> Option<Position> {
+        if self.len == 0 {
+            return None;
+        }
+        let position = self.positions[self.start];
+        self.start = (self.start + 1) % N;
+        self.len -= 1;
+        Some(position)
+    }
+
+    /// Removes all positions, yielding them oldest first.
+    pub fn drain(&mut self) -> impl Iterator<Item = Position> + '_ {
+        core::iter::from_fn(move || self.pop_oldest())
+    }
+}
+
+impl<const N: usize> Default for PositionHistory<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENCODER_REPLY: [u8; 8] = [0xE0, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x20];
+    const SHAFT_REPLY: [u8; 3] = [0xE0, 0x02, 0xE2];
+    const ERROR_REPLY: [u8; 5] = [0xE0, 0x00, 0xB7, 0x97, 0x00];
+
+    fn reply(frame: [u8; 3]) -> &'static [u8] {
+        match frame[1] {
+            0x30 => &ENCODER_REPLY,
+            0x39 => &ERROR_REPLY,
+            0x3E => &SHAFT_REPLY,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_schedule_rates() {
+        let mut poller: Poller<4> = Poller::new(Address::DEFAULT);
+        poller.schedule_hz(Channel::Encoder, 50).unwrap();
+        poller.schedule_hz(Channel::ShaftStatus, 5).unwrap();
+        poller.schedule_hz(Channel::AngleError, 10).unwrap();
+
+        let (mut encoder, mut shaft, mut error) = (0, 0, 0);
+        for now_ms in 0..1000 {
+            if let Some(frame) = poller.poll(now_ms) {
+                poller
+                    .handle_response(now_ms, reply(frame), &mut |sample| match sample.reading {
+                        Reading::Encoder(_) => encoder += 1,
+                        Reading::ShaftStatus(_) => shaft += 1,
+                        Reading::AngleError(_) => error += 1,
+                        _ => unreachable!(),
+                    })
+                    .unwrap();
+            }
+        }
+        assert_eq!((encoder, shaft, error), (50, 5, 10));
+    }
+
+    #[test]
+    fn test_one_read_in_flight() {
+        let mut poller: Poller<2> = Poller::new(Address::DEFAULT);
+        poller.schedule(Channel::Encoder, 10).unwrap();
+        poller.schedule(Channel::ShaftStatus, 10).unwrap();
+
+        assert_eq!(poller.poll(0), Some([0xE0, 0x30, 0x10]));
+        assert_eq!(poller.pending(), Some(Channel::Encoder));
+        assert_eq!(poller.poll(0), None);
+        assert_eq!(poller.cancel_pending(), Some(Channel::Encoder));
+        assert_eq!(poller.poll(0), Some([0xE0, 0x3E, 0x1E]));
+
+        let mut samples = 0;
+        assert_eq!(
+            poller.handle_response(1, &[0xE0, 0x02, 0x00], &mut |_| samples += 1),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(poller.pending(), None);
+        assert_eq!(
+            poller.handle_response(1, &SHAFT_REPLY, &mut |_| samples += 1),
+            Err(Error::InvalidPacket)
+        );
+        assert_eq!(samples, 0);
+        assert_eq!(poller.poll(5), None);
+    }
+
+    #[test]
+    fn test_supervisor_events() {
+        extern crate std;
+        use std::vec::Vec;
+
+        let mut supervisor = Supervisor::new(SupervisorConfig {
+            max_following_error: 182,
+            stall_protection: true,
+        });
+        let readings = [
+            Reading::ShaftStatus(ShaftStatus::Unblocked),
+            Reading::ShaftStatus(ShaftStatus::Blocked),
+            Reading::ShaftStatus(ShaftStatus::Error),
+            Reading::ShaftStatus(ShaftStatus::Blocked),
+            Reading::AngleError(ShaftErrValue { value: -400 }),
+            Reading::AngleError(ShaftErrValue { value: 300 }),
+            Reading::AngleError(ShaftErrValue { value: 10 }),
+            Reading::ShaftStatus(ShaftStatus::Unblocked),
+        ];
+        let mut events = Vec::new();
+        for reading in readings {
+            let sample = Sample {
+                timestamp_ms: 0,
+                reading,
+            };
+            supervisor.observe(&sample, &mut |event| events.push(event));
+        }
+        assert_eq!(
+            events,
+            [
+                Event::ShaftBlocked,
+                Event::ProtectionTripped,
+                Event::LargeFollowingError(ShaftErrValue { value: -400 }),
+                Event::FollowingErrorCleared,
+                Event::ShaftUnblocked,
+            ]
+        );
+        assert!(!supervisor.is_blocked());
+    }
+
+    #[test]
+    fn test_watchdog_flags_unresponsive() {
+        let mut watchdog = Watchdog::new(Address::DEFAULT, 100, 3)
+            .unwrap()
+            .block_motion_when_unresponsive(true);
+        assert_eq!(watchdog.poll(0), Some([0xE0, 0x3A, 0x1A]));
+        assert_eq!(watchdog.poll(50), None);
+
+        for now_ms in [100, 200, 300] {
+            assert!(watchdog.motion_allowed());
+            assert!(watchdog.poll(now_ms).is_some());
+            watchdog.on_timeout();
+        }
+        assert_eq!(watchdog.missed(), 3);
+        assert_eq!(watchdog.health(), Health::Unresponsive);
+        assert!(!watchdog.motion_allowed());
+
+        watchdog.on_reply(320);
+        assert_eq!(watchdog.health(), Health::Responsive);
+        assert!(watchdog.motion_allowed());
+        assert_eq!(watchdog.poll(400), None);
+        assert!(watchdog.poll(420).is_some());
+    }
+
+    #[test]
+    fn test_settle_tracker() {
+        let criteria = SettleCriteria {
+            settle_samples: 2,
+            timeout_ms: 100,
+            ..SettleCriteria::DEFAULT
+        };
+        assert!(criteria.within(Degrees(-10.0), Degrees(-7.0)));
+        assert!(!criteria.within(Degrees(-10.0), Degrees(-6.9)));
+
+        let mut tracker = SettleTracker::new(criteria, Degrees(0.0), 1000);
+        assert_eq!(tracker.observe(1010, Degrees(1.0)), Settle::Moving);
+        assert_eq!(tracker.observe(1020, Degrees(5.0)), Settle::Moving);
+        assert_eq!(tracker.observe(1030, Degrees(-1.0)), Settle::Moving);
+        assert_eq!(tracker.observe(1100, Degrees(8.0)), Settle::TimedOut);
+        assert_eq!(tracker.observe(1110, Degrees(0.0)), Settle::TimedOut);
+        assert_eq!(tracker.observe(1120, Degrees(0.0)), Settle::InPosition);
+    }
+
+    #[test]
+    fn test_verify_move() {
+        let at = |carry, value| EncoderValue { carry, value };
+        let tolerance = Degrees(2.0);
+        let check =
+            |after, expected| verify_move(at(-1, 0x8000), after, Degrees(expected), tolerance);
+
+        let report = check(at(1, 0x8000), 720.0);
+        assert_eq!(report.outcome, MoveOutcome::Ok);
+        assert_eq!(report.measured, Degrees(720.0));
+        assert_eq!(report.error, Degrees(0.0));
+        assert_eq!(
+            check(at(0, 0x0000), -180.0).outcome,
+            MoveOutcome::Undershoot
+        );
+        assert_eq!(check(at(-1, 0x4000), -90.0).outcome, MoveOutcome::Ok);
+        assert_eq!(check(at(-1, 0x0000), -90.0).outcome, MoveOutcome::Overshoot);
+        assert_eq!(check(at(-1, 0x8100), 90.0).outcome, MoveOutcome::NoMotion);
+        assert_eq!(check(at(0, 0x0000), 90.0).outcome, MoveOutcome::Overshoot);
+        assert_eq!(check(at(-1, 0x7000), 90.0).outcome, MoveOutcome::Undershoot);
+        assert_eq!(check(at(-1, 0x8100), 0.0).outcome, MoveOutcome::Ok);
+        assert_eq!(check(at(-1, 0x9000), 0.0).outcome, MoveOutcome::Overshoot);
+    }
+
+    #[test]
+    fn test_position_corrector() {
+        let speed = Speed::new(1).unwrap();
+        let mut corrector = PositionCorrector::new(Degrees(0.0), Degrees(0.1), 16, speed, 2);
+        assert_eq!(
+            corrector.next(Degrees(1.8)),
+            Correction::Move(Command::RunMotor {
+                direction: RotationDirection::CounterClockwise,
+                speed,
+                pulses: Pulses::new(16),
+            })
+        );
+        assert!(matches!(
+            corrector.next(Degrees(-0.9)),
+            Correction::Move(Command::RunMotor {
+                direction: RotationDirection::Clockwise,
+                ..
+            })
+        ));
+        assert_eq!(corrector.corrections(), 2);
+        assert_eq!(corrector.next(Degrees(0.5)), Correction::GaveUp);
+        assert_eq!(corrector.next(Degrees(0.05)), Correction::Done);
+    }
+
+    #[test]
+    fn test_speed_hold() {
+        let mut hold = SpeedHold::new(RotationDirection::CounterClockwise, 30.0, 32).unwrap();
+        assert_eq!(hold.speed().get(), 6);
+        let run = |gear| {
+            Some(Command::RunWithConstantSpeed {
+                direction: RotationDirection::CounterClockwise,
+                speed: Speed::new(gear).unwrap(),
+            })
+        };
+        assert_eq!(hold.observe(0, Revolutions(0.0)), None);
+        assert_eq!(hold.observe(0, Revolutions(0.1)), None);
+        // Half a turn in 1 s: 30 RPM, on target.
+        assert_eq!(hold.observe(1000, Revolutions(-0.4)), None);
+        assert_eq!(hold.measured_rpm(), Some(30.0));
+        // One turn in 1 s: 60 RPM, one gear down.
+        assert_eq!(hold.observe(2000, Revolutions(-1.4)), run(5));
+        // A tenth of a turn in 1 s: 6 RPM, one gear up.
+        assert_eq!(hold.observe(3000, Revolutions(-1.5)), run(6));
+        assert_eq!(
+            SpeedHold::new(RotationDirection::Clockwise, -1.0, 16),
+            Err(Error::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn test_load_estimator() {
+        let mut load = LoadEstimator::new(200);
+        assert_eq!(load.percent(), None);
+        assert_eq!(load.observe(ShaftErrValue { value: 20 }), 10.0);
+        assert_eq!(load.level(), Some(LoadLevel::Light));
+        // 20 + (-180 magnitude - 20) / 4 = 60 units.
+        assert_eq!(load.observe(ShaftErrValue { value: -180 }), 30.0);
+        assert_eq!(load.observe(ShaftErrValue { value: i16::MIN }), 100.0);
+        assert_eq!(load.level(), Some(LoadLevel::Overload));
+        load.reset();
+        assert_eq!(load.level(), None);
+
+        assert_eq!(LoadLevel::from_percent(59.9), LoadLevel::Moderate);
+        assert_eq!(LoadLevel::from_percent(60.0), LoadLevel::Heavy);
+        assert_eq!(
+            LoadEstimator::new(0).observe(ShaftErrValue { value: 0 }),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_pulse_accumulator() {
+        let mut odometer = PulseAccumulator::new();
+        assert_eq!(odometer.total(), 0);
+        assert_eq!(odometer.update(-5), -5);
+        assert_eq!(odometer.update(i32::MIN + 2), i64::from(i32::MIN) + 2);
+        // Wraps backwards through i32::MIN.
+        assert_eq!(odometer.update(i32::MAX - 1), i64::from(i32::MIN) - 2);
+        // Three forward turns of 2^30 pulses each.
+        let mut count = i32::MAX - 1;
+        for _ in 0..6 {
+            count = count.wrapping_add(1 << 29);
+            odometer.update(count);
+        }
+        assert_eq!(odometer.total(), i64::from(i32::MIN) - 2 + 3 * (1 << 30));
+        odometer.reset();
+        assert_eq!(odometer.update(7), 7);
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::from_hz(50).unwrap();
+        assert!(limiter.try_acquire(5));
+        assert!(!limiter.try_acquire(24));
+        assert!(limiter.ready(25));
+        assert!(limiter.try_acquire(30));
+        assert!(!limiter.ready(49));
+        assert_eq!(RateLimiter::new(0), Err(Error::InvalidValue));
+        assert_eq!(RateLimiter::from_hz(0), Err(Error::InvalidValue));
+        assert_eq!(RateLimiter::from_hz(1001), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_link_metrics() {
+        let mut metrics = LinkMetrics {
+            retries: u32::MAX,
+            ..LinkMetrics::default()
+        };
+        metrics.on_result::<()>(&Err(Error::Checksum));
+        metrics.on_result(&parse_encoder_response(&[0xE0, 0x00]));
+        metrics.on_retry();
+        metrics.on_resync();
+        assert_eq!(metrics.checksum_failures, 1);
+        assert_eq!(metrics.invalid_replies, 1);
+        assert_eq!(metrics.retries, u32::MAX);
+        assert_eq!(metrics.resyncs, 1);
+        assert_eq!(metrics.replies, 0);
+    }
+
+    #[test]
+    fn test_watchdog_limits() {
+        assert_eq!(
+            Watchdog::new(Address::DEFAULT, 0, 3).err(),
+            Some(Error::InvalidValue)
+        );
+        assert_eq!(
+            Watchdog::new(Address::DEFAULT, 100, 0).err(),
+            Some(Error::InvalidValue)
+        );
+        let mut watchdog = Watchdog::new(Address::DEFAULT, 100, 1).unwrap();
+        watchdog.on_timeout();
+        assert_eq!(watchdog.health(), Health::Unresponsive);
+        assert!(watchdog.motion_allowed());
+    }
+
+    #[test]
+    fn test_position_history() {
+        let mut history: PositionHistory<3> = PositionHistory::new();
+        assert!(history.is_empty());
+        assert_eq!(history.latest(), None);
+        assert_eq!(history.min(), None);
+
+        for (timestamp_ms, degrees) in [(0, 10.0), (1, -5.0), (2, 30.0), (3, 20.0)] {
+            history.push(Position {
+                timestamp_ms,
+                degrees: Degrees(degrees),
+            });
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.latest().map(|p| p.timestamp_ms), Some(3));
+        assert_eq!(history.min().map(|p| p.degrees), Some(Degrees(-5.0)));
+        assert_eq!(history.max().map(|p| p.timestamp_ms), Some(2));
+
+        let mut drained = [0u64; 3];
+        for (slot, position) in drained.iter_mut().zip(history.drain()) {
+            *slot = position.timestamp_ms;
+        }
+        assert_eq!(drained, [1, 2, 3]);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_position_history_records_samples() {
+        let mut history: PositionHistory<4> = PositionHistory::default();
+        let encoder = Sample {
+            timestamp_ms: 7,
+            reading: Channel::Encoder.parse(&ENCODER_REPLY).unwrap(),
+        };
+        let shaft = Sample {
+            timestamp_ms: 8,
+            reading: Reading::ShaftStatus(ShaftStatus::Unblocked),
+        };
+        assert!(history.record(&encoder));
+        assert!(!history.record(&shaft));
+        assert_eq!(
+            history.latest(),
+            Some(Position {
+                timestamp_ms: 7,
+                degrees: Degrees(90.0),
+            })
+        );
+    }
+
+    #[test]
+    fn test_unsolicited_frames() {
+        let mut poller: Poller = Poller::new(Address::DEFAULT);
+        poller.schedule(Channel::Encoder, 10).unwrap();
+        poller.poll(0).unwrap();
+        let mut data = [0u8; 14];
+        data[..3].copy_from_slice(&[0xE0, 0x02, 0xE2]);
+        data[3..11].copy_from_slice(&ENCODER_REPLY);
+        data[11..].copy_from_slice(&[0xE1, 0x00, 0xE1]);
+        let mut samples = 0;
+        let mut late = [None; 2];
+        let mut count = 0;
+        poller
+            .handle_response_with_unsolicited(0, &data, &mut |_| samples += 1, &mut |frame| {
+                late[count] = Some((frame.address.get(), frame.status));
+                count += 1;
+            })
+            .unwrap();
+        assert_eq!(samples, 1);
+        assert_eq!(late, [Some((0xE0, 2)), Some((0xE1, 0))]);
+
+        // A reply of the expected length is never split.
+        let mut sink = |_| panic!("split a reply");
+        assert_eq!(
+            split_unsolicited(Channel::ShaftStatus, &SHAFT_REPLY, &mut sink),
+            Ok(SHAFT_REPLY.as_slice())
+        );
+        let garbage = [0x00, 0xE0, 0x03, 0xE3];
+        assert_eq!(
+            split_unsolicited(Channel::ShaftStatus, &garbage, &mut sink),
+            Ok(garbage.as_slice())
+        );
+
+        // An angle error of +224 starts like a status frame, so only the
+        // late frame behind it may be split off.
+        let data = [0xE0, 0x00, 0xE0, 0xC0, 0x00, 0xE0, 0x02, 0xE2];
+        let mut late = None;
+        let reply = split_unsolicited(Channel::AngleError, &data, &mut |frame| late = Some(frame));
+        assert_eq!(reply, Ok(&data[..5]));
+        assert_eq!(late.map(|frame| frame.status), Some(2));
+        assert_eq!(
+            Channel::AngleError.parse(&data[..5]),
+            Ok(Reading::AngleError(ShaftErrValue { value: 224 }))
+        );
+
+        // Blocked followed by a late "complete" could be either order.
+        let data = [0xE0, 0x01, 0xE1, 0xE0, 0x02, 0xE2];
+        assert_eq!(
+            split_unsolicited(Channel::ShaftStatus, &data, &mut sink),
+            Err(Error::Ambiguous)
+        );
+        let mut poller: Poller = Poller::new(Address::DEFAULT);
+        poller.schedule(Channel::ShaftStatus, 10).unwrap();
+        poller.poll(0).unwrap();
+        assert_eq!(
+            poller.handle_response(0, &data, &mut |_| panic!("guessed a reply")),
+            Err(Error::Ambiguous)
+        );
+    }
+
+    #[test]
+    fn test_read_sequence() {
+        let mut sequence = ReadSequence::new(
+            Address::DEFAULT,
+            [Channel::ShaftStatus, Channel::Encoder, Channel::AngleError],
+        );
+        assert_eq!(sequence.next_frame(), Some([0xE0, 0x3E, 0x1E]));
+        sequence.on_reply(&SHAFT_REPLY);
+        assert_eq!(sequence.next_frame(), Some([0xE0, 0x30, 0x10]));
+        sequence.on_timeout();
+        sequence.on_reply(&[0x00]);
+        assert!(sequence.is_done());
+        assert_eq!(sequence.next_frame(), None);
+
+        assert_eq!(
+            sequence.result(Channel::ShaftStatus),
+            Some(Ok(Reading::ShaftStatus(ShaftStatus::Unblocked)))
+        );
+        assert_eq!(sequence.result(Channel::Encoder), Some(Err(Error::Timeout)));
+        assert_eq!(
+            sequence.result(Channel::AngleError),
+            Some(Err(Error::InvalidPacket))
+        );
+        assert_eq!(sequence.result(Channel::EnPinStatus), None);
+    }
+
+    #[test]
+    fn test_schedule_limits() {
+        let mut poller: Poller<1> = Poller::new(Address::DEFAULT);
+        assert_eq!(
+            poller.schedule(Channel::Encoder, 0),
+            Err(Error::InvalidValue)
+        );
+        assert_eq!(
+            poller.schedule_hz(Channel::Encoder, 0),
+            Err(Error::InvalidValue)
+        );
+        poller.schedule(Channel::Encoder, 10).unwrap();
+        poller.schedule(Channel::Encoder, 20).unwrap();
+        assert_eq!(
+            poller.schedule(Channel::ShaftStatus, 10),
+            Err(Error::BufferTooSmall)
+        );
+        poller.unschedule(Channel::Encoder);
+        poller.schedule(Channel::ShaftStatus, 10).unwrap();
+        assert_eq!(poller.poll(0), Some([0xE0, 0x3E, 0x1E]));
+    }
+}
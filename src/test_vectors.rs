@@ -0,0 +1,260 @@
+//! Known-good frames from the MKS SERVO42C manual and board captures, for the default address `0xE0`.
+//!
+//! Transports and mocks can replay these to check their framing against the
+//! firmware examples without a board:
+//!
+//! ```
+//! use mks_servo42_rs::{parse_encoder_response, test_vectors, Driver};
+//!
+//! assert_eq!(Driver::default().read_encoder_value().as_slice(), test_vectors::READ_ENCODER_VALUE);
+//! let value = parse_encoder_response(test_vectors::ENCODER_90_DEG).unwrap();
+//! assert_eq!(value.value, 0x4000);
+//! ```
+
+// Commands.
+
+/// `read_encoder_value`.
+pub const READ_ENCODER_VALUE: &[u8] = &[0xE0, 0x30, 0x10];
+/// `read_pulse_count`.
+pub const READ_PULSE_COUNT: &[u8] = &[0xE0, 0x33, 0x13];
+/// `read_motor_shaft_angle`.
+pub const READ_MOTOR_SHAFT_ANGLE: &[u8] = &[0xE0, 0x36, 0x16];
+/// `read_motor_shaft_angle_error`.
+pub const READ_MOTOR_SHAFT_ANGLE_ERROR: &[u8] = &[0xE0, 0x39, 0x19];
+/// `read_en_pin_status`.
+pub const READ_EN_PIN_STATUS: &[u8] = &[0xE0, 0x3A, 0x1A];
+/// `read_shaft_status`.
+pub const READ_SHAFT_STATUS: &[u8] = &[0xE0, 0x3E, 0x1E];
+/// `calibrate_encoder`.
+pub const CALIBRATE_ENCODER: &[u8] = &[0xE0, 0x80, 0x00, 0x60];
+/// `set_current_limit`, 1200 mA.
+pub const SET_CURRENT_LIMIT_1200MA: &[u8] = &[0xE0, 0x83, 0x06, 0x69];
+/// `set_subdivision`, 7 microsteps.
+pub const SET_SUBDIVISION_7: &[u8] = &[0xE0, 0x84, 0x07, 0x6B];
+/// `set_subdivision`, 26 microsteps.
+pub const SET_SUBDIVISION_26: &[u8] = &[0xE0, 0x84, 0x1A, 0x7E];
+/// `set_subdivision`, 256 microsteps (sent as `0x00`).
+pub const SET_SUBDIVISION_256: &[u8] = &[0xE0, 0x84, 0x00, 0x64];
+/// `set_enable_logic`, active low.
+pub const SET_ENABLE_LOGIC_LOW: &[u8] = &[0xE0, 0x85, 0x00, 0x65];
+/// `set_direction`, clockwise.
+pub const SET_DIRECTION_CW: &[u8] = &[0xE0, 0x86, 0x00, 0x66];
+/// `set_auto_screen_off`, disabled.
+pub const SET_AUTO_SCREEN_OFF_DISABLE: &[u8] = &[0xE0, 0x87, 0x00, 0x67];
+/// `set_stall_protection`, disabled.
+pub const SET_STALL_PROTECTION_DISABLE: &[u8] = &[0xE0, 0x88, 0x00, 0x68];
+/// `set_interpolation`, disabled.
+pub const SET_INTERPOLATION_DISABLE: &[u8] = &[0xE0, 0x89, 0x00, 0x69];
+/// `set_zero_mode`, direction mode.
+pub const SET_ZERO_MODE_DIR: &[u8] = &[0xE0, 0x90, 0x01, 0x71];
+/// `set_current_as_zero`.
+pub const SET_CURRENT_AS_ZERO: &[u8] = &[0xE0, 0x91, 0x00, 0x71];
+/// `set_zero_speed`, gear 1.
+///
+/// The manual prints the checksum as `0x72`; the correct sum is `0x73`.
+pub const SET_ZERO_SPEED_1: &[u8] = &[0xE0, 0x92, 0x01, 0x73];
+/// `set_zero_direction`, clockwise.
+pub const SET_ZERO_DIRECTION_CW: &[u8] = &[0xE0, 0x93, 0x00, 0x73];
+/// `go_to_zero`.
+pub const GO_TO_ZERO: &[u8] = &[0xE0, 0x94, 0x00, 0x74];
+/// `set_position_kp`, Kp = 0x120.
+pub const SET_POSITION_KP_0X120: &[u8] = &[0xE0, 0xA1, 0x01, 0x20, 0xA2];
+/// `set_position_ki`, Ki = 0x02.
+pub const SET_POSITION_KI_0X02: &[u8] = &[0xE0, 0xA2, 0x00, 0x02, 0x84];
+/// `set_position_kd`, Kd = 0x250.
+pub const SET_POSITION_KD_0X250: &[u8] = &[0xE0, 0xA3, 0x02, 0x50, 0xD5];
+/// `set_acceleration`, ACC = 0x80.
+pub const SET_ACCELERATION_0X80: &[u8] = &[0xE0, 0xA4, 0x00, 0x80, 0x04];
+/// `set_max_torque`, MaxT = 0x258.
+pub const SET_MAX_TORQUE_0X258: &[u8] = &[0xE0, 0xA5, 0x02, 0x58, 0xDF];
+/// `enable_motor(true)`.
+pub const ENABLE_MOTOR: &[u8] = &[0xE0, 0xF3, 0x01, 0xD4];
+/// `run_with_constant_speed`, clockwise at gear 1.
+pub const RUN_CW_GEAR_1: &[u8] = &[0xE0, 0xF6, 0x01, 0xD7];
+/// `run_with_constant_speed`, counter-clockwise at gear 90.
+pub const RUN_CCW_GEAR_90: &[u8] = &[0xE0, 0xF6, 0xDA, 0xB0];
+/// `stop`.
+pub const STOP: &[u8] = &[0xE0, 0xF7, 0xD7];
+/// `save_clear_status`, save.
+pub const SAVE_STATUS: &[u8] = &[0xE0, 0xFF, 0xC8, 0xA7];
+/// `run_motor`, clockwise at gear 1 for 3200 pulses (360° at 16 microsteps).
+pub const RUN_MOTOR_CW_360_DEG: &[u8] = &[0xE0, 0xFD, 0x01, 0x00, 0x00, 0x0C, 0x80, 0x6A];
+/// `run_motor`, counter-clockwise at gear 6 for 3200 pulses.
+pub const RUN_MOTOR_CCW_360_DEG: &[u8] = &[0xE0, 0xFD, 0x86, 0x00, 0x00, 0x0C, 0x80, 0xEF];
+
+// Replies.
+
+/// Encoder value with carry 0 and value 0x4000 (90°).
+pub const ENCODER_90_DEG: &[u8] = &[0xE0, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x20];
+/// Pulse count of 256.
+pub const PULSE_COUNT_256: &[u8] = &[0xE0, 0x00, 0x00, 0x01, 0x00, 0xE1];
+/// Motor shaft angle of 0x4000 (90°).
+pub const SHAFT_ANGLE_90_DEG: &[u8] = &[0xE0, 0x00, 0x00, 0x40, 0x00, 0x20];
+/// Shaft angle error of 0xB7 (1°), as captured: the firmware appends an
+/// undocumented `0x00` after the checksum.
+pub const ANGLE_ERROR_1_DEG: &[u8] = &[0xE0, 0x00, 0xB7, 0x97, 0x00];
+/// EN pin status: enabled.
+pub const EN_PIN_ENABLED: &[u8] = &[0xE0, 0x01, 0xE1];
+/// Shaft status: unblocked.
+pub const SHAFT_UNBLOCKED: &[u8] = &[0xE0, 0x02, 0xE2];
+/// Result of a write command: success.
+pub const SUCCESS: &[u8] = &[0xE0, 0x01, 0xE1];
+/// Result of a write command: failure.
+pub const FAILURE: &[u8] = &[0xE0, 0x00, 0xE0];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        parse_en_pin_status_response, parse_encoder_response, parse_motor_shaft_angle_error,
+        parse_motor_shaft_angle_response, parse_pulse_count_response, parse_shaft_status_response,
+        parse_success_response, EnPinStatus,
+    };
+    use crate::{
+        Driver, EnLogic, Milliamps, Pulses, Response, RotationDirection, SaveClearStatus,
+        ShaftStatus, Speed, ZeroMode,
+    };
+
+    #[test]
+    fn test_command_vectors() {
+        let driver = Driver::default();
+        let speed = |gear| Speed::new(gear).unwrap();
+        let cases: [(&[u8], &[u8]); 33] = [
+            (&driver.read_encoder_value(), READ_ENCODER_VALUE),
+            (&driver.read_pulse_count(), READ_PULSE_COUNT),
+            (
+                &driver.read_motor_shaft_angle().unwrap(),
+                READ_MOTOR_SHAFT_ANGLE,
+            ),
+            (
+                &driver.read_motor_shaft_angle_error(),
+                READ_MOTOR_SHAFT_ANGLE_ERROR,
+            ),
+            (&driver.read_en_pin_status(), READ_EN_PIN_STATUS),
+            (&driver.read_shaft_status(), READ_SHAFT_STATUS),
+            (&driver.calibrate_encoder(), CALIBRATE_ENCODER),
+            (
+                &driver.set_current_limit(Milliamps::new(1200).unwrap()),
+                SET_CURRENT_LIMIT_1200MA,
+            ),
+            (&driver.set_subdivision(7), SET_SUBDIVISION_7),
+            (&driver.set_subdivision(26), SET_SUBDIVISION_26),
+            (&driver.set_subdivision(0), SET_SUBDIVISION_256),
+            (&driver.set_enable_logic(EnLogic::Low), SET_ENABLE_LOGIC_LOW),
+            (
+                &driver.set_direction(RotationDirection::Clockwise),
+                SET_DIRECTION_CW,
+            ),
+            (
+                &driver.set_auto_screen_off(false),
+                SET_AUTO_SCREEN_OFF_DISABLE,
+            ),
+            (
+                &driver.set_stall_protection(false),
+                SET_STALL_PROTECTION_DISABLE,
+            ),
+            (&driver.set_interpolation(false), SET_INTERPOLATION_DISABLE),
+            (
+                &driver.set_zero_mode(ZeroMode::DirMode).unwrap(),
+                SET_ZERO_MODE_DIR,
+            ),
+            (&driver.set_current_as_zero(), SET_CURRENT_AS_ZERO),
+            (&driver.set_zero_speed(1).unwrap(), SET_ZERO_SPEED_1),
+            (
+                &driver
+                    .set_zero_direction(RotationDirection::Clockwise)
+                    .unwrap(),
+                SET_ZERO_DIRECTION_CW,
+            ),
+            (&driver.go_to_zero(), GO_TO_ZERO),
+            (
+                &driver.set_position_kp(0x120).unwrap(),
+                SET_POSITION_KP_0X120,
+            ),
+            (&driver.set_position_ki(0x02).unwrap(), SET_POSITION_KI_0X02),
+            (
+                &driver.set_position_kd(0x250).unwrap(),
+                SET_POSITION_KD_0X250,
+            ),
+            (
+                &driver.set_acceleration(0x80).unwrap(),
+                SET_ACCELERATION_0X80,
+            ),
+            (
+                &driver
+                    .set_max_torque(crate::TorqueLimit::new(0x258).unwrap())
+                    .unwrap(),
+                SET_MAX_TORQUE_0X258,
+            ),
+            (&driver.enable_motor(true), ENABLE_MOTOR),
+            (
+                &driver
+                    .run_with_constant_speed(RotationDirection::Clockwise, speed(1))
+                    .unwrap(),
+                RUN_CW_GEAR_1,
+            ),
+            (
+                &driver
+                    .run_with_constant_speed(RotationDirection::CounterClockwise, speed(90))
+                    .unwrap(),
+                RUN_CCW_GEAR_90,
+            ),
+            (&driver.stop(), STOP),
+            (
+                &driver.save_clear_status(SaveClearStatus::Save),
+                SAVE_STATUS,
+            ),
+            (
+                &driver
+                    .run_motor(RotationDirection::Clockwise, speed(1), Pulses::new(3200))
+                    .unwrap(),
+                RUN_MOTOR_CW_360_DEG,
+            ),
+            (
+                &driver
+                    .run_motor(
+                        RotationDirection::CounterClockwise,
+                        speed(6),
+                        Pulses::new(3200),
+                    )
+                    .unwrap(),
+                RUN_MOTOR_CCW_360_DEG,
+            ),
+        ];
+        for (built, vector) in cases {
+            assert_eq!(built, vector);
+        }
+    }
+
+    #[test]
+    fn test_reply_vectors() {
+        let encoder = parse_encoder_response(ENCODER_90_DEG).unwrap();
+        assert_eq!((encoder.carry, encoder.value), (0, 0x4000));
+        assert_eq!(
+            parse_pulse_count_response(PULSE_COUNT_256),
+            Ok(crate::PulseCount(256))
+        );
+        assert_eq!(
+            parse_motor_shaft_angle_response(SHAFT_ANGLE_90_DEG)
+                .unwrap()
+                .value,
+            0x4000
+        );
+        assert_eq!(
+            parse_motor_shaft_angle_error(ANGLE_ERROR_1_DEG)
+                .unwrap()
+                .value,
+            0xB7
+        );
+        assert_eq!(
+            parse_en_pin_status_response(EN_PIN_ENABLED),
+            Ok(EnPinStatus::Enabled)
+        );
+        assert_eq!(
+            parse_shaft_status_response(SHAFT_UNBLOCKED),
+            Ok(ShaftStatus::Unblocked)
+        );
+        assert_eq!(parse_success_response(SUCCESS), Ok(Response::Success));
+        assert_eq!(parse_success_response(FAILURE), Ok(Response::Failure));
+    }
+}
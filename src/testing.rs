@@ -0,0 +1,245 @@
+//! Hardware-in-the-loop test scaffolding (feature `testing`).
+//!
+//! Conservative motion limits, the commands that must never run against a
+//! board on the bench, [`FakeClock`] for deterministic timing, and
+//! [`AutoStopGuard`], which stops and disables the motor when a test ends or
+//! panics. The guard works over any transport that implements [`StopTarget`]:
+//!
+//! ```
+//! use mks_servo42_rs::testing::{AutoStopGuard, StopTarget};
+//! use mks_servo42_rs::Driver;
+//!
+//! struct Bench {
+//!     driver: Driver,
+//!     sent: usize,
+//! }
+//!
+//! impl StopTarget for Bench {
+//!     fn driver(&self) -> &Driver {
+//!         &self.driver
+//!     }
+//!
+//!     fn send(&mut self, _frame: &[u8]) {
+//!         self.sent += 1;
+//!     }
+//! }
+//!
+//! let mut bench = Bench { driver: Driver::default(), sent: 0 };
+//! {
+//!     let guarded = AutoStopGuard::new(&mut bench);
+//!     assert_eq!(guarded.sent, 0);
+//! }
+//! assert_eq!(bench.sent, 2); // stop + disable
+//! ```
+
+use core::cell::Cell;
+
+use crate::clock::{AsyncDelay, Clock, Delay};
+pub use crate::StopTarget;
+use crate::{index_for_microsteps, Error, MotionGuard, Result};
+
+/// Maximum safe speed for movement tests (gear 1 = minimal speed).
+pub const MAX_SAFE_SPEED: u8 = 1;
+
+/// Maximum safe angle for position movement tests.
+pub const MAX_SAFE_ANGLE_DEGREES: f32 = 10.0;
+
+/// Safe microstepping level for tests (microsteps per full step).
+pub const SAFE_MICROSTEPS: u16 = 4;
+
+/// Subdivision index selecting [`SAFE_MICROSTEPS`].
+pub const SAFE_SUBDIVISION_INDEX: u8 = match index_for_microsteps(SAFE_MICROSTEPS) {
+    Some(index) => index,
+    None => panic!("SAFE_MICROSTEPS is not in the subdivision table"),
+};
+
+/// Commands that should never be tested on real hardware, with the reason.
+///
+/// These can cause connection loss or irreversible changes.
+pub const DANGEROUS_COMMANDS: &[(&str, &str)] = &[
+    (
+        "set_work_mode",
+        "Changing control mode can break UART communication",
+    ),
+    ("set_baud_rate", "Changing baud rate will lose connection"),
+    (
+        "set_slave_address",
+        "Could make driver unresponsive if address is lost",
+    ),
+];
+
+/// Returns `true` if `command_name` is listed in [`DANGEROUS_COMMANDS`].
+#[must_use]
+pub fn should_skip_command(command_name: &str) -> bool {
+    DANGEROUS_COMMANDS
+        .iter()
+        .any(|(name, _)| *name == command_name)
+}
+
+/// Checks a speed gear against [`MAX_SAFE_SPEED`].
+///
+/// # Errors
+/// Returns `Error::InvalidValue` if `speed` exceeds the limit.
+pub const fn validate_safe_speed(speed: u8) -> Result<()> {
+    if speed > MAX_SAFE_SPEED {
+        return Err(Error::InvalidValue);
+    }
+    Ok(())
+}
+
+/// Checks an angle against [`MAX_SAFE_ANGLE_DEGREES`] in either direction.
+///
+/// # Errors
+/// Returns `Error::InvalidValue` if `angle_degrees` exceeds the limit or is NaN.
+pub fn validate_safe_angle(angle_degrees: f32) -> Result<()> {
+    if angle_degrees.abs() <= MAX_SAFE_ANGLE_DEGREES {
+        Ok(())
+    } else {
+        Err(Error::InvalidValue)
+    }
+}
+
+/// Stops and disables the motor when dropped, even if the test panics.
+///
+/// The crate-wide [`MotionGuard`] under its historical name.
+pub type AutoStopGuard<'a, T> = MotionGuard<'a, T>;
+
+/// A manually advanced [`Clock`], [`Delay`] and [`AsyncDelay`].
+///
+/// Delays return immediately and move the clock forward, so timeout and
+/// polling logic runs deterministically without sleeping:
+///
+/// ```
+/// use mks_servo42_rs::clock::{Clock, Delay};
+/// use mks_servo42_rs::testing::FakeClock;
+///
+/// let mut clock = FakeClock::new();
+/// clock.delay_ms(20);
+/// clock.advance(5);
+/// assert_eq!(clock.now_ms(), 25);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FakeClock {
+    now_ms: Cell<u64>,
+}
+
+impl FakeClock {
+    /// Creates a clock at time zero.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::starting_at(0)
+    }
+
+    /// Creates a clock at `now_ms`.
+    #[must_use]
+    pub const fn starting_at(now_ms: u64) -> Self {
+        Self {
+            now_ms: Cell::new(now_ms),
+        }
+    }
+
+    /// Moves the clock forward by `ms`.
+    ///
+    /// Takes `&self` so code holding the clock as a shared [`Clock`] can be
+    /// driven from the test.
+    pub fn advance(&self, ms: u64) {
+        self.now_ms.set(self.now_ms.get().saturating_add(ms));
+    }
+
+    /// Sets the clock to `now_ms`, which may move it backwards.
+    pub fn set(&self, now_ms: u64) {
+        self.now_ms.set(now_ms);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now_ms(&self) -> u64 {
+        self.now_ms.get()
+    }
+}
+
+impl Delay for FakeClock {
+    fn delay_ms(&mut self, ms: u32) {
+        self.advance(u64::from(ms));
+    }
+}
+
+impl AsyncDelay for FakeClock {
+    async fn delay_ms(&mut self, ms: u32) {
+        self.advance(u64::from(ms));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Driver;
+
+    struct Recorder {
+        driver: Driver,
+        frames: [[u8; 4]; 2],
+        count: usize,
+    }
+
+    impl StopTarget for Recorder {
+        fn driver(&self) -> &Driver {
+            &self.driver
+        }
+
+        fn send(&mut self, frame: &[u8]) {
+            self.frames[self.count][..frame.len()].copy_from_slice(frame);
+            self.count += 1;
+        }
+    }
+
+    #[test]
+    fn test_auto_stop_guard() {
+        let mut recorder = Recorder {
+            driver: Driver::default(),
+            frames: [[0; 4]; 2],
+            count: 0,
+        };
+        drop(AutoStopGuard::new(&mut recorder));
+        assert_eq!(recorder.count, 2);
+        assert_eq!(&recorder.frames[0][..3], &[0xE0, 0xF7, 0xD7]);
+        assert_eq!(recorder.frames[1], [0xE0, 0xF3, 0x00, 0xD3]);
+    }
+
+    #[test]
+    fn test_fake_clock() {
+        use crate::telemetry::{Channel, Poller};
+        use crate::Address;
+
+        let mut clock = FakeClock::starting_at(100);
+        assert_eq!(clock.elapsed_ms(40), 60);
+        clock.set(0);
+
+        let mut poller: Poller = Poller::new(Address::DEFAULT);
+        poller.schedule(Channel::PulseCount, 50).unwrap();
+        let reply = [0xE0, 0x00, 0x00, 0x01, 0x00, 0xE1];
+        let mut polls = 0;
+        while clock.now_ms() < 200 {
+            if poller.poll(clock.now_ms()).is_some() {
+                polls += 1;
+                poller
+                    .handle_response(clock.now_ms(), &reply, &mut |_| {})
+                    .unwrap();
+            }
+            Delay::delay_ms(&mut clock, 10);
+        }
+        assert_eq!(polls, 4);
+        assert_eq!(clock.elapsed_ms(500), 0);
+    }
+
+    #[test]
+    fn test_safety_limits() {
+        assert_eq!(SAFE_SUBDIVISION_INDEX, 4);
+        assert!(validate_safe_speed(MAX_SAFE_SPEED).is_ok());
+        assert_eq!(validate_safe_speed(2), Err(Error::InvalidValue));
+        assert!(validate_safe_angle(-MAX_SAFE_ANGLE_DEGREES).is_ok());
+        assert_eq!(validate_safe_angle(10.5), Err(Error::InvalidValue));
+        assert_eq!(validate_safe_angle(f32::NAN), Err(Error::InvalidValue));
+        assert!(should_skip_command("set_baud_rate"));
+        assert!(!should_skip_command("stop"));
+    }
+}
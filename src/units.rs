@@ -0,0 +1,651 @@
+use core::ops::{Add, Neg, Sub};
+
+use crate::helpers::{ENCODER_RESOLUTION, STEPS_PER_REV};
+use crate::Error;
+
+/// Speed scale factor from the manual: `Vrpm = (speed × 30000) / (Mstep × 200)`.
+const RPM_SCALE: f32 = 30000.0;
+
+/// An angle in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Degrees(pub f32);
+
+impl Degrees {
+    /// Returns the absolute value of the angle.
+    #[must_use]
+    pub fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+
+    /// Converts the angle to revolutions.
+    #[must_use]
+    pub fn to_revolutions(self) -> Revolutions {
+        Revolutions(self.0 / 360.0)
+    }
+}
+
+/// An angle in full shaft revolutions.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Revolutions(pub f32);
+
+impl Revolutions {
+    /// Returns the absolute value of the angle.
+    #[must_use]
+    pub fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+
+    /// Converts the angle to degrees.
+    #[must_use]
+    pub fn to_degrees(self) -> Degrees {
+        Degrees(self.0 * 360.0)
+    }
+}
+
+impl From<Revolutions> for Degrees {
+    fn from(revolutions: Revolutions) -> Self {
+        revolutions.to_degrees()
+    }
+}
+
+impl From<Degrees> for Revolutions {
+    fn from(degrees: Degrees) -> Self {
+        degrees.to_revolutions()
+    }
+}
+
+impl From<Degrees> for f32 {
+    fn from(degrees: Degrees) -> Self {
+        degrees.0
+    }
+}
+
+impl From<Revolutions> for f32 {
+    fn from(revolutions: Revolutions) -> Self {
+        revolutions.0
+    }
+}
+
+impl Add for Degrees {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Degrees {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Degrees {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Add for Revolutions {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Revolutions {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Revolutions {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+/// Validated speed gear for motion commands (0 to `MAX_SPEED`).
+///
+/// Construct once with [`Speed::new`] or [`Speed::from_rpm`]; motion commands
+/// accept it without further checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Speed(u8);
+
+impl Speed {
+    /// Slowest speed gear (motor holds position).
+    pub const ZERO: Self = Self(0);
+    /// Fastest speed gear (`MAX_SPEED`).
+    pub const MAX: Self = Self(crate::MAX_SPEED);
+
+    /// Creates a speed from a raw gear value.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if the gear exceeds `MAX_SPEED`.
+    pub const fn new(gear: u8) -> Result<Self, Error> {
+        if gear > crate::MAX_SPEED {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self(gear))
+    }
+
+    /// Picks the speed gear closest to `rpm` for a 1.8° motor at the given microstepping.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `rpm` is negative or not finite, if `microsteps`
+    /// is zero, or if the resulting gear exceeds `MAX_SPEED`.
+    pub fn from_rpm(rpm: f32, microsteps: u16) -> Result<Self, Error> {
+        if !rpm.is_finite() || rpm < 0.0 || microsteps == 0 {
+            return Err(Error::InvalidValue);
+        }
+        let gear = rpm * f32::from(microsteps) * STEPS_PER_REV / RPM_SCALE + 0.5;
+        // Float-to-int casts saturate, so oversized values still fail validation.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Self::new(gear as u8)
+    }
+
+    /// Returns the raw gear value sent on the wire.
+    #[must_use]
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    /// Converts the gear to RPM for a 1.8° motor at the given microstepping.
+    #[must_use]
+    pub fn to_rpm(self, microsteps: u16) -> f32 {
+        f32::from(self.0) * RPM_SCALE / (f32::from(microsteps) * STEPS_PER_REV)
+    }
+}
+
+impl TryFrom<u8> for Speed {
+    type Error = Error;
+
+    fn try_from(gear: u8) -> Result<Self, Self::Error> {
+        Self::new(gear)
+    }
+}
+
+impl From<Speed> for u8 {
+    fn from(speed: Speed) -> Self {
+        speed.0
+    }
+}
+
+/// Number of step pulses for a relative move.
+///
+/// Keeps pulse counts distinct from angles and encoder ticks; use the
+/// conversion constructors instead of passing raw integers around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Pulses(u32);
+
+impl Pulses {
+    /// Creates a pulse count from a raw value.
+    #[must_use]
+    pub const fn new(pulses: u32) -> Self {
+        Self(pulses)
+    }
+
+    /// Converts an angle to pulses for a 1.8° motor at the given microstepping.
+    ///
+    /// The direction is passed separately to motion commands, so only the
+    /// magnitude of `degrees` is used.
+    #[must_use]
+    pub fn from_degrees(degrees: Degrees, microsteps: u16) -> Self {
+        let pulses = (degrees.0.abs() / 360.0) * STEPS_PER_REV * f32::from(microsteps);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Self((pulses + 0.5) as u32)
+    }
+
+    /// Converts encoder ticks (65536 per revolution) to pulses for a 1.8° motor.
+    #[must_use]
+    pub fn from_ticks(ticks: u32, microsteps: u16) -> Self {
+        let pulses = f64::from(ticks) / f64::from(ENCODER_RESOLUTION)
+            * f64::from(STEPS_PER_REV)
+            * f64::from(microsteps);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Self((pulses + 0.5) as u32)
+    }
+
+    /// Returns the raw pulse count sent on the wire.
+    #[must_use]
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Converts the pulse count back to degrees for a 1.8° motor.
+    #[must_use]
+    pub fn to_degrees(self, microsteps: u16) -> Degrees {
+        Degrees(self.0 as f32 * 360.0 / (STEPS_PER_REV * f32::from(microsteps)))
+    }
+}
+
+impl From<Pulses> for u32 {
+    fn from(pulses: Pulses) -> Self {
+        pulses.0
+    }
+}
+
+/// Motor current in milliamps, bounded by the current limit table.
+///
+/// The firmware sets current in `CURRENT_STEP_MA` increments; values between
+/// steps are rounded down when converted to an index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Milliamps(u16);
+
+impl Milliamps {
+    /// Highest current reachable through the current limit index.
+    pub const MAX: Self = Self(crate::MAX_CURRENT_INDEX as u16 * crate::CURRENT_STEP_MA);
+
+    /// Creates a current value.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `milliamps` exceeds [`Milliamps::MAX`].
+    pub const fn new(milliamps: u16) -> Result<Self, Error> {
+        if milliamps > Self::MAX.0 {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self(milliamps))
+    }
+
+    /// Creates a current value from a current limit index.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `index` exceeds `MAX_CURRENT_INDEX`.
+    pub const fn from_index(index: u8) -> Result<Self, Error> {
+        if index > crate::MAX_CURRENT_INDEX {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self(index as u16 * crate::CURRENT_STEP_MA))
+    }
+
+    /// Returns the current in milliamps.
+    #[must_use]
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the current limit index, rounding down to the nearest step.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn index(self) -> u8 {
+        (self.0 / crate::CURRENT_STEP_MA) as u8
+    }
+}
+
+impl TryFrom<u16> for Milliamps {
+    type Error = Error;
+
+    fn try_from(milliamps: u16) -> Result<Self, Self::Error> {
+        Self::new(milliamps)
+    }
+}
+
+impl From<Milliamps> for u16 {
+    fn from(current: Milliamps) -> Self {
+        current.0
+    }
+}
+
+/// Maximum torque limit (0 to `MAX_TORQUE_LIMIT`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TorqueLimit(u16);
+
+impl TorqueLimit {
+    /// Highest torque limit, also the firmware default.
+    pub const MAX: Self = Self(crate::MAX_TORQUE_LIMIT);
+
+    /// Creates a torque limit.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if `value` exceeds `MAX_TORQUE_LIMIT`.
+    pub const fn new(value: u16) -> Result<Self, Error> {
+        if value > crate::MAX_TORQUE_LIMIT {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self(value))
+    }
+
+    /// Returns the raw torque limit sent on the wire.
+    #[must_use]
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+}
+
+impl Default for TorqueLimit {
+    fn default() -> Self {
+        Self::MAX
+    }
+}
+
+impl TryFrom<u16> for TorqueLimit {
+    type Error = Error;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<TorqueLimit> for u16 {
+    fn from(limit: TorqueLimit) -> Self {
+        limit.0
+    }
+}
+
+/// Position loop PID coefficients (0 to `MAX_PID_COEFFICIENT` each).
+///
+/// Named accessors keep Kp, Ki and Kd from being transposed when they are sent
+/// with [`Driver::set_pid`](crate::Driver::set_pid).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pid {
+    kp: u16,
+    ki: u16,
+    kd: u16,
+}
+
+impl Pid {
+    /// Firmware defaults: Kp = 0x650, Ki = 1, Kd = 0x650.
+    pub const DEFAULT: Self = Self {
+        kp: crate::defaults::POSITION_KP,
+        ki: crate::defaults::POSITION_KI,
+        kd: crate::defaults::POSITION_KD,
+    };
+
+    /// Creates a set of PID coefficients.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidValue` if any coefficient exceeds `MAX_PID_COEFFICIENT`.
+    pub const fn new(kp: u16, ki: u16, kd: u16) -> Result<Self, Error> {
+        let max = crate::MAX_PID_COEFFICIENT;
+        if kp > max || ki > max || kd > max {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self { kp, ki, kd })
+    }
+
+    /// Returns the proportional coefficient.
+    #[must_use]
+    pub const fn kp(self) -> u16 {
+        self.kp
+    }
+
+    /// Returns the integral coefficient.
+    #[must_use]
+    pub const fn ki(self) -> u16 {
+        self.ki
+    }
+
+    /// Returns the derivative coefficient.
+    #[must_use]
+    pub const fn kd(self) -> u16 {
+        self.kd
+    }
+}
+
+impl Default for Pid {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Conversions to and from `uom` quantities (feature `uom`).
+#[cfg(feature = "uom")]
+mod quantities {
+    use uom::si::angle::{degree, revolution};
+    use uom::si::angular_velocity::revolution_per_minute;
+    use uom::si::electric_current::milliampere;
+    use uom::si::f32::{Angle, AngularVelocity, ElectricCurrent};
+
+    use super::{Degrees, Milliamps, Pulses, Revolutions, Speed};
+    use crate::Error;
+
+    impl From<Degrees> for Angle {
+        fn from(degrees: Degrees) -> Self {
+            Self::new::<degree>(degrees.0)
+        }
+    }
+
+    impl From<Angle> for Degrees {
+        fn from(angle: Angle) -> Self {
+            Self(angle.get::<degree>())
+        }
+    }
+
+    impl From<Revolutions> for Angle {
+        fn from(revolutions: Revolutions) -> Self {
+            Self::new::<revolution>(revolutions.0)
+        }
+    }
+
+    impl From<Angle> for Revolutions {
+        fn from(angle: Angle) -> Self {
+            Self(angle.get::<revolution>())
+        }
+    }
+
+    impl From<Milliamps> for ElectricCurrent {
+        fn from(current: Milliamps) -> Self {
+            Self::new::<milliampere>(f32::from(current.get()))
+        }
+    }
+
+    impl TryFrom<ElectricCurrent> for Milliamps {
+        type Error = Error;
+
+        /// Rounds to the nearest milliamp.
+        ///
+        /// # Errors
+        /// Returns `Error::InvalidValue` if the current is negative, not finite or
+        /// exceeds [`Milliamps::MAX`].
+        fn try_from(current: ElectricCurrent) -> Result<Self, Self::Error> {
+            let milliamps = current.get::<milliampere>();
+            if !milliamps.is_finite() || milliamps < 0.0 {
+                return Err(Error::InvalidValue);
+            }
+            // Float-to-int casts saturate, so oversized values still fail validation.
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            Self::new((milliamps + 0.5) as u16)
+        }
+    }
+
+    impl Speed {
+        /// Picks the speed gear closest to `velocity`, as [`Speed::from_rpm`].
+        ///
+        /// # Errors
+        /// Same as [`Speed::from_rpm`].
+        pub fn from_angular_velocity(
+            velocity: AngularVelocity,
+            microsteps: u16,
+        ) -> Result<Self, Error> {
+            Self::from_rpm(velocity.get::<revolution_per_minute>(), microsteps)
+        }
+
+        /// Converts the gear to an angular velocity, as [`Speed::to_rpm`].
+        #[must_use]
+        pub fn to_angular_velocity(self, microsteps: u16) -> AngularVelocity {
+            AngularVelocity::new::<revolution_per_minute>(self.to_rpm(microsteps))
+        }
+    }
+
+    impl Pulses {
+        /// Converts an angle to pulses, as [`Pulses::from_degrees`].
+        #[must_use]
+        pub fn from_angle(angle: Angle, microsteps: u16) -> Self {
+            Self::from_degrees(angle.into(), microsteps)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degrees_revolutions_conversion() {
+        assert_eq!(Degrees(720.0).to_revolutions(), Revolutions(2.0));
+        assert_eq!(Revolutions(0.25).to_degrees(), Degrees(90.0));
+        assert_eq!(Degrees::from(Revolutions(-1.0)), Degrees(-360.0));
+        assert_eq!(Revolutions::from(Degrees(180.0)), Revolutions(0.5));
+        assert_eq!(f32::from(Degrees(12.5)), 12.5);
+        assert_eq!(f32::from(Revolutions(1.5)), 1.5);
+    }
+
+    #[test]
+    fn test_degrees_arithmetic() {
+        assert_eq!(Degrees(10.0) + Degrees(5.0), Degrees(15.0));
+        assert_eq!(Degrees(10.0) - Degrees(15.0), Degrees(-5.0));
+        assert_eq!(-Degrees(10.0), Degrees(-10.0));
+        assert_eq!(Degrees(-10.0).abs(), Degrees(10.0));
+        assert_eq!(Revolutions(1.0) + Revolutions(0.5), Revolutions(1.5));
+        assert_eq!(Revolutions(1.0) - Revolutions(1.5), Revolutions(-0.5));
+        assert_eq!(-Revolutions(1.0), Revolutions(-1.0));
+        assert_eq!(Revolutions(-2.0).abs(), Revolutions(2.0));
+    }
+
+    #[test]
+    fn test_speed_new() {
+        assert_eq!(Speed::new(0).map(Speed::get), Ok(0));
+        assert_eq!(Speed::new(crate::MAX_SPEED), Ok(Speed::MAX));
+        assert_eq!(Speed::new(crate::MAX_SPEED + 1), Err(Error::InvalidValue));
+        assert_eq!(Speed::try_from(0x80), Err(Error::InvalidValue));
+        assert_eq!(u8::from(Speed::MAX), crate::MAX_SPEED);
+    }
+
+    #[test]
+    fn test_speed_from_rpm() {
+        // Example from the manual: Mstep = 150, Speed = 1 -> 1 RPM
+        assert_eq!(Speed::from_rpm(1.0, 150).map(Speed::get), Ok(1));
+        assert_eq!(Speed::from_rpm(0.0, 16), Ok(Speed::ZERO));
+        // 16 microsteps: 1 gear = 9.375 RPM, so 100 RPM rounds to gear 11
+        assert_eq!(Speed::from_rpm(100.0, 16).map(Speed::get), Ok(11));
+        assert_eq!(Speed::from_rpm(10000.0, 16), Err(Error::InvalidValue));
+        assert_eq!(Speed::from_rpm(-1.0, 16), Err(Error::InvalidValue));
+        assert_eq!(Speed::from_rpm(f32::NAN, 16), Err(Error::InvalidValue));
+        assert_eq!(Speed::from_rpm(1.0, 0), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn test_speed_to_rpm() {
+        assert_eq!(Speed::new(1).unwrap().to_rpm(150), 1.0);
+        assert_eq!(Speed::new(16).unwrap().to_rpm(16), 150.0);
+    }
+
+    #[test]
+    fn test_pulses_from_degrees() {
+        // 1.8° motor at 16 microsteps: one revolution is 0x0C80 pulses (manual example)
+        assert_eq!(
+            Pulses::from_degrees(Degrees(360.0), 16),
+            Pulses::new(0x0C80)
+        );
+        assert_eq!(Pulses::from_degrees(Degrees(180.0), 4).get(), 400);
+        assert_eq!(Pulses::from_degrees(Degrees(-180.0), 4).get(), 400);
+        assert_eq!(Pulses::from_degrees(Degrees(0.0), 4).get(), 0);
+    }
+
+    #[test]
+    fn test_pulses_from_ticks() {
+        assert_eq!(Pulses::from_ticks(65536, 16), Pulses::new(3200));
+        assert_eq!(Pulses::from_ticks(16384, 1).get(), 50);
+        assert_eq!(Pulses::from_ticks(0, 16).get(), 0);
+    }
+
+    #[test]
+    fn test_pulses_to_degrees() {
+        assert_eq!(Pulses::new(3200).to_degrees(16), Degrees(360.0));
+        assert_eq!(u32::from(Pulses::new(42)), 42);
+    }
+
+    #[test]
+    fn test_milliamps() {
+        assert_eq!(Milliamps::MAX.get(), 3000);
+        assert_eq!(Milliamps::new(3000), Ok(Milliamps::MAX));
+        assert_eq!(Milliamps::new(3001), Err(Error::InvalidValue));
+        assert_eq!(Milliamps::try_from(1000).map(Milliamps::index), Ok(5));
+        // Values between steps round down
+        assert_eq!(Milliamps::new(1199).map(Milliamps::index), Ok(5));
+        assert_eq!(Milliamps::from_index(6).map(Milliamps::get), Ok(1200));
+        assert_eq!(
+            Milliamps::from_index(crate::MAX_CURRENT_INDEX + 1),
+            Err(Error::InvalidValue)
+        );
+        assert_eq!(u16::from(Milliamps::new(400).unwrap()), 400);
+    }
+
+    #[test]
+    fn test_torque_limit() {
+        assert_eq!(TorqueLimit::new(0x4B0), Ok(TorqueLimit::MAX));
+        assert_eq!(TorqueLimit::new(0x4B1), Err(Error::InvalidValue));
+        assert_eq!(
+            TorqueLimit::try_from(0x258).map(TorqueLimit::get),
+            Ok(0x258)
+        );
+        assert_eq!(TorqueLimit::default(), TorqueLimit::MAX);
+        assert_eq!(u16::from(TorqueLimit::MAX), crate::MAX_TORQUE_LIMIT);
+    }
+
+    #[test]
+    fn test_pid() {
+        let pid = Pid::new(0x120, 2, 0x300).unwrap();
+        assert_eq!((pid.kp(), pid.ki(), pid.kd()), (0x120, 2, 0x300));
+        assert_eq!(Pid::default(), Pid::new(0x650, 1, 0x650).unwrap());
+
+        let max = crate::MAX_PID_COEFFICIENT;
+        assert!(Pid::new(max, max, max).is_ok());
+        assert_eq!(Pid::new(max + 1, 1, 1), Err(Error::InvalidValue));
+        assert_eq!(Pid::new(1, max + 1, 1), Err(Error::InvalidValue));
+        assert_eq!(Pid::new(1, 1, max + 1), Err(Error::InvalidValue));
+    }
+
+    #[cfg(feature = "uom")]
+    #[test]
+    fn test_uom_conversions() {
+        use uom::si::angle::{degree, revolution};
+        use uom::si::angular_velocity::revolution_per_minute;
+        use uom::si::electric_current::{ampere, milliampere};
+        use uom::si::f32::{Angle, AngularVelocity, ElectricCurrent};
+
+        let angle = Angle::from(Degrees(180.0));
+        assert!((angle.get::<revolution>() - 0.5).abs() < 1e-6);
+        assert!((Degrees::from(Angle::new::<revolution>(0.25)).0 - 90.0).abs() < 1e-4);
+        assert!((Revolutions::from(Angle::new::<degree>(720.0)).0 - 2.0).abs() < 1e-6);
+
+        let current = ElectricCurrent::from(Milliamps::new(1200).unwrap());
+        assert!((current.get::<ampere>() - 1.2).abs() < 1e-6);
+        assert_eq!(
+            Milliamps::try_from(ElectricCurrent::new::<ampere>(1.2)),
+            Ok(Milliamps::new(1200).unwrap())
+        );
+        assert_eq!(
+            Milliamps::try_from(ElectricCurrent::new::<milliampere>(-1.0)),
+            Err(Error::InvalidValue)
+        );
+        assert_eq!(
+            Milliamps::try_from(ElectricCurrent::new::<ampere>(4.0)),
+            Err(Error::InvalidValue)
+        );
+
+        let velocity = AngularVelocity::new::<revolution_per_minute>(150.0);
+        assert_eq!(
+            Speed::from_angular_velocity(velocity, 16),
+            Speed::from_rpm(150.0, 16)
+        );
+        let speed = Speed::new(10).unwrap();
+        assert!(
+            (speed.to_angular_velocity(16).get::<revolution_per_minute>() - speed.to_rpm(16)).abs()
+                < 1e-4
+        );
+        assert_eq!(
+            Pulses::from_angle(Angle::new::<degree>(90.0), 16),
+            Pulses::from_degrees(Degrees(90.0), 16)
+        );
+    }
+}
@@ -5,50 +5,22 @@
 //!
 //! SAFETY: Tests use minimal speed and small movements to avoid damage.
 
-mod safety;
 mod test_utils;
 
 // use mks_servo42_rs::direction::Direction; (removed)
-use mks_servo42_rs::{EnLogic, RotationDirection, SaveClearStatus, ZeroMode};
-use safety::{
-    validate_safe_angle, validate_safe_speed, MAX_SAFE_ANGLE_DEGREES, MAX_SAFE_SPEED,
-    SAFE_MICROSTEPS,
+use mks_servo42_rs::helpers::parse_pulse_count_response;
+use mks_servo42_rs::telemetry::{verify_move, MoveOutcome, SettleCriteria};
+use mks_servo42_rs::testing::{
+    self as safety, validate_safe_angle, validate_safe_speed, AutoStopGuard,
+    MAX_SAFE_ANGLE_DEGREES, MAX_SAFE_SPEED, SAFE_MICROSTEPS, SAFE_SUBDIVISION_INDEX,
+};
+use mks_servo42_rs::{
+    parse_ack, Ack, Degrees, EnLogic, Milliamps, Pulses, RotationDirection, SaveClearStatus, Speed,
+    TorqueLimit, ZeroMode,
 };
-use std::ops::{Deref, DerefMut};
 use std::time::Duration;
 use test_utils::{init_env, TestContext, TestError, TestResult, LONG_PAUSE, TEST_MUTEX};
 
-/// Guard to ensure motor is stopped even if test panics or fails
-struct AutoStopGuard<'a> {
-    pub ctx: &'a mut TestContext,
-}
-
-impl<'a> Drop for AutoStopGuard<'a> {
-    fn drop(&mut self) {
-        println!("AutoStopGuard: Stopping and disabling motor...");
-        // Try to stop
-        let _ = self.ctx.serial.send_only(self.ctx.driver.stop());
-        // Try to disable
-        let _ = self
-            .ctx
-            .serial
-            .send_only(self.ctx.driver.enable_motor(false));
-    }
-}
-
-impl<'a> Deref for AutoStopGuard<'a> {
-    type Target = TestContext;
-    fn deref(&self) -> &Self::Target {
-        self.ctx
-    }
-}
-
-impl<'a> DerefMut for AutoStopGuard<'a> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.ctx
-    }
-}
-
 /// Test basic motor enable/disable
 #[test]
 fn test_motor_enable_disable() -> TestResult<()> {
@@ -66,7 +38,7 @@ fn test_motor_enable_disable() -> TestResult<()> {
         .serial
         .send_and_read(guarded.ctx.driver.enable_motor(true))?;
 
-    if response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Motor enabled successfully");
     } else {
         println!("Warning: Enable response not as expected: {:?}", response);
@@ -82,7 +54,7 @@ fn test_motor_enable_disable() -> TestResult<()> {
         .serial
         .send_and_read(guarded.ctx.driver.enable_motor(false))?;
 
-    if response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Motor disabled successfully");
     } else {
         println!("Warning: Disable response not as expected: {:?}", response);
@@ -107,10 +79,9 @@ fn test_run_with_constant_speed() -> TestResult<()> {
     let guarded = AutoStopGuard { ctx: &mut ctx };
 
     // Set safe subdivision
-    // Cast SAFE_MICROSTEPS (f32) to u8 directly, assuming integer value like 4.0 -> 4
-    let steps = SAFE_MICROSTEPS as u8;
+    let steps = SAFE_SUBDIVISION_INDEX;
     println!("Setting safe subdivision to {}...", steps);
-    let cmd = guarded.ctx.driver.set_subdivision(steps)?;
+    let cmd = guarded.ctx.driver.set_subdivision(steps);
     // Read response to ensure setting applied
     guarded.ctx.serial.send_and_read(cmd)?;
 
@@ -136,7 +107,7 @@ fn test_run_with_constant_speed() -> TestResult<()> {
     let cmd = guarded
         .ctx
         .driver
-        .run_with_constant_speed(RotationDirection::Clockwise, MAX_SAFE_SPEED)?;
+        .run_with_constant_speed(RotationDirection::Clockwise, Speed::new(MAX_SAFE_SPEED)?)?;
     guarded.ctx.serial.send_only(cmd)?;
 
     // Let it run briefly
@@ -170,10 +141,10 @@ fn test_run_with_constant_speed() -> TestResult<()> {
 
     // Run backward (CCW)
     println!("Running CCW at speed {}...", MAX_SAFE_SPEED);
-    let cmd = guarded
-        .ctx
-        .driver
-        .run_with_constant_speed(RotationDirection::CounterClockwise, MAX_SAFE_SPEED)?;
+    let cmd = guarded.ctx.driver.run_with_constant_speed(
+        RotationDirection::CounterClockwise,
+        Speed::new(MAX_SAFE_SPEED)?,
+    )?;
     guarded.ctx.serial.send_only(cmd)?;
 
     // Let it run briefly
@@ -220,9 +191,9 @@ fn test_run_motor() -> TestResult<()> {
     let guarded = AutoStopGuard { ctx: &mut ctx };
 
     // Set safe subdivision
-    let steps = SAFE_MICROSTEPS as u8;
+    let steps = SAFE_SUBDIVISION_INDEX;
     println!("Setting safe subdivision to {}...", steps);
-    let cmd = guarded.ctx.driver.set_subdivision(steps)?;
+    let cmd = guarded.ctx.driver.set_subdivision(steps);
     guarded.ctx.serial.send_and_read(cmd)?;
 
     // Enable motor
@@ -243,17 +214,19 @@ fn test_run_motor() -> TestResult<()> {
     println!("Initial Angle: {:.2}°", initial_angle);
 
     // Calculate pulses for safe angle
-    let pulses = mks_servo42_rs::angle_to_steps(MAX_SAFE_ANGLE_DEGREES, SAFE_MICROSTEPS);
+    let pulses = Pulses::from_degrees(Degrees(MAX_SAFE_ANGLE_DEGREES), SAFE_MICROSTEPS);
     println!(
         "Moving {}° Clockwise ({} pulses)...",
-        MAX_SAFE_ANGLE_DEGREES, pulses
+        MAX_SAFE_ANGLE_DEGREES,
+        pulses.get()
     );
 
     // Move CW
-    let cmd = guarded
-        .ctx
-        .driver
-        .run_motor(RotationDirection::Clockwise, MAX_SAFE_SPEED, pulses)?;
+    let cmd = guarded.ctx.driver.run_motor(
+        RotationDirection::Clockwise,
+        Speed::new(MAX_SAFE_SPEED)?,
+        pulses,
+    )?;
     guarded.ctx.serial.send_only(cmd)?;
 
     // Wait for movement
@@ -288,11 +261,12 @@ fn test_run_motor() -> TestResult<()> {
     // Move CCW
     println!(
         "Moving {}° Counter-Clockwise ({} pulses)...",
-        MAX_SAFE_ANGLE_DEGREES, pulses
+        MAX_SAFE_ANGLE_DEGREES,
+        pulses.get()
     );
     let cmd = guarded.ctx.driver.run_motor(
         RotationDirection::CounterClockwise,
-        MAX_SAFE_SPEED,
+        Speed::new(MAX_SAFE_SPEED)?,
         pulses,
     )?;
     guarded.ctx.serial.send_only(cmd)?;
@@ -315,7 +289,7 @@ fn test_run_motor() -> TestResult<()> {
     println!("Final Delta from Initial: {:.2}°", delta_total);
 
     // Should be close to 0
-    if delta_total.abs() > 3.0 {
+    if !SettleCriteria::DEFAULT.within(Degrees(initial_angle), Degrees(final_angle)) {
         println!(
             "Warning: Final angle not exactly initial. Delta: {}",
             delta_total
@@ -399,7 +373,7 @@ fn test_read_motor_shaft_angle() -> TestResult<()> {
     let response = guarded
         .ctx
         .serial
-        .send_and_read(guarded.ctx.driver.read_motor_shaft_angle())?;
+        .send_and_read(guarded.ctx.driver.read_motor_shaft_angle()?)?;
 
     if !response.is_empty() {
         match test_utils::parse_motor_shaft_angle_response(&response) {
@@ -549,17 +523,8 @@ fn test_read_pulse_count() -> TestResult<()> {
 
     if !response.is_empty() {
         println!("Pulse count response: {:02x?}", response);
-        // Parse 4-byte signed integer
-        if response.len() >= 6 {
-            // address + 4 bytes + checksum
-            let pulse_bytes = &response[1..5];
-            let pulses = i32::from_be_bytes([
-                pulse_bytes[0],
-                pulse_bytes[1],
-                pulse_bytes[2],
-                pulse_bytes[3],
-            ]);
-            println!("Pulse count: {}", pulses);
+        if let Ok(pulses) = parse_pulse_count_response(&response) {
+            println!("Pulse count: {}", pulses.0);
         }
     } else {
         println!("No pulse count response received");
@@ -580,13 +545,13 @@ fn test_set_subdivision() -> TestResult<()> {
 
     let mut ctx = TestContext::new()?;
 
-    // Try to set subdivision to a safe value (4 microsteps)
-    println!("Setting subdivision to 4 microsteps...");
-    let cmd = ctx.driver.set_subdivision(4)?; // Index 4 = 16 microsteps per full step
+    // Try to set subdivision to a safe value (16 microsteps)
+    println!("Setting subdivision to 16 microsteps...");
+    let cmd = ctx.driver.set_subdivision(16);
     let response = ctx.serial.send_and_read(cmd)?;
 
     if !response.is_empty() && response.len() >= 3 {
-        if response[1] == 0x01 {
+        if parse_ack(&response).is_ok_and(Ack::is_success) {
             println!("Subdivision set successfully");
         } else {
             println!("Failed to set subdivision: response {:02x?}", response);
@@ -613,11 +578,13 @@ fn test_set_max_torque() -> TestResult<()> {
     let default_torque = 0x4B0;
     println!("Setting max torque to default {}...", default_torque);
 
-    let cmd = ctx.driver.set_max_torque(default_torque)?;
+    let cmd = ctx
+        .driver
+        .set_max_torque(TorqueLimit::new(default_torque)?)?;
     let response = ctx.serial.send_and_read(cmd)?;
 
     if !response.is_empty() && response.len() >= 3 {
-        if response[1] == 0x01 {
+        if parse_ack(&response).is_ok_and(Ack::is_success) {
             println!("Max torque set successfully");
         } else {
             println!("Failed to set max torque: response {:02x?}", response);
@@ -651,17 +618,17 @@ fn test_misc_config() -> TestResult<()> {
     println!("Setting auto screen off (disable)...");
     let cmd = ctx.driver.set_auto_screen_off(false);
     let response = ctx.serial.send_and_read(cmd)?;
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Auto screen off disabled");
     } else {
         println!("Failed set_auto_screen_off: {:?}", response);
     }
 
-    // set_stall_protection (0x88) - Enable
+    // set_stall_protection (0x88) - Enable (before 2.0.0 this sent 00 and disabled it)
     println!("Setting stall protection (enable)...");
     let cmd = ctx.driver.set_stall_protection(true);
     let response = ctx.serial.send_and_read(cmd)?;
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Stall protection enabled");
     } else {
         println!("Failed set_stall_protection: {:?}", response);
@@ -671,7 +638,7 @@ fn test_misc_config() -> TestResult<()> {
     println!("Setting interpolation (enable)...");
     let cmd = ctx.driver.set_interpolation(true);
     let response = ctx.serial.send_and_read(cmd)?;
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Interpolation enabled");
     } else {
         println!("Failed set_interpolation: {:?}", response);
@@ -694,10 +661,10 @@ fn test_set_position_kp() -> TestResult<()> {
     let default_kp = 0x650;
     println!("Setting position KP to default {}...", default_kp);
 
-    let cmd = ctx.driver.set_position_kp(default_kp);
+    let cmd = ctx.driver.set_position_kp(default_kp)?;
     let response = ctx.serial.send_and_read(cmd)?;
 
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Position KP set successfully");
     } else {
         println!("Failed to set position KP: response {:02x?}", response);
@@ -725,10 +692,10 @@ fn test_set_position_ki() -> TestResult<()> {
     let default_ki = 1;
     println!("Setting position KI to default {}...", default_ki);
 
-    let cmd = ctx.driver.set_position_ki(default_ki);
+    let cmd = ctx.driver.set_position_ki(default_ki)?;
     let response = ctx.serial.send_and_read(cmd)?;
 
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Position KI set successfully");
     } else {
         println!("Failed to set position KI: response {:02x?}", response);
@@ -756,10 +723,10 @@ fn test_set_position_kd() -> TestResult<()> {
     let default_kd = 0x650;
     println!("Setting position KD to default {}...", default_kd);
 
-    let cmd = ctx.driver.set_position_kd(default_kd);
+    let cmd = ctx.driver.set_position_kd(default_kd)?;
     let response = ctx.serial.send_and_read(cmd)?;
 
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Position KD set successfully");
     } else {
         println!("Failed to set position KD: response {:02x?}", response);
@@ -787,10 +754,10 @@ fn test_set_acceleration() -> TestResult<()> {
     let default_acc = 0x11e;
     println!("Setting acceleration to default {}...", default_acc);
 
-    let cmd = ctx.driver.set_acceleration(default_acc);
+    let cmd = ctx.driver.set_acceleration(default_acc)?;
     let response = ctx.serial.send_and_read(cmd)?;
 
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Acceleration set successfully");
     } else {
         println!("Failed to set acceleration: response {:02x?}", response);
@@ -812,8 +779,6 @@ fn test_set_max_torque_out_of_range() -> TestResult<()> {
 
     println!("=== Test: set_max_torque_out_of_range ===");
 
-    let mut ctx = TestContext::new()?;
-
     // Max allowed is 0x4B0. Try 0x4B1.
     let out_of_range_torque = 0x4B1;
     println!(
@@ -821,20 +786,20 @@ fn test_set_max_torque_out_of_range() -> TestResult<()> {
         out_of_range_torque
     );
 
-    let result = ctx.driver.set_max_torque(out_of_range_torque);
+    let result = TorqueLimit::new(out_of_range_torque);
 
     match result {
         Ok(_) => {
             println!("Error: mismatched expectation. Should have failed.");
             return Err(TestError::Protocol(
-                "Driver allowed out of range torque".into(),
+                "TorqueLimit allowed out of range torque".into(),
             ));
         }
         Err(mks_servo42_rs::Error::InvalidValue) => {
-            println!("Driver correctly rejected out of range torque with InvalidValue.");
+            println!("TorqueLimit correctly rejected out of range torque with InvalidValue.");
         }
         Err(e) => {
-            println!("Driver failed with unexpected error: {:?}", e);
+            println!("TorqueLimit failed with unexpected error: {:?}", e);
             return Err(TestError::Protocol(format!(
                 "Unexpected error type: {:?}",
                 e
@@ -975,20 +940,19 @@ fn test_set_current_limit() -> TestResult<()> {
 
     let mut ctx = TestContext::new()?;
 
-    // User constraint: use index for 1000ma value
     // CURRENT_STEP_MA = 200. 1000 / 200 = 5.
-    let index: u8 = 5;
+    let current = Milliamps::new(1000)?;
     println!(
         "Setting current limit to index {} ({}mA)...",
-        index,
-        index as u16 * mks_servo42_rs::CURRENT_STEP_MA
+        current.index(),
+        current.get()
     );
 
-    let cmd = ctx.driver.set_current_limit(index)?;
+    let cmd = ctx.driver.set_current_limit(current);
     let response = ctx.serial.send_and_read(cmd)?;
 
     if !response.is_empty() && response.len() >= 3 {
-        if response[1] == 0x01 {
+        if parse_ack(&response).is_ok_and(Ack::is_success) {
             println!("Current limit set successfully");
         } else {
             println!("Failed to set current limit: {:02x?}", response);
@@ -1024,7 +988,7 @@ fn test_set_en_logic() -> TestResult<()> {
     let response = ctx.serial.send_and_read(cmd)?;
 
     if !response.is_empty() && response.len() >= 3 {
-        if response[1] == 0x01 {
+        if parse_ack(&response).is_ok_and(Ack::is_success) {
             println!("EN logic set successfully");
         } else {
             println!("Failed to set EN logic: {:02x?}", response);
@@ -1057,7 +1021,7 @@ fn test_set_direction() -> TestResult<()> {
     let cmd = ctx.driver.set_direction(RotationDirection::Clockwise);
     let response = ctx.serial.send_and_read(cmd)?;
 
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Direction set to CW successfully");
     } else {
         println!("Failed to set direction CW: {:02x?}", response);
@@ -1072,7 +1036,7 @@ fn test_set_direction() -> TestResult<()> {
         .set_direction(RotationDirection::CounterClockwise);
     let response = ctx.serial.send_and_read(cmd)?;
 
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("Direction set to CCW successfully");
     } else {
         println!("Failed to set direction CCW: {:02x?}", response);
@@ -1102,9 +1066,9 @@ fn test_zero_mode_workflow() -> TestResult<()> {
 
     // Step 1: Set zero mode to DirMode
     println!("Step 1: Setting zero mode to DirMode...");
-    let cmd = guarded.ctx.driver.set_zero_mode(ZeroMode::DirMode);
+    let cmd = guarded.ctx.driver.set_zero_mode(ZeroMode::DirMode)?;
     let response = guarded.ctx.serial.send_and_read(cmd)?;
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("  Zero mode set to DirMode successfully");
     } else {
         println!("  Failed to set zero mode: {:02x?}", response);
@@ -1120,9 +1084,9 @@ fn test_zero_mode_workflow() -> TestResult<()> {
     let cmd = guarded
         .ctx
         .driver
-        .set_zero_direction(RotationDirection::Clockwise);
+        .set_zero_direction(RotationDirection::Clockwise)?;
     let response = guarded.ctx.serial.send_and_read(cmd)?;
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("  Zero direction set to CW successfully");
     } else {
         println!(
@@ -1136,7 +1100,7 @@ fn test_zero_mode_workflow() -> TestResult<()> {
     println!("Step 3: Setting zero speed to 2...");
     let cmd = guarded.ctx.driver.set_zero_speed(2)?;
     let response = guarded.ctx.serial.send_and_read(cmd)?;
-    if !response.is_empty() && response.len() >= 3 && response[1] == 0x01 {
+    if parse_ack(&response).is_ok_and(Ack::is_success) {
         println!("  Zero speed set successfully");
     } else {
         println!("  Failed to set zero speed: {:02x?}", response);
@@ -1180,7 +1144,7 @@ fn test_zero_mode_workflow() -> TestResult<()> {
 
     // Step 5: Set safe subdivision
     println!("Step 5: Setting safe subdivision...");
-    let cmd = guarded.ctx.driver.set_subdivision(SAFE_MICROSTEPS as u8)?;
+    let cmd = guarded.ctx.driver.set_subdivision(SAFE_SUBDIVISION_INDEX);
     guarded.ctx.serial.send_and_read(cmd)?;
     std::thread::sleep(Duration::from_millis(100));
 
@@ -1192,6 +1156,7 @@ fn test_zero_mode_workflow() -> TestResult<()> {
         .send_and_read(guarded.ctx.driver.read_encoder_value())?;
     let initial_angle = test_utils::parse_encoder_response(&response)
         .map_err(|e| TestError::Protocol(format!("Failed to parse initial encoder: {:?}", e)))?;
+    let initial_reading = mks_servo42_rs::parse_encoder_response(&response)?;
     println!("  Initial Angle: {:.2}°", initial_angle);
 
     // Step 7: Set current position as zero
@@ -1200,7 +1165,7 @@ fn test_zero_mode_workflow() -> TestResult<()> {
     match guarded.ctx.serial.send_and_read(cmd) {
         Ok(response) => {
             if !response.is_empty() && response.len() >= 3 {
-                if response[1] == 0x01 {
+                if parse_ack(&response).is_ok_and(Ack::is_success) {
                     println!("  Current position set as zero successfully");
                 } else {
                     println!(
@@ -1226,15 +1191,17 @@ fn test_zero_mode_workflow() -> TestResult<()> {
 
     // Step 8: Move motor 20 degrees CW
     let move_angle = 20.0_f32;
-    let pulses = mks_servo42_rs::angle_to_steps(move_angle, SAFE_MICROSTEPS);
+    let pulses = Pulses::from_degrees(Degrees(move_angle), SAFE_MICROSTEPS);
     println!(
         "Step 8: Moving motor {:.0}° Clockwise ({} pulses)...",
-        move_angle, pulses
+        move_angle,
+        pulses.get()
     );
-    let cmd = guarded
-        .ctx
-        .driver
-        .run_motor(RotationDirection::Clockwise, MAX_SAFE_SPEED, pulses)?;
+    let cmd = guarded.ctx.driver.run_motor(
+        RotationDirection::Clockwise,
+        Speed::new(MAX_SAFE_SPEED)?,
+        pulses,
+    )?;
     guarded.ctx.serial.send_only(cmd)?;
     std::thread::sleep(LONG_PAUSE);
 
@@ -1250,12 +1217,19 @@ fn test_zero_mode_workflow() -> TestResult<()> {
         .map_err(|e| TestError::Protocol(format!("Failed to parse moved encoder: {:?}", e)))?;
     println!("  Angle after move: {:.2}°", moved_angle);
 
-    let delta_move = moved_angle - initial_angle;
+    let moved_reading = mks_servo42_rs::parse_encoder_response(&response)?;
+    let report = verify_move(
+        initial_reading,
+        moved_reading,
+        Degrees(move_angle),
+        Degrees(5.0),
+    );
+    let delta_move: f32 = report.measured.into();
     println!("  Delta from initial: {:.2}°", delta_move);
-    if delta_move.abs() < 5.0 {
+    if report.outcome != MoveOutcome::Ok {
         println!(
-            "  Warning: Motor did not move significantly. Delta: {:.2}",
-            delta_move
+            "  Warning: Move verification reported {:?}. Delta: {:.2}",
+            report.outcome, delta_move
         );
     }
 
@@ -1265,7 +1239,7 @@ fn test_zero_mode_workflow() -> TestResult<()> {
     match guarded.ctx.serial.send_and_read(cmd) {
         Ok(response) => {
             if !response.is_empty() && response.len() >= 3 {
-                if response[1] == 0x01 {
+                if parse_ack(&response).is_ok_and(Ack::is_success) {
                     println!("  Go to zero command accepted");
                 } else {
                     println!("  Go to zero returned status: {:02x}", response[1]);
@@ -1303,7 +1277,11 @@ fn test_zero_mode_workflow() -> TestResult<()> {
     println!("  Delta from initial (after return): {:.2}°", delta_return);
 
     // Check if returned close to initial position (within 5 degrees tolerance)
-    if delta_return.abs() > 5.0 {
+    let criteria = SettleCriteria {
+        tolerance: Degrees(5.0),
+        ..SettleCriteria::DEFAULT
+    };
+    if !criteria.within(Degrees(initial_angle), Degrees(final_angle)) {
         println!(
             "  Warning: Motor did not return exactly to zero. Delta: {:.2}°",
             delta_return
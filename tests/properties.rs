@@ -0,0 +1,218 @@
+//! Property-based tests for the frame encoder and the reply parsers.
+//!
+//! Unlike `integration.rs`, these run without hardware.
+
+use mks_servo42_rs::helpers::{parse_pulse_count_response, PulseCount};
+use mks_servo42_rs::{
+    parse_en_pin_status_response, parse_encoder_response, parse_motor_shaft_angle_error,
+    parse_motor_shaft_angle_response, parse_shaft_status_response, parse_success_response,
+    strip_leading_garbage, Command, Driver, EnLogic, Error, Milliamps, Pulses, RotationDirection,
+    SaveClearStatus, Speed, TorqueLimit, ZeroMode, MAX_ACCELERATION, MAX_ADDRESS,
+    MAX_CURRENT_INDEX, MAX_SPEED, MAX_TORQUE_LIMIT, MIN_ADDRESS,
+};
+use proptest::prelude::*;
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
+
+fn direction() -> impl Strategy<Value = RotationDirection> {
+    prop_oneof![
+        Just(RotationDirection::Clockwise),
+        Just(RotationDirection::CounterClockwise)
+    ]
+}
+
+fn speed() -> impl Strategy<Value = Speed> {
+    (0..=MAX_SPEED).prop_map(|gear| Speed::new(gear).unwrap())
+}
+
+/// Every `Command` variant, with payloads drawn from their full valid ranges
+/// (and beyond, for the variants the encoder validates).
+fn command() -> impl Strategy<Value = Command> {
+    prop_oneof![
+        any::<bool>().prop_map(Command::EnableMotor),
+        (direction(), speed())
+            .prop_map(|(direction, speed)| Command::RunWithConstantSpeed { direction, speed }),
+        Just(Command::Stop),
+        prop_oneof![Just(SaveClearStatus::Save), Just(SaveClearStatus::Clear)]
+            .prop_map(Command::SaveClearStatus),
+        (direction(), speed(), any::<u32>()).prop_map(|(direction, speed, pulses)| {
+            Command::RunMotor {
+                direction,
+                speed,
+                pulses: Pulses::new(pulses),
+            }
+        }),
+        Just(Command::CalibrateEncoder),
+        (0..=MAX_CURRENT_INDEX)
+            .prop_map(|index| Command::SetCurrentLimit(Milliamps::from_index(index).unwrap())),
+        any::<u8>().prop_map(Command::SetSubdivision),
+        prop_oneof![
+            Just(EnLogic::Low),
+            Just(EnLogic::High),
+            Just(EnLogic::AlwaysOn)
+        ]
+        .prop_map(Command::SetEnableLogic),
+        direction().prop_map(Command::SetDirection),
+        any::<bool>().prop_map(Command::SetAutoScreenOff),
+        any::<bool>().prop_map(Command::SetStallProtection),
+        any::<bool>().prop_map(Command::SetInterpolation),
+        prop_oneof![
+            Just(ZeroMode::Disable),
+            Just(ZeroMode::DirMode),
+            Just(ZeroMode::NearMode)
+        ]
+        .prop_map(Command::SetZeroMode),
+        Just(Command::SetCurrentAsZero),
+        any::<u8>().prop_map(Command::SetZeroSpeed),
+        Just(Command::GoToZero),
+        direction().prop_map(Command::SetZeroDirection),
+        any::<u16>().prop_map(Command::SetPositionKp),
+        any::<u16>().prop_map(Command::SetPositionKi),
+        any::<u16>().prop_map(Command::SetPositionKd),
+        (0..=MAX_ACCELERATION * 2).prop_map(Command::SetAcceleration),
+        (0..=MAX_TORQUE_LIMIT)
+            .prop_map(|limit| Command::SetMaxTorque(TorqueLimit::new(limit).unwrap())),
+        Just(Command::ReadShaftStatus),
+        Just(Command::ReadEncoderValue),
+        Just(Command::ReadPulseCount),
+        Just(Command::ReadMotorShaftAngle),
+        Just(Command::ReadEnPinStatus),
+        Just(Command::ReadMotorShaftAngleError),
+        Just(Command::ReadReleaseStatus),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn encoded_frames_are_addressed_and_checksummed(
+        address in MIN_ADDRESS..=MAX_ADDRESS,
+        command in command(),
+    ) {
+        let driver = Driver::try_with_address(address).unwrap();
+        match driver.encode(&command) {
+            Ok(frame) => {
+                let (&sum, body) = frame.split_last().unwrap();
+                prop_assert_eq!(body[0], address);
+                prop_assert!(body.len() >= 2);
+                prop_assert_eq!(sum, checksum(body));
+
+                let mut out = [0u8; 16];
+                let len = driver.encode_into(&command, &mut out).unwrap();
+                prop_assert_eq!(&out[..len], frame.as_slice());
+            }
+            Err(error) => prop_assert_eq!(error, Error::InvalidValue),
+        }
+    }
+
+    #[test]
+    fn parsers_never_panic(data in proptest::collection::vec(any::<u8>(), 0..32)) {
+        let _ = parse_encoder_response(&data);
+        let _ = parse_motor_shaft_angle_response(&data);
+        let _ = parse_motor_shaft_angle_error(&data);
+        let _ = parse_pulse_count_response(&data);
+        let _ = parse_en_pin_status_response(&data);
+        let _ = parse_shaft_status_response(&data);
+        let _ = parse_success_response(&data);
+        let _ = strip_leading_garbage(&data);
+    }
+
+    #[test]
+    fn encoder_reply_roundtrip(
+        address in MIN_ADDRESS..=MAX_ADDRESS,
+        carry: i32,
+        value: u16,
+        noise in proptest::collection::vec(0u8..MIN_ADDRESS, 0..8),
+    ) {
+        let mut frame = noise;
+        frame.push(address);
+        frame.extend_from_slice(&carry.to_be_bytes());
+        frame.extend_from_slice(&value.to_be_bytes());
+        frame.push(checksum(&frame[frame.len() - 7..]));
+
+        let parsed = parse_encoder_response(&frame).unwrap();
+        prop_assert_eq!((parsed.carry, parsed.value), (carry, value));
+
+        let last = frame.len() - 1;
+        frame[last] = frame[last].wrapping_add(1);
+        prop_assert!(parse_encoder_response(&frame).is_err());
+    }
+
+    #[test]
+    fn pulse_count_reply_roundtrip(address in MIN_ADDRESS..=MAX_ADDRESS, pulses: i32) {
+        let mut frame = vec![address];
+        frame.extend_from_slice(&pulses.to_be_bytes());
+        frame.push(checksum(&frame));
+        prop_assert_eq!(parse_pulse_count_response(&frame), Ok(PulseCount(pulses)));
+
+        frame[5] = frame[5].wrapping_add(1);
+        prop_assert!(parse_pulse_count_response(&frame).is_err());
+    }
+}
+
+#[cfg(feature = "servo42d")]
+mod servo42d {
+    use super::{checksum, command};
+    use mks_servo42_rs::servo42d::{parse_reply, Driver};
+    use mks_servo42_rs::{CommandPolicy, Error};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn encoded_frames_are_addressed_and_checksummed(address: u8, command in command()) {
+            let driver: Driver =
+                Driver::new(address).with_policy(CommandPolicy::SAFE.allow_destructive());
+            match driver.encode(&command) {
+                Ok(frame) => {
+                    let (&sum, body) = frame.split_last().unwrap();
+                    prop_assert_eq!(&body[..2], &[0xFA, address]);
+                    prop_assert_eq!(sum, checksum(body));
+                }
+                Err(error) => {
+                    prop_assert!(matches!(error, Error::InvalidValue | Error::Unsupported));
+                }
+            }
+        }
+
+        #[test]
+        fn reply_roundtrip(
+            address: u8,
+            code: u8,
+            data in proptest::collection::vec(any::<u8>(), 0..8),
+            noise in proptest::collection::vec(any::<u8>(), 0..8),
+        ) {
+            let mut frame = vec![0xFB, address, code];
+            frame.extend_from_slice(&data);
+            frame.push(checksum(&frame));
+            let mut input = noise;
+            input.retain(|&b| b != 0xFB);
+            input.extend_from_slice(&frame);
+
+            let reply = parse_reply(&input, code, data.len()).unwrap();
+            prop_assert_eq!((reply.address, reply.code, reply.data), (address, code, &data[..]));
+
+            let last = input.len() - 1;
+            input[last] = input[last].wrapping_add(1);
+            prop_assert!(parse_reply(&input, code, data.len()).is_err());
+        }
+    }
+
+    #[cfg(feature = "can")]
+    proptest! {
+        #[test]
+        fn can_frames_roundtrip(
+            address: u8,
+            code: u8,
+            data in proptest::collection::vec(any::<u8>(), 0..6),
+        ) {
+            use mks_servo42_rs::servo42d::can::{reply_to_serial, CanFrame};
+
+            let frame = CanFrame::new(u16::from(address), code, &data).unwrap();
+            let mut serial = [0u8; 16];
+            let len = reply_to_serial(frame.id(), frame.data(), &mut serial).unwrap();
+            let reply = parse_reply(&serial[..len], code, data.len()).unwrap();
+            prop_assert_eq!((reply.address, reply.data), (address, &data[..]));
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Test utilities for MKS SERVO42 E2E tests
 
-use mks_servo42_rs::Driver;
+use mks_servo42_rs::testing::StopTarget;
+use mks_servo42_rs::{BaudRate, Driver};
 use serial::{SerialPort, SerialPortSettings};
 use std::env;
 use std::io::{Read, Write};
@@ -22,7 +23,7 @@ lazy_static! {
 }
 
 /// Default baud rate for tests (matches examples/base.rs)
-pub const DEFAULT_BAUD_RATE: serial::BaudRate = serial::Baud38400;
+pub const DEFAULT_BAUD_RATE: BaudRate = BaudRate::Baud38400;
 
 /// Default timeout for serial operations
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(500);
@@ -99,7 +100,9 @@ impl TestSerialPort {
         let mut port = serial::open(&port_path)?;
 
         port.reconfigure(&|settings: &mut dyn SerialPortSettings| {
-            settings.set_baud_rate(DEFAULT_BAUD_RATE)?;
+            settings.set_baud_rate(serial::BaudRate::from_speed(
+                DEFAULT_BAUD_RATE.bps() as usize
+            ))?;
             settings.set_char_size(serial::Bits8);
             settings.set_parity(serial::ParityNone);
             settings.set_stop_bits(serial::Stop1);
@@ -145,7 +148,8 @@ impl TestSerialPort {
     }
 
     /// Send command and log it
-    pub fn send_command(&mut self, command: &[u8]) -> TestResult<()> {
+    pub fn send_command(&mut self, command: impl AsRef<[u8]>) -> TestResult<()> {
+        let command = command.as_ref();
         // Drain any pending bytes before sending new command
         self.clear_input_buffer()?;
         println!("TX: {:02x?}", command);
@@ -198,7 +202,7 @@ impl TestSerialPort {
     }
 
     /// Send command and read response with pause
-    pub fn send_and_read(&mut self, command: &[u8]) -> TestResult<Vec<u8>> {
+    pub fn send_and_read(&mut self, command: impl AsRef<[u8]>) -> TestResult<Vec<u8>> {
         // Quick drain of any stale RX data before sending
         self.port.set_timeout(Duration::from_millis(20))?;
         let mut drain_buf = [0u8; 64];
@@ -216,7 +220,7 @@ impl TestSerialPort {
     }
 
     /// Send command, pause, and discard response
-    pub fn send_only(&mut self, command: &[u8]) -> TestResult<()> {
+    pub fn send_only(&mut self, command: impl AsRef<[u8]>) -> TestResult<()> {
         self.send_command(command)?;
         thread::sleep(SHORT_PAUSE);
         let _ = self.read_response();
@@ -247,11 +251,21 @@ impl TestContext {
     }
 }
 
+impl StopTarget for TestContext {
+    fn driver(&self) -> &Driver {
+        &self.driver
+    }
+
+    fn send(&mut self, frame: &[u8]) {
+        let _ = self.serial.send_only(frame);
+    }
+}
+
 /// Helper to parse encoder response
 #[allow(dead_code)]
 pub fn parse_encoder_response(data: &[u8]) -> TestResult<f32> {
     match mks_servo42_rs::parse_encoder_response(data) {
-        Ok(encoder_value) => Ok(encoder_value.to_degrees()),
+        Ok(encoder_value) => Ok(encoder_value.to_degrees().into()),
         Err(e) => Err(TestError::Protocol(format!(
             "Parse error: {:?}",
             e.as_str()
@@ -263,7 +277,7 @@ pub fn parse_encoder_response(data: &[u8]) -> TestResult<f32> {
 #[allow(dead_code)]
 pub fn parse_motor_shaft_angle_response(data: &[u8]) -> TestResult<f32> {
     match mks_servo42_rs::parse_motor_shaft_angle_response(data) {
-        Ok(angle) => Ok(angle.to_degrees()),
+        Ok(angle) => Ok(angle.to_degrees().into()),
         Err(e) => Err(TestError::Protocol(format!(
             "Parse error: {:?}",
             e.as_str()
@@ -275,7 +289,7 @@ pub fn parse_motor_shaft_angle_response(data: &[u8]) -> TestResult<f32> {
 #[allow(dead_code)]
 pub fn parse_motor_shaft_angle_error_response(data: &[u8]) -> TestResult<f32> {
     match mks_servo42_rs::parse_motor_shaft_angle_error(data) {
-        Ok(error) => Ok(error.to_degrees()),
+        Ok(error) => Ok(error.to_degrees().into()),
         Err(e) => Err(TestError::Protocol(format!(
             "Parse error: {:?}",
             e.as_str()
@@ -307,10 +321,5 @@ pub fn parse_shaft_status_response(data: &[u8]) -> TestResult<mks_servo42_rs::Sh
 /// Check if response indicates success
 #[allow(dead_code)]
 pub fn check_success_response(data: &[u8]) -> TestResult<bool> {
-    if data.len() >= 3 {
-        // Response format: [address, 0x01 (success), checksum]
-        Ok(data[1] == 0x01)
-    } else {
-        Err(TestError::from("Response too short"))
-    }
+    Ok(mks_servo42_rs::parse_ack(data)?.is_success())
 }